@@ -0,0 +1,45 @@
+use counter::Counter;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::Rng;
+
+fn sorted_pairs(len: usize) -> Vec<(u64, u64)> {
+    let mut rng = rand::thread_rng();
+    // Pre-aggregated dumps have distinct keys; spread them out so they don't hash in order.
+    (0..len as u64)
+        .map(|item| (item * 0x9e37_79b9, rng.gen_range(1..1000)))
+        .collect()
+}
+
+fn load_pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("load_pairs");
+    for len in [1 << 10, 1 << 16, 1 << 20] {
+        let input = sorted_pairs(len);
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::new("collect", len), &input, |b, input| {
+            b.iter(|| {
+                black_box(input)
+                    .iter()
+                    .copied()
+                    .collect::<Counter<u64, u64>>()
+            });
+        });
+        group.bench_with_input(BenchmarkId::new("from_vec", len), &input, |b, input| {
+            b.iter(|| Counter::<u64, u64>::from(black_box(input).clone()));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("from_sorted_pairs_unchecked", len),
+            &input,
+            |b, input| {
+                b.iter(|| {
+                    Counter::<u64, u64>::from_sorted_pairs_unchecked(
+                        black_box(input).iter().copied(),
+                    )
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, load_pairs);
+criterion_main!(benches);
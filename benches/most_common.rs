@@ -0,0 +1,50 @@
+use counter::Counter;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::Rng;
+
+/// A counter of `len` distinct items, each counted between one and `max_count` times, as after
+/// de-duplicating a large input.
+fn counter(len: u64, max_count: usize) -> Counter<u64> {
+    let mut rng = rand::thread_rng();
+    (0..len)
+        .map(|_| (rng.gen(), rng.gen_range(1..=max_count)))
+        .collect()
+}
+
+/// Sorts the pairs into the order of `most_common_ordered` by comparing counts, then items.
+fn comparison_sort(counter: &Counter<u64>) -> Vec<(u64, usize)> {
+    let mut pairs = counter.iter().collect::<Vec<_>>();
+    pairs.sort_unstable_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    pairs
+        .into_iter()
+        .map(|(&item, &count)| (item, count))
+        .collect()
+}
+
+/// Compares `most_common_ordered`, which groups the items by count when there are only a few
+/// distinct counts, with a comparison sort into the same order.
+fn rank_small_counts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("rank_small_counts");
+    for len in [1 << 10, 1 << 12, 1 << 16, 1 << 20] {
+        let counter = counter(len, 4);
+        group.throughput(Throughput::Elements(len));
+        group.bench_with_input(
+            BenchmarkId::new("most_common_ordered", len),
+            &counter,
+            |b, counter| {
+                b.iter(|| black_box(counter).most_common_ordered());
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("comparison_sort", len),
+            &counter,
+            |b, counter| {
+                b.iter(|| comparison_sort(black_box(counter)));
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, rank_small_counts);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+use counter::text::MultiPatternCounter;
+use counter::Counter;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::Rng;
+
+fn words(len: usize) -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..len)
+        .map(|_| {
+            (0..rng.gen_range(3..9))
+                .map(|_| char::from(rng.gen_range(b'a'..=b'z')))
+                .collect()
+        })
+        .collect()
+}
+
+fn count_keywords(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_keywords");
+    let text = words(1 << 17).join(" ");
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    for keywords in [8, 64, 512] {
+        let patterns = words(keywords);
+        group.bench_with_input(
+            BenchmarkId::new("scan_per_pattern", keywords),
+            &patterns,
+            |b, patterns| {
+                b.iter(|| {
+                    let text = black_box(&text);
+                    patterns
+                        .iter()
+                        .map(|pattern| (pattern.clone(), text.matches(pattern.as_str()).count()))
+                        .filter(|&(_, count)| count > 0)
+                        .collect::<Counter<String>>()
+                });
+            },
+        );
+        let counter = MultiPatternCounter::new(patterns).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("multi_pattern", keywords),
+            &counter,
+            |b, counter| {
+                b.iter(|| counter.count(black_box(&text)));
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, count_keywords);
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+use counter::{Counter, DenseCounter};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::Rng;
+
+fn bytes(len: usize) -> Vec<u8> {
+    let mut rng = rand::thread_rng();
+    // A skewed distribution with long runs, closer to real files than uniform noise.
+    (0..len)
+        .map(|_| rng.gen_range(0..=u8::MAX) & rng.gen_range(0..=u8::MAX))
+        .collect()
+}
+
+fn count_bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_bytes");
+    for len in [1 << 10, 1 << 16, 1 << 20] {
+        let input = bytes(len);
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("collect", len), &input, |b, input| {
+            b.iter(|| black_box(input).iter().copied().collect::<Counter<u8>>());
+        });
+        group.bench_with_input(BenchmarkId::new("from_slice", len), &input, |b, input| {
+            b.iter(|| Counter::<u8>::from_slice(black_box(input)));
+        });
+        group.bench_with_input(BenchmarkId::new("dense", len), &input, |b, input| {
+            b.iter(|| {
+                black_box(input)
+                    .iter()
+                    .copied()
+                    .collect::<DenseCounter<u8>>()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn count_u16s(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_u16s");
+    let len = 1 << 20;
+    let mut rng = rand::thread_rng();
+    let input: Vec<u16> = (0..len).map(|_| rng.gen_range(0..4096)).collect();
+    group.throughput(Throughput::Elements(len as u64));
+    group.bench_function("collect", |b| {
+        b.iter(|| black_box(&input).iter().copied().collect::<Counter<u16>>());
+    });
+    group.bench_function("from_slice", |b| {
+        b.iter(|| Counter::<u16>::from_slice(black_box(&input)));
+    });
+    group.finish();
+}
+
+fn count_chars(c: &mut Criterion) {
+    let mut group = c.benchmark_group("count_chars");
+    let mut rng = rand::thread_rng();
+    // Mostly ASCII, with the occasional accented letter.
+    let input: String = (0..1 << 20)
+        .map(|_| match rng.gen_range(0..100) {
+            0 => 'é',
+            n => char::from(b' ' + n % 64),
+        })
+        .collect();
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("collect", |b| {
+        b.iter(|| black_box(&input).chars().collect::<Counter<char>>());
+    });
+    group.bench_function("from_char_frequencies", |b| {
+        b.iter(|| Counter::<char>::from_char_frequencies(black_box(&input)));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, count_bytes, count_u16s, count_chars);
+criterion_main!(benches);
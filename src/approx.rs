@@ -0,0 +1,264 @@
+//! Approximate counting of unbounded streams in bounded memory.
+//!
+//! A [`HeavyHitters`] counter monitors at most a fixed number of distinct items, using the
+//! Space-Saving algorithm of Metwally, Agrawal and El Abbadi. Its estimates are never lower
+//! than the true counts, and any item which makes up more than `1 / capacity` of the stream
+//! is guaranteed to be monitored.
+//!
+//! ```rust
+//! # use counter::approx::HeavyHitters;
+//! let mut hitters = HeavyHitters::<char>::new(3);
+//! hitters.update("abacabadabacaba".chars());
+//!
+//! assert_eq!(hitters.len(), 3);
+//! assert_eq!(*hitters.estimate(&'a'), 8);
+//! assert_eq!(hitters.k_most_common_ordered(2)[0], ('a', 8));
+//! ```
+
+use crate::collections::HashMap;
+use crate::{k_most_common_ordered, Counter};
+
+use crate::num::{One, Zero};
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::ops::{AddAssign, Sub};
+
+/// The estimated count of a monitored item, and by how much it may overestimate the true count.
+#[derive(Clone, Debug)]
+struct Estimate<N> {
+    count: N,
+    error: N,
+}
+
+/// A counter of the most frequent items of a stream, which monitors at most `capacity`
+/// distinct items.
+///
+/// When an item which is not monitored arrives and the counter is full, the monitored item
+/// with the lowest count is evicted, and the new item inherits its count, which is recorded as
+/// the possible error of the new item's estimate. For an item which is monitored, with true
+/// count *c*, estimate *ĉ* and error *ε*:
+///
+/// *ĉ* - *ε* <= *c* <= *ĉ*
+///
+/// and *ε* never exceeds the total count of the stream divided by the capacity.
+///
+/// # Time complexity
+///
+/// Updating a monitored item, or inserting while the counter has room, takes *O*(1) time.
+/// Evicting an item takes *O*(*capacity*) time to find the least common.
+#[derive(Clone, Debug)]
+pub struct HeavyHitters<T, N = usize> {
+    estimates: HashMap<T, Estimate<N>>,
+    capacity: usize,
+    total: N,
+    zero: N,
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `HeavyHitters` which monitors at most `capacity` distinct items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a HeavyHitters counter needs a capacity of at least 1"
+        );
+        HeavyHitters {
+            estimates: HashMap::with_capacity(capacity),
+            capacity,
+            total: N::zero(),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Returns the maximum number of distinct items monitored.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of distinct items currently monitored.
+    pub fn len(&self) -> usize {
+        self.estimates.len()
+    }
+
+    /// Returns `true` if no item has been counted.
+    pub fn is_empty(&self) -> bool {
+        self.estimates.is_empty()
+    }
+
+    /// Returns the total count of the stream, including the counts of evicted items.
+    pub fn total(&self) -> &N {
+        &self.total
+    }
+
+    /// Returns the estimated count of `item`, which is never lower than its true count.
+    ///
+    /// Items which are not monitored have an estimate of zero; their true count is no higher
+    /// than the lowest estimate of a monitored item.
+    pub fn estimate<Q>(&self, item: &Q) -> &N
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.estimates
+            .get(item)
+            .map_or(&self.zero, |estimate| &estimate.count)
+    }
+
+    /// Returns how much the estimate of `item` may exceed its true count, or `None` if it is
+    /// not monitored.
+    pub fn error<Q>(&self, item: &Q) -> Option<&N>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.estimates.get(item).map(|estimate| &estimate.error)
+    }
+
+    /// Returns an iterator over the monitored items and their estimated counts, in arbitrary
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, &N)> {
+        self.estimates
+            .iter()
+            .map(|(item, estimate)| (item, &estimate.count))
+    }
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + Ord + AddAssign,
+{
+    /// Count `count` occurrences of `item`.
+    pub fn add(&mut self, item: T, count: N) {
+        self.total += count.clone();
+        if let Some(estimate) = self.estimates.get_mut(&item) {
+            estimate.count += count;
+            return;
+        }
+        if self.estimates.len() < self.capacity {
+            let error = self.zero.clone();
+            self.estimates.insert(item, Estimate { count, error });
+            return;
+        }
+
+        // Evict an item with the lowest count; the new item inherits that count as its error.
+        let Some(min_count) = self.estimates.values().map(|e| &e.count).min().cloned() else {
+            return;
+        };
+        let mut evicted = false;
+        self.estimates.retain(|_, estimate| {
+            let evict = !evicted && estimate.count == min_count;
+            evicted |= evict;
+            !evict
+        });
+        let mut estimate = Estimate {
+            count: min_count.clone(),
+            error: min_count,
+        };
+        estimate.count += count;
+        self.estimates.insert(item, estimate);
+    }
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + Ord + AddAssign + One,
+{
+    /// Count the elements of the given iterable.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.add(item, N::one());
+        }
+    }
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: Clone + Ord,
+{
+    /// Returns the `k` monitored items with the highest estimated counts, in decreasing order
+    /// of their estimates, breaking ties by the natural ordering of the items.
+    ///
+    /// See [`Counter::k_most_common_ordered`].
+    pub fn k_most_common_ordered(&self, k: usize) -> Vec<(T, N)> {
+        if k == 0 {
+            return Vec::new();
+        }
+        k_most_common_ordered(self.iter(), k)
+    }
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + Ord + Sub<Output = N>,
+{
+    /// Returns the monitored items whose true count is guaranteed to be at least `threshold`:
+    /// those whose estimate minus error is at least `threshold`.
+    pub fn guaranteed_at_least(&self, threshold: &N) -> impl Iterator<Item = (&T, &N)> + '_ {
+        let threshold = threshold.clone();
+        self.estimates
+            .iter()
+            .filter(move |(_, estimate)| {
+                estimate.count.clone() - estimate.error.clone() >= threshold
+            })
+            .map(|(item, estimate)| (item, &estimate.count))
+    }
+}
+
+impl<T, N> HeavyHitters<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + Ord + AddAssign + Zero,
+{
+    /// Count the items of an exact counter into a new `HeavyHitters` of the given capacity.
+    ///
+    /// If the counter has no more than `capacity` items, every estimate is exact.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn from_counter<S>(counter: Counter<T, N, S>, capacity: usize) -> Self {
+        let mut hitters = HeavyHitters::new(capacity);
+        for (item, count) in counter {
+            hitters.add(item, count);
+        }
+        hitters
+    }
+}
+
+impl<T, N> From<HeavyHitters<T, N>> for Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Convert the monitored items and their estimated counts into an exact [`Counter`].
+    fn from(hitters: HeavyHitters<T, N>) -> Self {
+        Counter {
+            map: hitters
+                .estimates
+                .into_iter()
+                .map(|(item, estimate)| (item, estimate.count))
+                .collect(),
+            zero: hitters.zero,
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// A counter over a set of keys fixed up front, whose counts can be incremented through a
+/// shared reference.
+///
+/// Each count is an [`AtomicU64`], so once the keys are registered, any number of threads can
+/// increment them at once without locking. Registering a new key needs `&mut self`, which
+/// suits the read-mostly pattern of metrics collection: the set of metrics is known when the
+/// collector starts, and the hot path only increments them.
+///
+/// ```rust
+/// # use counter::{AtomicCounter, Counter};
+/// let counter = AtomicCounter::with_keys(["hit", "miss"]);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             counter.increment("hit");
+///             counter.add("miss", 2);
+///         });
+///     }
+/// });
+///
+/// assert!(!counter.increment("error"));
+/// assert_eq!(counter.freeze(), Counter::from([("hit", 4), ("miss", 8)]));
+/// ```
+#[derive(Debug)]
+pub struct AtomicCounter<T: Hash + Eq> {
+    map: HashMap<T, AtomicU64>,
+}
+
+impl<T> AtomicCounter<T>
+where
+    T: Hash + Eq,
+{
+    /// Create a new `AtomicCounter` with no keys.
+    pub fn new() -> Self {
+        AtomicCounter {
+            map: HashMap::default(),
+        }
+    }
+
+    /// Create a new `AtomicCounter` with the given keys, each with a count of zero.
+    pub fn with_keys<I>(keys: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter = Self::new();
+        for key in keys {
+            counter.insert(key);
+        }
+        counter
+    }
+
+    /// Register `key` with a count of zero, so that it can be incremented through a shared
+    /// reference. Returns `false` if the key was already registered, leaving its count as it
+    /// was.
+    pub fn insert(&mut self, key: T) -> bool {
+        let mut inserted = false;
+        self.map.entry(key).or_insert_with(|| {
+            inserted = true;
+            AtomicU64::new(0)
+        });
+        inserted
+    }
+
+    /// Returns the number of registered keys.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if no keys are registered.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if `key` is registered.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Add one to the count of `key`. Returns `false`, and counts nothing, if the key is not
+    /// registered.
+    pub fn increment<Q>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.add(key, 1)
+    }
+
+    /// Add `count` to the count of `key`. Returns `false`, and counts nothing, if the key is not
+    /// registered.
+    ///
+    /// Counts wrap around on overflow, as [`AtomicU64::fetch_add`] does.
+    pub fn add<Q>(&self, key: &Q, count: u64) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(atomic) => {
+                atomic.fetch_add(count, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the current count of `key`, or `None` if it is not registered.
+    pub fn get<Q>(&self, key: &Q) -> Option<u64>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map
+            .get(key)
+            .map(|atomic| atomic.load(Ordering::Relaxed))
+    }
+
+    /// Consumes the `AtomicCounter` and returns a [`Counter`] of the counts.
+    ///
+    /// Keys which were never incremented are left out.
+    pub fn freeze(self) -> Counter<T, u64> {
+        let mut counter = Counter::new();
+        counter.map.extend(
+            self.map
+                .into_iter()
+                .map(|(key, atomic)| (key, atomic.into_inner()))
+                .filter(|&(_, count)| count > 0),
+        );
+        counter
+    }
+}
+
+impl<T> AtomicCounter<T>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Returns a [`Counter`] of the current counts, without consuming the `AtomicCounter`.
+    ///
+    /// The counts are read one at a time, so increments made by other threads while the
+    /// snapshot is taken may or may not be included. Keys which have not been incremented are
+    /// left out.
+    pub fn snapshot(&self) -> Counter<T, u64> {
+        let mut counter = Counter::new();
+        counter.map.extend(
+            self.map
+                .iter()
+                .map(|(key, atomic)| (key.clone(), atomic.load(Ordering::Relaxed)))
+                .filter(|&(_, count)| count > 0),
+        );
+        counter
+    }
+}
+
+impl<T> Default for AtomicCounter<T>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,126 @@
+use crate::Counter;
+
+use crate::num::{CheckedAdd, One};
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+
+/// An item whose count would have overflowed in [`Counter::try_update`] or
+/// [`Counter::try_extend`].
+///
+/// It converts into [`Error::Overflow`](crate::Error::Overflow), so `?` can pass it on as the
+/// crate-wide error once the item has been reported.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OverflowError<T, N> {
+    /// The item whose count overflowed.
+    pub item: T,
+    /// The count which could not be added to it; the count of the item was left unchanged.
+    pub added: N,
+}
+
+impl<T, N> fmt::Display for OverflowError<T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "adding {:?} to the count of item {:?} would overflow",
+            self.added, self.item
+        )
+    }
+}
+
+impl<T, N> core::error::Error for OverflowError<T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: CheckedAdd,
+    S: BuildHasher,
+{
+    /// Add the counts of the elements from the given iterable to this counter, as
+    /// [`update`](Counter::update) does, but with checked arithmetic.
+    ///
+    /// Plain addition silently wraps around in release builds, which makes an overflowing
+    /// count hard to notice, let alone trace back to its item. This stops at the first item
+    /// whose count would overflow, and names it in the error.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<&str, u8>::from([("spam", 254)]);
+    /// counter.try_update(["spam", "eggs"]).unwrap();
+    ///
+    /// let err = counter.try_update(["ham", "spam"]).unwrap_err();
+    /// assert_eq!(err.item, "spam");
+    /// assert_eq!(err.to_string(), r#"adding 1 to the count of item "spam" would overflow"#);
+    /// assert_eq!(counter[&"spam"], 255);
+    /// assert_eq!(counter[&"ham"], 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OverflowError`] naming the first item whose count would not fit in `N`.
+    /// The items before it have been counted, and it and the items after it have not.
+    pub fn try_update<I>(&mut self, iterable: I) -> Result<(), OverflowError<T, N>>
+    where
+        I: IntoIterator<Item = T>,
+        N: One,
+    {
+        for item in iterable {
+            self.try_add_count(item, N::one())?;
+        }
+        Ok(())
+    }
+
+    /// Extend this counter with `(item, count)` tuples, as [`Extend`] does, but with checked
+    /// arithmetic.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Error};
+    /// fn merge(counter: &mut Counter<char, i8>, pairs: &[(char, i8)]) -> Result<(), Error> {
+    ///     counter.try_extend(pairs.iter().copied())?;
+    ///     Ok(())
+    /// }
+    ///
+    /// let mut counter = Counter::new();
+    /// merge(&mut counter, &[('a', 100), ('b', -100)]).unwrap();
+    /// assert!(matches!(merge(&mut counter, &[('b', -100)]), Err(Error::Overflow)));
+    /// assert_eq!(counter[&'b'], -100);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`OverflowError`] naming the first item whose count would not fit in `N`.
+    /// The pairs before it have been added, and it and the pairs after it have not.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), OverflowError<T, N>>
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        for (item, count) in iter {
+            self.try_add_count(item, count)?;
+        }
+        Ok(())
+    }
+
+    fn try_add_count(&mut self, item: T, added: N) -> Result<(), OverflowError<T, N>> {
+        // Looking the item up before inserting it costs a second hash for new items, but the
+        // `Entry` API can't hand the item back for the error.
+        match self.map.get_mut(&item) {
+            Some(count) => match count.checked_add(&added) {
+                Some(sum) => *count = sum,
+                None => return Err(OverflowError { item, added }),
+            },
+            None => {
+                self.map.insert(item, added);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+//! The hash map backing a [`Counter`](crate::Counter): the standard library's with the `std`
+//! feature, and `hashbrown`'s without it.
+
+#[cfg(feature = "std")]
+pub(crate) use std::collections::hash_map::RandomState as DefaultHashBuilder;
+#[cfg(feature = "std")]
+pub(crate) use std::collections::{hash_map, HashMap};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::{hash_map, DefaultHashBuilder, HashMap};
+
+/// The entry types of a map with hasher `S`, for the counter's own entry types to wrap. Only
+/// `hashbrown`'s entries are generic over the hasher.
+pub(crate) trait MapEntries<'a, K: 'a, V: 'a> {
+    type Entry;
+    type OccupiedEntry;
+}
+
+#[cfg(feature = "std")]
+impl<'a, K: 'a, V: 'a, S> MapEntries<'a, K, V> for S {
+    type Entry = hash_map::Entry<'a, K, V>;
+    type OccupiedEntry = hash_map::OccupiedEntry<'a, K, V>;
+}
+
+#[cfg(not(feature = "std"))]
+impl<'a, K: 'a, V: 'a, S: 'a> MapEntries<'a, K, V> for S {
+    type Entry = hash_map::Entry<'a, K, V, S>;
+    type OccupiedEntry = hash_map::OccupiedEntry<'a, K, V, S>;
+}
+
+pub(crate) type Entry<'a, K, V, S> = <S as MapEntries<'a, K, V>>::Entry;
+pub(crate) type OccupiedEntry<'a, K, V, S> = <S as MapEntries<'a, K, V>>::OccupiedEntry;
+
+#[cfg(not(any(feature = "std", feature = "hashbrown")))]
+compile_error!("counter needs either the `std` feature or, for `no_std`, the `hashbrown` feature");
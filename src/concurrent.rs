@@ -0,0 +1,211 @@
+use crate::collections::DefaultHashBuilder;
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use std::hash::{BuildHasher, Hash};
+use std::ops::AddAssign;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+
+/// A counter which many threads can update at once.
+///
+/// Items are spread by their hash over a number of shards, each a [`Counter`] behind its own
+/// lock, so threads counting different items rarely wait for each other. This scales much
+/// better than sharing a single `Mutex<Counter>` when many threads count at high rates.
+///
+/// Each call to [`update`](ConcurrentCounter::update) or [`add`](ConcurrentCounter::add) takes
+/// a lock per item. When a thread has many items to count, it is usually faster to count them
+/// into a local [`Counter`] first and [`merge`](ConcurrentCounter::merge) that, which takes
+/// each shard's lock only once.
+///
+/// ```rust
+/// # use counter::{ConcurrentCounter, Counter};
+/// let counter = ConcurrentCounter::<char>::new();
+///
+/// std::thread::scope(|scope| {
+///     for text in ["abc", "abb", "aaa"] {
+///         let counter = &counter;
+///         scope.spawn(move || counter.update(text.chars()));
+///     }
+/// });
+///
+/// assert_eq!(counter.get(&'a'), 5);
+/// assert_eq!(counter.into_counter(), "aaaaabbbc".chars().collect::<Counter<_>>());
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentCounter<T: Hash + Eq, N = usize> {
+    shards: Box<[Mutex<Counter<T, N>>]>,
+    hasher: DefaultHashBuilder,
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `ConcurrentCounter`, with a number of shards suited to the number of
+    /// threads the machine can run in parallel.
+    pub fn new() -> Self {
+        let parallelism = thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_shards(parallelism * 4)
+    }
+
+    /// Create a new, empty `ConcurrentCounter` with the given number of shards.
+    ///
+    /// More shards make it less likely that two threads wait for the same lock, at the cost of
+    /// a little memory per shard. A single shard behaves like a `Mutex<Counter>`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is zero.
+    pub fn with_shards(shards: usize) -> Self {
+        assert!(shards > 0, "a concurrent counter needs at least one shard");
+        ConcurrentCounter {
+            shards: (0..shards).map(|_| Mutex::new(Counter::new())).collect(),
+            hasher: DefaultHashBuilder::default(),
+        }
+    }
+}
+
+impl<T, N> Default for ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Returns the number of shards.
+    pub fn shards(&self) -> usize {
+        self.shards.len()
+    }
+
+    fn shard_index(&self, item: &T) -> usize {
+        // Truncating the hash is fine: only its distribution over the shards matters.
+        (self.hasher.hash_one(item) as usize) % self.shards.len()
+    }
+
+    fn lock(&self, index: usize) -> MutexGuard<'_, Counter<T, N>> {
+        // Every count is valid between individual additions, so a panic while the lock was
+        // held can at worst have lost part of a merge.
+        self.shards[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Consumes the `ConcurrentCounter` and returns a [`Counter`] of everything counted.
+    pub fn into_counter(self) -> Counter<T, N> {
+        let mut shards = self
+            .shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner));
+        let mut counter = shards.next().expect("a concurrent counter has a shard");
+        for shard in shards {
+            // The shards hold disjoint items, so their counts never need adding together.
+            counter.map.extend(shard.map);
+        }
+        counter
+    }
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + Zero,
+{
+    /// Returns the count of `item`, or zero if it has not been counted.
+    pub fn get(&self, item: &T) -> N {
+        self.lock(self.shard_index(item))
+            .map
+            .get(item)
+            .cloned()
+            .unwrap_or_else(N::zero)
+    }
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: Clone + Zero,
+{
+    /// Returns a [`Counter`] of everything counted so far.
+    ///
+    /// The shards are copied one at a time, so counts added by other threads while the
+    /// snapshot is taken may or may not be included.
+    pub fn snapshot(&self) -> Counter<T, N> {
+        let mut counter = Counter::new();
+        for index in 0..self.shards.len() {
+            let shard = self.lock(index);
+            counter.map.extend(
+                shard
+                    .map
+                    .iter()
+                    .map(|(item, count)| (item.clone(), count.clone())),
+            );
+        }
+        counter
+    }
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Add `count` to the count of `item`.
+    pub fn add(&self, item: T, count: N) {
+        let mut shard = self.lock(self.shard_index(&item));
+        *shard.map.entry(item).or_insert_with(N::zero) += count;
+    }
+
+    /// Add the counts of another counter to this one, taking each shard's lock only once.
+    ///
+    /// ```rust
+    /// # use counter::{ConcurrentCounter, Counter};
+    /// let counter = ConcurrentCounter::<char>::with_shards(4);
+    /// counter.merge("aab".chars().collect::<Counter<_>>());
+    /// counter.merge("bc".chars().collect::<Counter<_>>());
+    /// assert_eq!(counter.get(&'b'), 2);
+    /// ```
+    pub fn merge<S>(&self, other: Counter<T, N, S>) {
+        let mut batches = (0..self.shards.len())
+            .map(|_| Vec::new())
+            .collect::<Vec<_>>();
+        for (item, count) in other.map {
+            batches[self.shard_index(&item)].push((item, count));
+        }
+        for (index, batch) in batches.into_iter().enumerate() {
+            if batch.is_empty() {
+                continue;
+            }
+            let mut shard = self.lock(index);
+            for (item, count) in batch {
+                *shard.map.entry(item).or_insert_with(N::zero) += count;
+            }
+        }
+    }
+}
+
+impl<T, N> ConcurrentCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+{
+    /// Add one to the count of each element of the given iterable.
+    pub fn update<I>(&self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.add(item, N::one());
+        }
+    }
+}
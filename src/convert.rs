@@ -0,0 +1,126 @@
+use crate::collections::hash_map::Entry;
+use crate::collections::HashMap;
+use crate::Counter;
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::ops::AddAssign;
+
+/// An item which could not be converted by [`Counter::try_convert_keys`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct KeyConversionError<T, E> {
+    /// The item which failed to convert.
+    pub item: T,
+    /// The error returned by its conversion.
+    pub error: E,
+}
+
+impl<T, E> fmt::Display for KeyConversionError<T, E>
+where
+    T: fmt::Debug,
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "item {:?} could not be converted: {}",
+            self.item, self.error
+        )
+    }
+}
+
+impl<T, E> core::error::Error for KeyConversionError<T, E>
+where
+    T: fmt::Debug,
+    E: core::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign,
+    S: BuildHasher + Default,
+{
+    /// Consumes this counter and returns a counter of its items converted with [`From`].
+    ///
+    /// If several items convert to the same key, their counts are added together.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = "the cat the hat".split_whitespace().collect::<Counter<&str>>();
+    /// let owned = words.convert_keys::<String>();
+    /// assert_eq!(owned[&"the".to_string()], 2);
+    ///
+    /// let small = Counter::<u8>::from([(1, 2), (2, 3)]);
+    /// let wide = small.convert_keys::<u64>();
+    /// assert_eq!(wide[&2], 3);
+    /// ```
+    pub fn convert_keys<U>(self) -> Counter<U, N, S>
+    where
+        U: From<T> + Hash + Eq,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), S::default());
+        for (item, count) in self.map {
+            merge(&mut map, U::from(item), count);
+        }
+        Counter {
+            map,
+            zero: self.zero,
+        }
+    }
+
+    /// Consumes this counter and returns a counter of its items converted with [`TryFrom`].
+    ///
+    /// If several items convert to the same key, their counts are added together.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let codes = Counter::<u32>::from([(0x61, 2), (0x62, 1)]);
+    /// let chars = codes.try_convert_keys::<char>().unwrap();
+    /// assert_eq!(chars[&'a'], 2);
+    ///
+    /// let codes = Counter::<u32>::from([(0x61, 2), (0xd800, 1)]);
+    /// let err = codes.try_convert_keys::<char>().unwrap_err();
+    /// assert_eq!(err.item, 0xd800);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`KeyConversionError`] holding the first item, in arbitrary order, which fails
+    /// to convert, and the error of its conversion.
+    pub fn try_convert_keys<U>(self) -> Result<Counter<U, N, S>, KeyConversionError<T, U::Error>>
+    where
+        T: Clone,
+        U: TryFrom<T> + Hash + Eq,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), S::default());
+        for (item, count) in self.map {
+            match U::try_from(item.clone()) {
+                Ok(key) => merge(&mut map, key, count),
+                Err(error) => return Err(KeyConversionError { item, error }),
+            }
+        }
+        Ok(Counter {
+            map,
+            zero: self.zero,
+        })
+    }
+}
+
+fn merge<U, N, S>(map: &mut HashMap<U, N, S>, key: U, count: N)
+where
+    U: Hash + Eq,
+    N: AddAssign,
+    S: BuildHasher,
+{
+    match map.entry(key) {
+        Entry::Occupied(mut entry) => *entry.get_mut() += count,
+        Entry::Vacant(entry) => {
+            entry.insert(count);
+        }
+    }
+}
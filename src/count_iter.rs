@@ -0,0 +1,215 @@
+use alloc::collections::BinaryHeap;
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+
+/// Adapters for iterators over borrowed `(item, count)` pairs, such as those returned by
+/// iterating over `&Counter` or by [`positive_iter`](crate::Counter::positive_iter).
+///
+/// They let a chain over the contents of a counter filter and order them as it goes, without
+/// collecting the pairs into a `Vec` first. Bring the trait into scope to use them.
+///
+/// ```rust
+/// # use counter::{CountIteratorExt, Counter};
+/// let counter = "abracadabra".chars().collect::<Counter<_>>();
+///
+/// let total = counter.iter().counts_copied().map(|(_, count)| count).sum::<usize>();
+/// assert_eq!(total, 11);
+///
+/// let repeated = counter.iter().min_count(2).by_count_desc().collect::<Vec<_>>();
+/// assert_eq!(repeated, [(&'a', &5), (&'b', &2), (&'r', &2)]);
+///
+/// // The order is produced lazily, so taking a prefix doesn't sort the rest.
+/// let top = counter.iter().by_count_desc().map_while(|(&item, &count)| {
+///     (count > 1).then_some(item)
+/// });
+/// assert_eq!(top.collect::<String>(), "abr");
+/// ```
+pub trait CountIteratorExt<'a, T, N>: Iterator<Item = (&'a T, &'a N)> + Sized
+where
+    T: 'a,
+    N: 'a,
+{
+    /// Copies each count, yielding `(&item, count)` pairs.
+    fn counts_copied(self) -> CountsCopied<Self>
+    where
+        N: Copy,
+    {
+        CountsCopied { pairs: self }
+    }
+
+    /// Skips the pairs whose count is less than `min`.
+    fn min_count(self, min: N) -> MinCount<Self, N>
+    where
+        N: PartialOrd,
+    {
+        MinCount { pairs: self, min }
+    }
+
+    /// Yields the pairs in decreasing order of their counts; pairs with equal counts are
+    /// yielded in increasing order of their items, as in
+    /// [`most_common_ordered`](crate::Counter::most_common_ordered).
+    ///
+    /// The pairs are gathered into a binary heap, which takes linear time, and each is then
+    /// taken from the heap in logarithmic time as it is needed.
+    fn by_count_desc(self) -> ByCountDesc<'a, T, N>
+    where
+        T: Ord,
+        N: Ord,
+    {
+        ByCountDesc {
+            heap: self.map(|(item, count)| ByCount { item, count }).collect(),
+        }
+    }
+}
+
+impl<'a, I, T, N> CountIteratorExt<'a, T, N> for I
+where
+    I: Iterator<Item = (&'a T, &'a N)>,
+    T: 'a,
+    N: 'a,
+{
+}
+
+/// An iterator copying the counts of borrowed `(item, count)` pairs.
+///
+/// This `struct` is created by [`CountIteratorExt::counts_copied`].
+#[derive(Clone, Debug)]
+pub struct CountsCopied<I> {
+    pairs: I,
+}
+
+impl<'a, I, T, N> Iterator for CountsCopied<I>
+where
+    I: Iterator<Item = (&'a T, &'a N)>,
+    T: 'a,
+    N: Copy + 'a,
+{
+    type Item = (&'a T, N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pairs.next().map(|(item, &count)| (item, count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pairs.size_hint()
+    }
+}
+
+impl<'a, I, T, N> ExactSizeIterator for CountsCopied<I>
+where
+    I: ExactSizeIterator<Item = (&'a T, &'a N)>,
+    T: 'a,
+    N: Copy + 'a,
+{
+}
+
+impl<'a, I, T, N> FusedIterator for CountsCopied<I>
+where
+    I: FusedIterator<Item = (&'a T, &'a N)>,
+    T: 'a,
+    N: Copy + 'a,
+{
+}
+
+/// An iterator skipping the borrowed `(item, count)` pairs whose count is below a minimum.
+///
+/// This `struct` is created by [`CountIteratorExt::min_count`].
+#[derive(Clone, Debug)]
+pub struct MinCount<I, N> {
+    pairs: I,
+    min: N,
+}
+
+impl<'a, I, T, N> Iterator for MinCount<I, N>
+where
+    I: Iterator<Item = (&'a T, &'a N)>,
+    T: 'a,
+    N: PartialOrd + 'a,
+{
+    type Item = (&'a T, &'a N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let min = &self.min;
+        self.pairs.find(|(_, count)| *count >= min)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pairs.size_hint().1)
+    }
+}
+
+impl<'a, I, T, N> FusedIterator for MinCount<I, N>
+where
+    I: FusedIterator<Item = (&'a T, &'a N)>,
+    T: 'a,
+    N: PartialOrd + 'a,
+{
+}
+
+/// An iterator over borrowed `(item, count)` pairs, most common first.
+///
+/// This `struct` is created by [`CountIteratorExt::by_count_desc`].
+#[derive(Clone, Debug)]
+pub struct ByCountDesc<'a, T, N> {
+    heap: BinaryHeap<ByCount<'a, T, N>>,
+}
+
+impl<'a, T, N> Iterator for ByCountDesc<'a, T, N>
+where
+    T: Ord,
+    N: Ord,
+{
+    type Item = (&'a T, &'a N);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop().map(|entry| (entry.item, entry.count))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.heap.len(), Some(self.heap.len()))
+    }
+}
+
+impl<T, N> ExactSizeIterator for ByCountDesc<'_, T, N>
+where
+    T: Ord,
+    N: Ord,
+{
+}
+
+impl<T, N> FusedIterator for ByCountDesc<'_, T, N>
+where
+    T: Ord,
+    N: Ord,
+{
+}
+
+/// A pair in the heap of a [`ByCountDesc`], greatest for the highest count and, among equal
+/// counts, the least item.
+#[derive(Clone, Debug)]
+struct ByCount<'a, T, N> {
+    item: &'a T,
+    count: &'a N,
+}
+
+impl<T: Ord, N: Ord> Ord for ByCount<'_, T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.count
+            .cmp(other.count)
+            .then_with(|| other.item.cmp(self.item))
+    }
+}
+
+impl<T: Ord, N: Ord> PartialOrd for ByCount<'_, T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, N: Ord> PartialEq for ByCount<'_, T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord, N: Ord> Eq for ByCount<'_, T, N> {}
@@ -0,0 +1,203 @@
+//! Reading and writing counters as CSV, or any other delimited format, with one `item,count`
+//! row per item.
+//!
+//! [`Counter::to_csv_writer`] writes the rows and [`Counter::from_csv_reader`] reads them back;
+//! a [`CsvFormat`] sets the delimiter, the order of the rows and whether there is a header row.
+//!
+//! ```rust
+//! # use counter::Counter;
+//! # use counter::csv::CsvFormat;
+//! # use counter::OrderBy;
+//! let counter = "abracadabra".chars().collect::<Counter<_>>();
+//!
+//! let mut csv = Vec::new();
+//! counter.to_csv_writer(&mut csv, &CsvFormat::new()).unwrap();
+//! assert_eq!(csv, b"item,count\na,5\nb,2\nr,2\nc,1\nd,1\n");
+//!
+//! let tsv_format = CsvFormat::new().delimiter(b'\t').order(OrderBy::KeyAsc).header(false);
+//! let mut tsv = Vec::new();
+//! counter.to_csv_writer(&mut tsv, &tsv_format).unwrap();
+//! assert_eq!(tsv, b"a\t5\nb\t2\nc\t1\nd\t1\nr\t2\n");
+//!
+//! let read = Counter::<char>::from_csv_reader(tsv.as_slice(), &tsv_format).unwrap();
+//! assert_eq!(read, counter);
+//! ```
+
+use crate::collections::HashMap;
+use crate::{Counter, OrderBy};
+
+use crate::num::Zero;
+
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::ops::AddAssign;
+use std::str::FromStr;
+
+/// The layout of the rows read and written by [`Counter::from_csv_reader`] and
+/// [`Counter::to_csv_writer`].
+///
+/// By default, fields are separated by commas, there is an `item,count` header row, and the
+/// most common items are written first.
+#[derive(Clone, Copy, Debug)]
+pub struct CsvFormat<'a, T, N> {
+    delimiter: u8,
+    header: bool,
+    order: OrderBy<'a, T, N>,
+}
+
+impl<T, N> Default for CsvFormat<'_, T, N> {
+    fn default() -> Self {
+        CsvFormat {
+            delimiter: b',',
+            header: true,
+            order: OrderBy::CountDesc,
+        }
+    }
+}
+
+impl<'a, T, N> CsvFormat<'a, T, N> {
+    /// Create the default `CsvFormat`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the field delimiter, such as `b'\t'` for TSV.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the first row is an `item,count` header rather than data.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the order in which rows are written. Reading accepts rows in any order.
+    pub fn order(mut self, order: OrderBy<'a, T, N>) -> Self {
+        self.order = order;
+        self
+    }
+}
+
+/// An error reading or writing a counter as CSV.
+#[derive(Debug)]
+pub enum CsvError {
+    /// The underlying reader or writer failed, or the input was not valid CSV.
+    Csv(csv::Error),
+    /// A row did not consist of an item and a count which could be parsed.
+    Row {
+        /// The line on which the row starts, counting from 1.
+        line: u64,
+        /// Why the row was rejected.
+        message: String,
+    },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(err) => err.fmt(f),
+            CsvError::Row { line, message } => write!(f, "invalid row on line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Csv(err) => Some(err),
+            CsvError::Row { .. } => None,
+        }
+    }
+}
+
+impl From<csv::Error> for CsvError {
+    fn from(err: csv::Error) -> Self {
+        CsvError::Csv(err)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone + Ord + fmt::Display,
+    N: Clone + Ord + fmt::Display,
+{
+    /// Writes one `item,count` row per item to `writer`, laid out according to `format`.
+    ///
+    /// Items and counts are written with their [`Display`](fmt::Display) implementations, and
+    /// quoted where needed. See the [`csv`](crate::csv) module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `writer` fails.
+    pub fn to_csv_writer<W: io::Write>(
+        &self,
+        writer: W,
+        format: &CsvFormat<'_, T, N>,
+    ) -> Result<(), CsvError> {
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(format.delimiter)
+            .from_writer(writer);
+        if format.header {
+            writer.write_record(["item", "count"])?;
+        }
+        for (item, count) in self.to_ordered_pairs(format.order, None) {
+            writer.write_record([item.to_string(), count.to_string()])?;
+        }
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq + FromStr,
+    T::Err: fmt::Display,
+    N: AddAssign + Zero + FromStr,
+    N::Err: fmt::Display,
+{
+    /// Reads a counter from `item,count` rows laid out according to `format`.
+    ///
+    /// Items and counts are parsed with their [`FromStr`] implementations. The counts of items
+    /// which appear on several rows are summed. See the [`csv`](crate::csv) module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from `reader` fails, or if a row does not have exactly two
+    /// fields, or its item or count cannot be parsed.
+    pub fn from_csv_reader<R: io::Read>(
+        reader: R,
+        format: &CsvFormat<'_, T, N>,
+    ) -> Result<Self, CsvError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(format.delimiter)
+            .has_headers(format.header)
+            .flexible(true)
+            .from_reader(reader);
+        let mut map = HashMap::default();
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, csv::Position::line);
+            let row_error = |message: String| CsvError::Row { line, message };
+            if record.len() != 2 {
+                return Err(row_error(format!(
+                    "expected 2 fields, found {}",
+                    record.len()
+                )));
+            }
+            let item = record[0]
+                .parse::<T>()
+                .map_err(|err| row_error(format!("invalid item {:?}: {err}", &record[0])))?;
+            let count = record[1]
+                .parse::<N>()
+                .map_err(|err| row_error(format!("invalid count {:?}: {err}", &record[1])))?;
+            *map.entry(item).or_insert_with(N::zero) += count;
+        }
+        Ok(Counter {
+            map,
+            zero: N::zero(),
+        })
+    }
+}
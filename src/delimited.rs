@@ -0,0 +1,135 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use std::fmt;
+use std::hash::Hash;
+use std::io;
+use std::ops::AddAssign;
+use std::str::FromStr;
+
+/// How [`Counter::from_delimited`] splits each line into columns.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Delimiter {
+    /// Columns are separated by runs of whitespace, and whitespace at the start and end of the
+    /// line is ignored. This suits the aligned output of tools such as `uniq -c` and `wc`, but
+    /// items cannot contain whitespace.
+    Whitespace,
+    /// Columns are separated by this character, and whitespace around each column is ignored.
+    Char(char),
+}
+
+/// An error reading a counter with [`Counter::from_delimited`].
+#[derive(Debug)]
+pub enum DelimitedError {
+    /// The underlying reader failed.
+    Io(io::Error),
+    /// A line did not hold an item and a count which could be parsed.
+    Line {
+        /// The number of the line, counting from 1.
+        line: u64,
+        /// Why the line was rejected.
+        message: String,
+    },
+}
+
+impl fmt::Display for DelimitedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DelimitedError::Io(err) => err.fmt(f),
+            DelimitedError::Line { line, message } => write!(f, "invalid line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for DelimitedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DelimitedError::Io(err) => Some(err),
+            DelimitedError::Line { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for DelimitedError {
+    fn from(err: io::Error) -> Self {
+        DelimitedError::Io(err)
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq + FromStr,
+    T::Err: fmt::Display,
+    N: AddAssign + Zero + FromStr,
+    N::Err: fmt::Display,
+{
+    /// Reads a counter from pre-aggregated lines of text, taking the item from column
+    /// `item_column` and its count from column `count_column` of each line.
+    ///
+    /// Columns are numbered from 0. Other columns are ignored, as are blank lines. Items and
+    /// counts are parsed with their [`FromStr`] implementations, and the counts of items which
+    /// appear on several lines are summed. For CSV with quoting, use the `csv` feature instead.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Delimiter};
+    /// // The output of `sort | uniq -c`.
+    /// let uniq = "      3 apple\n      1 banana\n\n     12 cherry\n";
+    /// let counter = Counter::<String>::from_delimited(uniq.as_bytes(), Delimiter::Whitespace, 1, 0)
+    ///     .unwrap();
+    /// assert_eq!(counter[&"cherry".to_string()], 12);
+    /// assert_eq!(counter.len(), 3);
+    ///
+    /// let err = Counter::<String>::from_delimited("a 1\nb x\n".as_bytes(), Delimiter::Whitespace, 0, 1)
+    ///     .unwrap_err();
+    /// assert_eq!(err.to_string(), r#"invalid line 2: invalid count "x": invalid digit found in string"#);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading from `reader` fails, or if a line which is not blank lacks
+    /// either column, or its item or count cannot be parsed.
+    pub fn from_delimited<R: io::BufRead>(
+        reader: R,
+        delimiter: Delimiter,
+        item_column: usize,
+        count_column: usize,
+    ) -> Result<Self, DelimitedError> {
+        let mut map = HashMap::default();
+        for (line, text) in (1..).zip(reader.lines()) {
+            let text = text?;
+            if text.trim().is_empty() {
+                continue;
+            }
+            let columns: Vec<&str> = match delimiter {
+                Delimiter::Whitespace => text.split_whitespace().collect(),
+                Delimiter::Char(delimiter) => text.split(delimiter).map(str::trim).collect(),
+            };
+            let line_error = |message: String| DelimitedError::Line { line, message };
+            let column = |index: usize| {
+                columns.get(index).copied().ok_or_else(|| {
+                    line_error(format!(
+                        "expected at least {} columns, found {}",
+                        index + 1,
+                        columns.len()
+                    ))
+                })
+            };
+
+            let item = column(item_column)?;
+            let item = item
+                .parse::<T>()
+                .map_err(|err| line_error(format!("invalid item {item:?}: {err}")))?;
+            let count = column(count_column)?;
+            let count = count
+                .parse::<N>()
+                .map_err(|err| line_error(format!("invalid count {count:?}: {err}")))?;
+            *map.entry(item).or_insert_with(N::zero) += count;
+        }
+        Ok(Counter {
+            map,
+            zero: N::zero(),
+        })
+    }
+}
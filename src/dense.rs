@@ -0,0 +1,488 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash};
+use core::iter::{self, FromIterator};
+use core::marker::PhantomData;
+use core::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, Index, IndexMut, Sub, SubAssign,
+};
+
+/// A counter for items from a small, dense domain, such as fieldless enums or bounded
+/// integers, backed by a vector of counts indexed by the items.
+///
+/// Each item is converted to its index with [`Into<usize>`], so counting an item is an array
+/// increment rather than a hash and a probe, and the counts of a small domain stay together in
+/// a few cache lines. The vector grows to the largest index counted, so the indices should be
+/// small: a single item converting to `1 << 30` allocates a billion counts.
+///
+/// An item whose count is zero is indistinguishable from one never counted. Iteration and
+/// conversion back to items skip both, and need the inverse conversion,
+/// [`TryFrom<usize>`].
+///
+/// ```rust
+/// # use counter::DenseCounter;
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// enum Suit {
+///     Clubs,
+///     Diamonds,
+///     Hearts,
+///     Spades,
+/// }
+///
+/// impl From<Suit> for usize {
+///     fn from(suit: Suit) -> usize {
+///         suit as usize
+///     }
+/// }
+///
+/// impl TryFrom<usize> for Suit {
+///     type Error = usize;
+///
+///     fn try_from(index: usize) -> Result<Suit, usize> {
+///         [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades]
+///             .get(index)
+///             .copied()
+///             .ok_or(index)
+///     }
+/// }
+///
+/// let hand = [Suit::Hearts, Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Hearts];
+/// let suits = hand.into_iter().collect::<DenseCounter<Suit>>();
+/// assert_eq!(suits[Suit::Hearts], 3);
+/// assert_eq!(suits[Suit::Diamonds], 0);
+/// assert_eq!(
+///     suits.most_common_ordered(),
+///     [(Suit::Hearts, 3), (Suit::Clubs, 1), (Suit::Spades, 1)],
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct DenseCounter<T: Into<usize>, N = usize> {
+    counts: Vec<N>,
+    zero: N,
+    items: PhantomData<fn(T) -> T>,
+}
+
+impl<T, N> DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Zero,
+{
+    /// Create a new, empty `DenseCounter`.
+    pub fn new() -> Self {
+        DenseCounter {
+            counts: Vec::new(),
+            zero: N::zero(),
+            items: PhantomData,
+        }
+    }
+
+    /// Create a new, empty `DenseCounter` with room for the items with indices below `domain`
+    /// without reallocation.
+    pub fn with_domain(domain: usize) -> Self {
+        DenseCounter {
+            counts: Vec::with_capacity(domain),
+            zero: N::zero(),
+            items: PhantomData,
+        }
+    }
+}
+
+impl<T, N> Default for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Zero,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N> DenseCounter<T, N>
+where
+    T: Into<usize>,
+{
+    /// Returns the counts, indexed by the items' indices.
+    ///
+    /// The slice ends at the largest index counted so far, so it may be shorter than the
+    /// domain.
+    pub fn counts(&self) -> &[N] {
+        &self.counts
+    }
+
+    /// Returns the sum of the counts.
+    pub fn total<'a, M>(&'a self) -> M
+    where
+        M: iter::Sum<&'a N>,
+    {
+        self.counts.iter().sum()
+    }
+}
+
+impl<T, N> DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Zero,
+{
+    /// Returns a mutable reference to the count at `index`, growing the vector to reach it.
+    fn count_mut(&mut self, index: usize) -> &mut N {
+        if index >= self.counts.len() {
+            self.counts.resize_with(index + 1, N::zero);
+        }
+        &mut self.counts[index]
+    }
+
+    /// Returns the number of items with a non-zero count.
+    pub fn len(&self) -> usize {
+        self.counts.iter().filter(|count| !count.is_zero()).count()
+    }
+
+    /// Returns `true` if every count is zero.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(N::is_zero)
+    }
+}
+
+impl<T, N> DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: AddAssign + Zero,
+{
+    /// Add `count` to the count of `item`.
+    ///
+    /// This is not named `add` because, on a counter held by value, that call would resolve to
+    /// [`Add::add`] instead.
+    pub fn add_count(&mut self, item: T, count: N) {
+        *self.count_mut(item.into()) += count;
+    }
+
+    /// Add one to the count of each element of the given iterable.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+        N: One,
+    {
+        for item in iterable {
+            *self.count_mut(item.into()) += N::one();
+        }
+    }
+}
+
+impl<T, N> DenseCounter<T, N>
+where
+    T: Into<usize> + TryFrom<usize>,
+    N: Zero,
+{
+    /// Returns an iterator over the items with a non-zero count, and their counts, in
+    /// increasing order of their indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of a counted item does not convert back to an item.
+    pub fn iter(&self) -> impl Iterator<Item = (T, &N)> + '_ {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| !count.is_zero())
+            .map(|(index, count)| (item_at(index), count))
+    }
+
+    /// Returns the `k` most common items in decreasing order of their counts. Items with equal
+    /// counts are in increasing order of their indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of a counted item does not convert back to an item.
+    pub fn k_most_common_ordered(&self, k: usize) -> Vec<(T, N)>
+    where
+        N: Ord + Clone,
+    {
+        let mut indices = (0..self.counts.len())
+            .filter(|&index| !self.counts[index].is_zero())
+            .collect::<Vec<_>>();
+        let compare = |&a: &usize, &b: &usize| -> Ordering {
+            self.counts[b].cmp(&self.counts[a]).then(a.cmp(&b))
+        };
+        if k < indices.len() {
+            if k == 0 {
+                return Vec::new();
+            }
+            indices.select_nth_unstable_by(k - 1, compare);
+            indices.truncate(k);
+        }
+        indices.sort_unstable_by(compare);
+        indices
+            .into_iter()
+            .map(|index| (item_at(index), self.counts[index].clone()))
+            .collect()
+    }
+
+    /// Returns the items with a non-zero count in decreasing order of their counts. Items with
+    /// equal counts are in increasing order of their indices.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of a counted item does not convert back to an item.
+    pub fn most_common_ordered(&self) -> Vec<(T, N)>
+    where
+        N: Ord + Clone,
+    {
+        self.k_most_common_ordered(usize::MAX)
+    }
+}
+
+/// Converts an index back to the item it was taken from.
+fn item_at<T: TryFrom<usize>>(index: usize) -> T {
+    match T::try_from(index) {
+        Ok(item) => item,
+        Err(_) => panic!("index {index} does not convert back to an item"),
+    }
+}
+
+impl<T, N> Index<T> for DenseCounter<T, N>
+where
+    T: Into<usize>,
+{
+    type Output = N;
+
+    /// Index in the same way as a [`Counter`]: items which have not been counted have a count
+    /// of zero.
+    fn index(&self, item: T) -> &N {
+        self.counts.get(item.into()).unwrap_or(&self.zero)
+    }
+}
+
+impl<T, N> IndexMut<T> for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Zero,
+{
+    /// Index mutably in the same way as a [`Counter`], growing the vector of counts if needed.
+    fn index_mut(&mut self, item: T) -> &mut N {
+        self.count_mut(item.into())
+    }
+}
+
+impl<T, N> PartialEq for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: PartialEq + Zero,
+{
+    /// Two dense counters are equal if every item has the same count in both, however far
+    /// their vectors of counts have grown.
+    fn eq(&self, other: &Self) -> bool {
+        let (shorter, longer) = if self.counts.len() <= other.counts.len() {
+            (&self.counts, &other.counts)
+        } else {
+            (&other.counts, &self.counts)
+        };
+        shorter == &longer[..shorter.len()] && longer[shorter.len()..].iter().all(N::is_zero)
+    }
+}
+
+impl<T, N> Eq for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Eq + Zero,
+{
+}
+
+impl<T, N> FromIterator<T> for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: AddAssign + Zero + One,
+{
+    /// Produce a `DenseCounter` from an iterator of items, in the same way as a [`Counter`].
+    fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
+        let mut counter = DenseCounter::new();
+        counter.update(iterable);
+        counter
+    }
+}
+
+impl<T, N> Extend<T> for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: AddAssign + Zero + One,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        self.update(iterable);
+    }
+}
+
+impl<T, N, S> From<Counter<T, N, S>> for DenseCounter<T, N>
+where
+    T: Hash + Eq + Into<usize>,
+    N: AddAssign + Zero,
+{
+    fn from(counter: Counter<T, N, S>) -> Self {
+        let mut dense = DenseCounter::new();
+        for (item, count) in counter.map {
+            dense.add_count(item, count);
+        }
+        dense
+    }
+}
+
+impl<T, N, S> From<DenseCounter<T, N>> for Counter<T, N, S>
+where
+    T: Hash + Eq + Into<usize> + TryFrom<usize>,
+    N: Zero,
+    S: BuildHasher + Default,
+{
+    /// Converts to a [`Counter`] of the items with a non-zero count.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of a counted item does not convert back to an item.
+    fn from(dense: DenseCounter<T, N>) -> Self {
+        let mut map = HashMap::default();
+        map.extend(
+            dense
+                .counts
+                .into_iter()
+                .enumerate()
+                .filter(|(_, count)| !count.is_zero())
+                .map(|(index, count)| (item_at(index), count)),
+        );
+        Counter {
+            map,
+            zero: dense.zero,
+        }
+    }
+}
+
+/// Combines each count of `rhs` into the matching count of `lhs`, growing `lhs` to fit.
+fn combine<N, F>(lhs: &mut Vec<N>, rhs: Vec<N>, mut combine: F)
+where
+    N: Zero,
+    F: FnMut(&mut N, N),
+{
+    if rhs.len() > lhs.len() {
+        lhs.resize_with(rhs.len(), N::zero);
+    }
+    for (lhs, rhs) in lhs.iter_mut().zip(rhs) {
+        combine(lhs, rhs);
+    }
+}
+
+impl<T, N> AddAssign for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: AddAssign + Zero,
+{
+    /// `c += d;` -> `c[x] += d[x]` for all `x`
+    fn add_assign(&mut self, rhs: Self) {
+        combine(&mut self.counts, rhs.counts, |lhs, rhs| *lhs += rhs);
+    }
+}
+
+impl<T, N> Add for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: AddAssign + Zero,
+{
+    type Output = Self;
+
+    /// `out = c + d;` -> `out[x] == c[x] + d[x]` for all `x`
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, N> SubAssign for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: PartialOrd + SubAssign + Zero,
+{
+    /// Subtract, saturating at zero as the subtraction of [`Counter`]s does.
+    ///
+    /// `c -= d;` -> `c[x] -= d[x]` for all `x`, or zero where `d[x] > c[x]`
+    fn sub_assign(&mut self, rhs: Self) {
+        combine(&mut self.counts, rhs.counts, |lhs, rhs| {
+            if rhs >= *lhs {
+                *lhs = N::zero();
+            } else {
+                *lhs -= rhs;
+            }
+        });
+    }
+}
+
+impl<T, N> Sub for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: PartialOrd + SubAssign + Zero,
+{
+    type Output = Self;
+
+    /// `out = c - d;` -> `out[x] == c[x] - d[x]` for all `x`, or zero where `d[x] > c[x]`
+    fn sub(mut self, rhs: Self) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, N> BitOrAssign for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Ord + Zero,
+{
+    /// `c |= d;` -> `c[x] == max(c[x], d[x])`
+    fn bitor_assign(&mut self, rhs: Self) {
+        combine(&mut self.counts, rhs.counts, |lhs, rhs| {
+            if rhs > *lhs {
+                *lhs = rhs;
+            }
+        });
+    }
+}
+
+impl<T, N> BitOr for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Ord + Zero,
+{
+    type Output = Self;
+
+    /// `out = c | d;` -> `out[x] == max(c[x], d[x])`
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl<T, N> BitAndAssign for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Ord + Zero,
+{
+    /// `c &= d;` -> `c[x] == min(c[x], d[x])`
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.counts.truncate(rhs.counts.len());
+        combine(&mut self.counts, rhs.counts, |lhs, rhs| {
+            if rhs < *lhs {
+                *lhs = rhs;
+            }
+        });
+    }
+}
+
+impl<T, N> BitAnd for DenseCounter<T, N>
+where
+    T: Into<usize>,
+    N: Ord + Zero,
+{
+    type Output = Self;
+
+    /// `out = c & d;` -> `out[x] == min(c[x], d[x])`
+    fn bitand(mut self, rhs: Self) -> Self {
+        self &= rhs;
+        self
+    }
+}
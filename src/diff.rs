@@ -0,0 +1,198 @@
+use crate::Counter;
+
+use crate::num::Zero;
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, Sub};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An item whose count differs between the two counters compared by [`Counter::diff`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CountChange<T, N> {
+    /// The item.
+    pub item: T,
+    /// Its count in the baseline.
+    pub before: N,
+    /// Its count in the other counter.
+    pub after: N,
+}
+
+/// The differences between a baseline counter and another, as returned by [`Counter::diff`].
+///
+/// Each list is sorted by item. Displaying a `CounterDiff` writes one line per difference:
+/// `+ item: count` for added items, `- item: count` for removed items and
+/// `~ item: before -> after` for changed items.
+///
+/// ```rust
+/// # use counter::Counter;
+/// let baseline = "aabbbc".chars().collect::<Counter<_>>();
+/// let current = "abbbbd".chars().collect::<Counter<_>>();
+/// let diff = baseline.diff(&current);
+/// assert_eq!(
+///     diff.to_string(),
+///     "+ d: 1\n- c: 1\n~ a: 2 -> 1\n~ b: 3 -> 4\n",
+/// );
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CounterDiff<T, N> {
+    /// Items which are only in the other counter, with their counts.
+    pub added: Vec<(T, N)>,
+    /// Items which are only in the baseline, with their counts.
+    pub removed: Vec<(T, N)>,
+    /// Items which are in both counters, with different counts.
+    pub changed: Vec<CountChange<T, N>>,
+}
+
+/// Totals of the differences between two counters, as returned by [`CounterDiff::summary`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DiffSummary<N> {
+    /// The number of added items.
+    pub added_items: usize,
+    /// The number of removed items.
+    pub removed_items: usize,
+    /// The number of changed items.
+    pub changed_items: usize,
+    /// The sum of the counts of the added items.
+    pub added: N,
+    /// The sum of the counts of the removed items.
+    pub removed: N,
+    /// The sum of the absolute differences in count of the changed items.
+    pub changed: N,
+}
+
+impl<T, N> CounterDiff<T, N> {
+    /// Returns `true` if the two counters were equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<T, N> CounterDiff<T, N>
+where
+    N: Clone + PartialOrd + Sub<Output = N> + AddAssign + Zero,
+{
+    /// Returns the number of added, removed and changed items, and the total count of each.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let baseline = "aabbbc".chars().collect::<Counter<_>>();
+    /// let current = "abbbbd".chars().collect::<Counter<_>>();
+    /// let summary = baseline.diff(&current).summary();
+    /// assert_eq!((summary.added_items, summary.added), (1, 1));
+    /// assert_eq!((summary.removed_items, summary.removed), (1, 1));
+    /// assert_eq!((summary.changed_items, summary.changed), (2, 2));
+    /// assert_eq!(summary.to_string(), "1 added (1), 1 removed (1), 2 changed (2)");
+    /// ```
+    pub fn summary(&self) -> DiffSummary<N> {
+        let mut summary = DiffSummary {
+            added_items: self.added.len(),
+            removed_items: self.removed.len(),
+            changed_items: self.changed.len(),
+            added: N::zero(),
+            removed: N::zero(),
+            changed: N::zero(),
+        };
+        for (_, count) in &self.added {
+            summary.added += count.clone();
+        }
+        for (_, count) in &self.removed {
+            summary.removed += count.clone();
+        }
+        for change in &self.changed {
+            summary.changed += if change.after > change.before {
+                change.after.clone() - change.before.clone()
+            } else {
+                change.before.clone() - change.after.clone()
+            };
+        }
+        summary
+    }
+}
+
+impl<T, N> fmt::Display for CounterDiff<T, N>
+where
+    T: fmt::Display,
+    N: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (item, count) in &self.added {
+            writeln!(f, "+ {item}: {count}")?;
+        }
+        for (item, count) in &self.removed {
+            writeln!(f, "- {item}: {count}")?;
+        }
+        for CountChange {
+            item,
+            before,
+            after,
+        } in &self.changed
+        {
+            writeln!(f, "~ {item}: {before} -> {after}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<N> fmt::Display for DiffSummary<N>
+where
+    N: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} added ({}), {} removed ({}), {} changed ({})",
+            self.added_items,
+            self.added,
+            self.removed_items,
+            self.removed,
+            self.changed_items,
+            self.changed
+        )
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: Clone + PartialEq,
+    S: BuildHasher,
+{
+    /// Compare this counter, as the baseline, with `other`.
+    ///
+    /// Returns the items added in `other`, the items removed from it, and the items whose
+    /// counts changed, each sorted by item. See [`CounterDiff`].
+    pub fn diff(&self, other: &Self) -> CounterDiff<T, N> {
+        let mut diff = CounterDiff {
+            added: Vec::new(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (item, before) in &self.map {
+            match other.map.get(item) {
+                None => diff.removed.push((item.clone(), before.clone())),
+                Some(after) if after != before => diff.changed.push(CountChange {
+                    item: item.clone(),
+                    before: before.clone(),
+                    after: after.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        for (item, after) in &other.map {
+            if !self.map.contains_key(item) {
+                diff.added.push((item.clone(), after.clone()));
+            }
+        }
+        diff.added.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        diff.removed.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        diff.changed.sort_unstable_by(|a, b| a.item.cmp(&b.item));
+        diff
+    }
+}
@@ -0,0 +1,127 @@
+//! Text rendering of counters for quick diagnostics.
+//!
+//! [`Counter::histogram`] returns a [`Histogram`], which renders the counter as an ASCII bar
+//! chart through its [`Display`](fmt::Display) implementation:
+//!
+//! ```rust
+//! # use counter::Counter;
+//! let counter = "abracadabra".chars().collect::<Counter<_>>();
+//! let chart = counter.histogram().width(10).max_rows(3).to_string();
+//! assert_eq!(
+//!     chart,
+//!     "a | ########## 5\n\
+//!      b | ####       2\n\
+//!      r | ####       2\n\
+//!      ... 2 more\n",
+//! );
+//! ```
+
+use crate::frequencies::count_to_f64;
+use crate::{Counter, OrderBy};
+
+use crate::num::ToPrimitive;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+
+/// An ASCII bar chart of the counts of a [`Counter`], rendered by its
+/// [`Display`](fmt::Display) implementation.
+///
+/// Each row shows an item, a bar whose length is proportional to its count, and the count. The
+/// longest bar, for the largest count, is `width` characters long; items with a count of zero
+/// or less have no bar.
+///
+/// Created by [`Counter::histogram`]. By default, bars are 40 characters wide, every item is
+/// shown, and the most common items come first.
+#[derive(Debug)]
+pub struct Histogram<'a, T: Hash + Eq, N, S> {
+    counter: &'a Counter<T, N, S>,
+    width: usize,
+    order: OrderBy<'a, T, N>,
+    max_rows: Option<usize>,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Returns a [`Histogram`] of this counter, which renders it as an ASCII bar chart.
+    ///
+    /// See the [`display`](crate::display) module.
+    pub fn histogram(&self) -> Histogram<'_, T, N, S> {
+        Histogram {
+            counter: self,
+            width: 40,
+            order: OrderBy::CountDesc,
+            max_rows: None,
+        }
+    }
+}
+
+impl<'a, T, N, S> Histogram<'a, T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Sets the length of the longest bar, in characters.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Sets the order of the rows.
+    pub fn order(mut self, order: OrderBy<'a, T, N>) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Shows at most `max_rows` items, followed by a line counting the items left out.
+    pub fn max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = Some(max_rows);
+        self
+    }
+}
+
+impl<T, N, S> fmt::Display for Histogram<'_, T, N, S>
+where
+    T: Hash + Eq + Clone + Ord + fmt::Display,
+    N: Clone + Ord + ToPrimitive + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = self.counter.to_ordered_pairs(self.order, self.max_rows);
+        let labels = rows
+            .iter()
+            .map(|(item, _)| item.to_string())
+            .collect::<Vec<_>>();
+        let label_width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let max_count = rows
+            .iter()
+            .map(|(_, count)| count_to_f64(count))
+            .fold(0.0, f64::max);
+
+        for ((_, count), label) in rows.iter().zip(&labels) {
+            let bar = if max_count > 0.0 {
+                let length = count_to_f64(count) / max_count * self.width as f64;
+                // Rounds to the nearest length; NaN and negative lengths become 0.
+                "#".repeat((length + 0.5) as usize)
+            } else {
+                String::new()
+            };
+            writeln!(
+                f,
+                "{label:<label_width$} | {bar:<width$} {count}",
+                width = self.width
+            )?;
+        }
+        let hidden = self.counter.map.len() - rows.len();
+        if hidden > 0 {
+            writeln!(f, "... {hidden} more")?;
+        }
+        Ok(())
+    }
+}
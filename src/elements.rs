@@ -0,0 +1,323 @@
+use crate::collections::hash_map;
+use crate::{Counter, Error};
+
+use crate::num::{ToPrimitive, Zero};
+
+use alloc::vec::{self, Vec};
+use core::hash::Hash;
+use core::iter::{self, FusedIterator};
+
+/// The number of times an item with the given count is repeated: zero for non-positive
+/// counts, and saturating at `usize::MAX` for counts too large to represent.
+pub(crate) fn repetitions<N>(count: &N) -> usize
+where
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    match count.to_usize() {
+        Some(repetitions) => repetitions,
+        None if *count > N::zero() => usize::MAX,
+        None => 0,
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    /// Returns an iterator over the items of the multiset, each repeated as many times as its
+    /// count.
+    ///
+    /// Items are returned in arbitrary order, but the repetitions of an item are always
+    /// consecutive. As with Python's `Counter.elements()`, items with a count less than one
+    /// are ignored.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut elements = counter.elements().collect::<Vec<_>>();
+    /// elements.sort();
+    /// assert_eq!(elements, [&'a', &'b', &'b', &'c', &'c', &'c']);
+    /// ```
+    pub fn elements(&self) -> Elements<'_, T, N> {
+        self.elements_capped(usize::MAX)
+    }
+
+    /// Returns an iterator over the items of the multiset, each repeated as many times as its
+    /// count, but no more than `max_per_item` times.
+    ///
+    /// This draws a sample in which no item is overrepresented, such as at most 1000 examples
+    /// per class, without collecting the elements first. Otherwise it behaves like
+    /// [`elements`](Counter::elements).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut elements = counter.elements_capped(2).collect::<Vec<_>>();
+    /// elements.sort();
+    /// assert_eq!(elements, [&'a', &'b', &'b', &'c', &'c']);
+    /// ```
+    pub fn elements_capped(&self, max_per_item: usize) -> Elements<'_, T, N> {
+        Elements {
+            items: self.map.iter(),
+            current: None,
+            remaining: 0,
+            cap: max_per_item,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    /// Consumes the counter and returns an iterator over the items of the multiset, each
+    /// repeated as many times as its count.
+    ///
+    /// Each item is cloned one time fewer than its count; the last repetition is the item
+    /// itself. Items with a count less than one are ignored.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = ["x", "y", "x"].into_iter().map(String::from).collect::<Counter<_>>();
+    /// let mut elements = counter.into_elements().collect::<Vec<String>>();
+    /// elements.sort();
+    /// assert_eq!(elements, ["x", "x", "y"]);
+    /// ```
+    pub fn into_elements(self) -> IntoElements<T, N> {
+        IntoElements {
+            items: self.map.into_iter(),
+            current: None,
+            remaining: 0,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    /// Returns an iterator over the items of the multiset, each repeated as many times as its
+    /// count, in increasing order.
+    ///
+    /// Only the distinct items are sorted up front; the repetitions are produced lazily. Items
+    /// with a count less than one are ignored.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let digits = "3141592".chars().collect::<Counter<_>>();
+    /// let smallest = digits.sorted_elements().collect::<String>();
+    /// assert_eq!(smallest, "1123459");
+    /// ```
+    pub fn sorted_elements(&self) -> SortedElements<'_, T> {
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, count)| (item, repetitions(count)))
+            .filter(|&(_, repetitions)| repetitions > 0)
+            .collect::<Vec<_>>();
+        items.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        let len = items.iter().try_fold(0_usize, |len, &(_, repetitions)| {
+            len.checked_add(repetitions)
+        });
+        SortedElements {
+            items: items.into_iter(),
+            current: None,
+            remaining: 0,
+            len,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord + Clone,
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    /// Consumes the counter and returns a vector of the items of the multiset, each repeated as
+    /// many times as its count, in increasing order.
+    ///
+    /// This is the canonical, lexicographically smallest arrangement of the multiset, such as
+    /// the smallest number which can be written with a set of counted digits. Items with a
+    /// count less than one are ignored. See also [`sorted_elements`](Counter::sorted_elements),
+    /// which produces the same sequence lazily.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let letters = "banana".chars().collect::<Counter<_>>();
+    /// assert_eq!(letters.into_sorted_elements(), ['a', 'a', 'a', 'b', 'n', 'n']);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total count does not fit in memory, as collecting the elements would. Use
+    /// [`try_into_sorted_elements`](Counter::try_into_sorted_elements) to get an error instead.
+    pub fn into_sorted_elements(self) -> Vec<T> {
+        self.try_into_sorted_elements()
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Consumes the counter and returns a vector of the items of the multiset in increasing
+    /// order, as [`into_sorted_elements`](Counter::into_sorted_elements) does, unless the
+    /// vector cannot be allocated.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Error};
+    /// let letters = "banana".chars().collect::<Counter<_>>();
+    /// assert_eq!(letters.try_into_sorted_elements().unwrap(), ['a', 'a', 'a', 'b', 'n', 'n']);
+    ///
+    /// let huge = Counter::<_, u64>::from([('a', u64::MAX), ('b', u64::MAX)]);
+    /// assert!(matches!(huge.try_into_sorted_elements(), Err(Error::CapacityExhausted)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CapacityExhausted`] if the total count does not fit in a `usize`, or
+    /// the allocator fails.
+    pub fn try_into_sorted_elements(self) -> Result<Vec<T>, Error> {
+        let mut items = self
+            .map
+            .into_iter()
+            .map(|(item, count)| (item, repetitions(&count)))
+            .filter(|&(_, repetitions)| repetitions > 0)
+            .collect::<Vec<_>>();
+        items.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let len = items
+            .iter()
+            .try_fold(0_usize, |len, &(_, repetitions)| {
+                len.checked_add(repetitions)
+            })
+            .ok_or(Error::CapacityExhausted)?;
+        let mut elements = Vec::new();
+        elements
+            .try_reserve_exact(len)
+            .map_err(|_| Error::CapacityExhausted)?;
+        for (item, repetitions) in items {
+            elements.extend(iter::repeat_n(item, repetitions));
+        }
+        Ok(elements)
+    }
+}
+
+/// An iterator over the items of a [`Counter`], each repeated as many times as its count.
+///
+/// Created by [`Counter::elements`] and [`Counter::elements_capped`].
+#[derive(Clone, Debug)]
+pub struct Elements<'a, T, N> {
+    items: hash_map::Iter<'a, T, N>,
+    current: Option<&'a T>,
+    remaining: usize,
+    cap: usize,
+}
+
+impl<'a, T, N> Iterator for Elements<'a, T, N>
+where
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        while self.remaining == 0 {
+            let (item, count) = self.items.next()?;
+            self.current = Some(item);
+            self.remaining = repetitions(count).min(self.cap);
+        }
+        self.remaining -= 1;
+        self.current
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let upper = self
+            .items
+            .len()
+            .checked_mul(self.cap)
+            .and_then(|upper| upper.checked_add(self.remaining));
+        (self.remaining, upper)
+    }
+}
+
+impl<'a, T, N> FusedIterator for Elements<'a, T, N> where N: ToPrimitive + PartialOrd + Zero {}
+
+/// An owning iterator over the items of a [`Counter`], each repeated as many times as its
+/// count.
+///
+/// Created by [`Counter::into_elements`].
+#[derive(Debug)]
+pub struct IntoElements<T, N> {
+    items: hash_map::IntoIter<T, N>,
+    current: Option<T>,
+    remaining: usize,
+}
+
+impl<T, N> Iterator for IntoElements<T, N>
+where
+    T: Clone,
+    N: ToPrimitive + PartialOrd + Zero,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while self.remaining == 0 {
+            let (item, count) = self.items.next()?;
+            self.current = Some(item);
+            self.remaining = repetitions(&count);
+        }
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.current.take()
+        } else {
+            self.current.clone()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.items.len() == 0 {
+            (self.remaining, Some(self.remaining))
+        } else {
+            (self.remaining, None)
+        }
+    }
+}
+
+impl<T, N> FusedIterator for IntoElements<T, N>
+where
+    T: Clone,
+    N: ToPrimitive + PartialOrd + Zero,
+{
+}
+
+/// An iterator over the items of a [`Counter`], each repeated as many times as its count, in
+/// increasing order.
+///
+/// Created by [`Counter::sorted_elements`].
+#[derive(Clone, Debug)]
+pub struct SortedElements<'a, T> {
+    items: vec::IntoIter<(&'a T, usize)>,
+    current: Option<&'a T>,
+    remaining: usize,
+    /// The number of elements left to produce, or `None` if it overflows a `usize`.
+    len: Option<usize>,
+}
+
+impl<'a, T> Iterator for SortedElements<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            let (item, repetitions) = self.items.next()?;
+            self.current = Some(item);
+            self.remaining = repetitions;
+        }
+        self.remaining -= 1;
+        self.len = self.len.map(|len| len - 1);
+        self.current
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len.unwrap_or(usize::MAX), self.len)
+    }
+}
+
+impl<T> FusedIterator for SortedElements<'_, T> {}
@@ -0,0 +1,399 @@
+use crate::collections::{hash_map, DefaultHashBuilder, Entry, OccupiedEntry};
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, SubAssign};
+
+/// A view into the count of a single item of a [`Counter`], returned by
+/// [`Counter::count_entry`].
+///
+/// Its methods keep the counter's invariants: decrementing stops at zero, and an item whose
+/// count is decremented or set to zero is removed, as [`subtract`](Counter::subtract) and
+/// [`set`](Counter::set) would do. The methods of the map's own entry API, such as
+/// [`or_insert`](CounterEntry::or_insert), are also available.
+///
+/// ```rust
+/// # use counter::Counter;
+/// let mut counter = Counter::<&str>::new();
+/// counter.count_entry("apples").increment_by(3);
+/// counter.count_entry("pears").increment();
+///
+/// assert_eq!(counter.count_entry("apples").decrement_saturating(), Some(&mut 2));
+/// assert_eq!(counter.count_entry("pears").decrement_saturating(), None);
+/// assert!(!counter.contains_key(&"pears"));
+/// ```
+pub struct CounterEntry<'a, T, N, S: 'a = DefaultHashBuilder> {
+    entry: Entry<'a, T, N, S>,
+    zero: &'a N,
+}
+
+impl<T, N, S> fmt::Debug for CounterEntry<'_, T, N, S>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CounterEntry")
+            .field("item", self.key())
+            .field("count", self.count())
+            .finish()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Returns the [`CounterEntry`] of `item`, for updating its count in place.
+    ///
+    /// The counter entry supports the `or_insert` family of methods of the map's own
+    /// [`entry`](std::collections::HashMap::entry), which the counter derefs to, as well as
+    /// counting methods which keep the counter free of zero counts.
+    pub fn count_entry(&mut self, item: T) -> CounterEntry<'_, T, N, S> {
+        CounterEntry {
+            entry: self.map.entry(item),
+            zero: &self.zero,
+        }
+    }
+}
+
+impl<'a, T, N, S> CounterEntry<'a, T, N, S> {
+    /// Returns the item of this entry.
+    pub fn key(&self) -> &T {
+        self.entry.key()
+    }
+
+    /// Returns the count of the item, which is zero if the counter does not hold it.
+    pub fn count(&self) -> &N {
+        match &self.entry {
+            hash_map::Entry::Occupied(entry) => entry.get(),
+            hash_map::Entry::Vacant(_) => self.zero,
+        }
+    }
+
+    /// Inserts `default` if the counter does not hold the item, and returns the count.
+    pub fn or_insert(self, default: N) -> &'a mut N
+    where
+        T: Hash,
+        S: BuildHasher,
+    {
+        self.entry.or_insert(default)
+    }
+
+    /// Inserts the result of `default` if the counter does not hold the item, and returns the
+    /// count.
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut N
+    where
+        T: Hash,
+        S: BuildHasher,
+        F: FnOnce() -> N,
+    {
+        self.entry.or_insert_with(default)
+    }
+
+    /// Inserts the default count if the counter does not hold the item, and returns the count.
+    pub fn or_default(self) -> &'a mut N
+    where
+        T: Hash,
+        N: Default,
+        S: BuildHasher,
+    {
+        self.entry.or_default()
+    }
+
+    /// Calls `f` with the count if the counter holds the item.
+    pub fn and_modify<F>(self, f: F) -> Self
+    where
+        F: FnOnce(&mut N),
+    {
+        CounterEntry {
+            entry: self.entry.and_modify(f),
+            zero: self.zero,
+        }
+    }
+
+    /// Removes the item from the counter, returning its count if it was held.
+    pub fn remove(self) -> Option<N> {
+        match self.entry {
+            hash_map::Entry::Occupied(entry) => Some(entry.remove()),
+            hash_map::Entry::Vacant(_) => None,
+        }
+    }
+}
+
+impl<'a, T, N, S> CounterEntry<'a, T, N, S>
+where
+    T: Hash,
+    N: AddAssign + Zero,
+    S: BuildHasher,
+{
+    /// Adds `by` to the count of the item, and returns the new count.
+    pub fn increment_by(self, by: N) -> &'a mut N {
+        let count = self.entry.or_insert_with(N::zero);
+        *count += by;
+        count
+    }
+
+    /// Adds one to the count of the item, and returns the new count.
+    pub fn increment(self) -> &'a mut N
+    where
+        N: One,
+    {
+        self.increment_by(N::one())
+    }
+}
+
+impl<'a, T, N, S> CounterEntry<'a, T, N, S>
+where
+    T: Hash,
+    N: PartialOrd + SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Subtracts `by` from the count of the item, stopping at zero, and returns the new count.
+    ///
+    /// If the count reaches zero, or was not positive to begin with, the item is removed and
+    /// `None` is returned.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_>::from([('a', 5)]);
+    /// assert_eq!(counter.count_entry('a').decrement_by_saturating(3), Some(&mut 2));
+    /// assert_eq!(counter.count_entry('a').decrement_by_saturating(3), None);
+    /// assert!(counter.is_empty());
+    /// ```
+    pub fn decrement_by_saturating(self, by: N) -> Option<&'a mut N> {
+        match self.entry {
+            hash_map::Entry::Occupied(mut entry) => {
+                if *entry.get() > by {
+                    *entry.get_mut() -= by;
+                    Some(entry.into_mut())
+                } else {
+                    entry.remove();
+                    None
+                }
+            }
+            hash_map::Entry::Vacant(_) => None,
+        }
+    }
+
+    /// Subtracts one from the count of the item, stopping at zero, and returns the new count.
+    ///
+    /// If the count reaches zero, or was not positive to begin with, the item is removed and
+    /// `None` is returned.
+    pub fn decrement_saturating(self) -> Option<&'a mut N>
+    where
+        N: One,
+    {
+        self.decrement_by_saturating(N::one())
+    }
+
+    /// Sets the count of the item, returning its previous count if it was held.
+    ///
+    /// As with [`Counter::set`], a count which is not positive removes the item.
+    pub fn set(self, count: N) -> Option<N> {
+        match self.entry {
+            hash_map::Entry::Occupied(mut entry) => {
+                if count > N::zero() {
+                    Some(entry.insert(count))
+                } else {
+                    Some(entry.remove())
+                }
+            }
+            hash_map::Entry::Vacant(entry) => {
+                if count > N::zero() {
+                    entry.insert(count);
+                }
+                None
+            }
+        }
+    }
+}
+
+/// A handle on the count of a single item of a [`Counter`], for many updates in a row, returned
+/// by [`Counter::cursor`].
+///
+/// The item is looked up once, when the cursor is created; every later read or update goes
+/// straight to its count, without hashing the item again. Unlike a [`CounterEntry`], whose
+/// methods consume it, a cursor can be used any number of times.
+///
+/// The cursor holds the item in the counter while it lives, starting it at zero if needed.
+/// When the cursor is dropped, the item is removed again if its count is not positive, so the
+/// counter is left free of zero counts as usual.
+///
+/// ```rust
+/// # use counter::Counter;
+/// let mut counter = Counter::<&str>::new();
+/// let mut cursor = counter.cursor("word");
+/// for _ in 0..3 {
+///     cursor.increment();
+/// }
+/// cursor.decrement_saturating();
+/// assert_eq!(*cursor.count(), 2);
+/// drop(cursor);
+///
+/// assert_eq!(counter[&"word"], 2);
+/// counter.cursor("unseen");
+/// assert!(!counter.contains_key(&"unseen"));
+/// ```
+pub struct CountCursor<'a, T, N, S: 'a = DefaultHashBuilder>
+where
+    N: PartialOrd + Zero,
+{
+    // Only `None` once the cursor has been consumed, which happens just before it is dropped.
+    entry: Option<OccupiedEntry<'a, T, N, S>>,
+}
+
+impl<T, N, S> fmt::Debug for CountCursor<'_, T, N, S>
+where
+    T: fmt::Debug,
+    N: fmt::Debug + PartialOrd + Zero,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CountCursor")
+            .field("item", self.key())
+            .field("count", self.count())
+            .finish()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero,
+    S: BuildHasher,
+{
+    /// Returns a [`CountCursor`] on `item`, for reading and updating its count repeatedly
+    /// without hashing the item each time.
+    pub fn cursor(&mut self, item: T) -> CountCursor<'_, T, N, S> {
+        let entry = match self.map.entry(item) {
+            hash_map::Entry::Occupied(entry) => entry,
+            hash_map::Entry::Vacant(entry) => entry.insert_entry(N::zero()),
+        };
+        CountCursor { entry: Some(entry) }
+    }
+}
+
+impl<T, N, S> CountCursor<'_, T, N, S>
+where
+    N: PartialOrd + Zero,
+{
+    fn entry(&self) -> &OccupiedEntry<'_, T, N, S> {
+        self.entry.as_ref().expect("a live cursor has an entry")
+    }
+
+    fn count_mut(&mut self) -> &mut N {
+        self.entry
+            .as_mut()
+            .expect("a live cursor has an entry")
+            .get_mut()
+    }
+
+    /// Returns the item of this cursor.
+    pub fn key(&self) -> &T {
+        self.entry().key()
+    }
+
+    /// Returns the current count of the item.
+    pub fn count(&self) -> &N {
+        self.entry().get()
+    }
+
+    /// Sets the count of the item, returning its previous count.
+    ///
+    /// A count which is not positive is kept while the cursor lives, and removes the item when
+    /// the cursor is dropped.
+    pub fn set(&mut self, count: N) -> N {
+        core::mem::replace(self.count_mut(), count)
+    }
+
+    /// Removes the item from the counter, returning its count.
+    pub fn remove(mut self) -> N {
+        self.entry
+            .take()
+            .expect("a live cursor has an entry")
+            .remove()
+    }
+
+    /// Removes the item from the counter if `predicate` holds for its count, returning the
+    /// count it had. Otherwise the cursor is returned, to continue using it.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_>::from([('a', 3)]);
+    /// let cursor = counter.cursor('a');
+    /// let cursor = cursor.remove_if(|&count| count < 3).unwrap_err();
+    /// assert_eq!(cursor.remove_if(|&count| count == 3).ok(), Some(3));
+    /// assert!(counter.is_empty());
+    /// ```
+    pub fn remove_if<F>(self, predicate: F) -> Result<N, Self>
+    where
+        F: FnOnce(&N) -> bool,
+    {
+        if predicate(self.count()) {
+            Ok(self.remove())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl<T, N, S> CountCursor<'_, T, N, S>
+where
+    N: PartialOrd + AddAssign + Zero,
+{
+    /// Adds `by` to the count of the item, and returns the new count.
+    pub fn increment_by(&mut self, by: N) -> &N {
+        let count = self.count_mut();
+        *count += by;
+        count
+    }
+
+    /// Adds one to the count of the item, and returns the new count.
+    pub fn increment(&mut self) -> &N
+    where
+        N: One,
+    {
+        self.increment_by(N::one())
+    }
+}
+
+impl<T, N, S> CountCursor<'_, T, N, S>
+where
+    N: PartialOrd + SubAssign + Zero,
+{
+    /// Subtracts `by` from the count of the item, stopping at zero, and returns the new count.
+    pub fn decrement_by_saturating(&mut self, by: N) -> &N {
+        let count = self.count_mut();
+        if *count > by {
+            *count -= by;
+        } else {
+            *count = N::zero();
+        }
+        count
+    }
+
+    /// Subtracts one from the count of the item, stopping at zero, and returns the new count.
+    pub fn decrement_saturating(&mut self) -> &N
+    where
+        N: One,
+    {
+        self.decrement_by_saturating(N::one())
+    }
+}
+
+impl<T, N, S> Drop for CountCursor<'_, T, N, S>
+where
+    N: PartialOrd + Zero,
+{
+    fn drop(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            if *entry.get() <= N::zero() {
+                entry.remove();
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::ops::AddAssign;
+use core::{array, iter, mem};
+
+/// A ring of counters, one per epoch, for counting over a sliding window of time.
+///
+/// Counts go into the current epoch. Each call to [`rotate`](EpochCounter::rotate) starts a new
+/// epoch and drops the oldest, so the counter always covers the last `EPOCHS` epochs. For "the
+/// last five minutes" at one-minute resolution, use five epochs and rotate once a minute.
+///
+/// ```rust
+/// # use counter::EpochCounter;
+/// let mut requests = EpochCounter::<&str, 3>::new();
+/// requests.update(["/", "/about"]);
+/// requests.rotate();
+/// requests.update(["/", "/"]);
+/// assert_eq!(requests.windowed_total::<usize>(), 4);
+/// assert_eq!(requests.windowed_most_common(1), [("/", 3)]);
+///
+/// requests.rotate();
+/// requests.rotate();
+/// assert_eq!(requests.windowed_counter()[&"/"], 2);
+/// assert_eq!(requests.windowed_counter()[&"/about"], 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EpochCounter<T: Hash + Eq, const EPOCHS: usize, N = usize> {
+    epochs: [Counter<T, N>; EPOCHS],
+    current: usize,
+}
+
+impl<T, const EPOCHS: usize, N> EpochCounter<T, EPOCHS, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new `EpochCounter`, with every epoch empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `EPOCHS` is zero.
+    pub fn new() -> Self {
+        assert!(EPOCHS > 0, "an epoch counter needs at least one epoch");
+        EpochCounter {
+            epochs: array::from_fn(|_| Counter::new()),
+            current: 0,
+        }
+    }
+
+    /// Start a new, empty epoch, and return the counter of the oldest epoch, which leaves the
+    /// window.
+    pub fn rotate(&mut self) -> Counter<T, N> {
+        self.current = (self.current + 1) % EPOCHS;
+        mem::replace(&mut self.epochs[self.current], Counter::new())
+    }
+}
+
+impl<T, const EPOCHS: usize, N> Default for EpochCounter<T, EPOCHS, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new `EpochCounter`, with every epoch empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `EPOCHS` is zero.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const EPOCHS: usize, N> EpochCounter<T, EPOCHS, N>
+where
+    T: Hash + Eq,
+{
+    /// Returns the counter of the current epoch.
+    pub fn current(&self) -> &Counter<T, N> {
+        &self.epochs[self.current]
+    }
+
+    /// Returns the counter of the epoch `age` rotations ago, where the current epoch has age
+    /// zero, or `None` if that epoch has left the window.
+    pub fn epoch(&self, age: usize) -> Option<&Counter<T, N>> {
+        (age < EPOCHS).then(|| &self.epochs[(self.current + EPOCHS - age) % EPOCHS])
+    }
+
+    /// Returns an iterator over the counters of the epochs in the window, from the current
+    /// epoch to the oldest.
+    pub fn epochs(&self) -> impl Iterator<Item = &Counter<T, N>> + '_ {
+        (0..EPOCHS).map(move |age| &self.epochs[(self.current + EPOCHS - age) % EPOCHS])
+    }
+
+    /// Returns the sum of the counts of every epoch in the window.
+    pub fn windowed_total<'a, M>(&'a self) -> M
+    where
+        M: iter::Sum<&'a N>,
+    {
+        self.epochs.iter().flat_map(|epoch| epoch.values()).sum()
+    }
+}
+
+impl<T, const EPOCHS: usize, N> EpochCounter<T, EPOCHS, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Add `count` to the count of `item` in the current epoch.
+    pub fn add(&mut self, item: T, count: N) {
+        let epoch = &mut self.epochs[self.current];
+        *epoch.map.entry(item).or_insert_with(N::zero) += count;
+    }
+
+    /// Add one to the count of each element of the given iterable in the current epoch.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+        N: One,
+    {
+        self.epochs[self.current].update(iterable);
+    }
+}
+
+impl<T, const EPOCHS: usize, N> EpochCounter<T, EPOCHS, N>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + Clone,
+{
+    /// Returns a counter of everything counted in the window, summing the epochs.
+    pub fn windowed_counter(&self) -> Counter<T, N> {
+        let mut window = Counter::new();
+        for epoch in &self.epochs {
+            for (item, count) in epoch.iter() {
+                *window.map.entry(item.clone()).or_insert_with(N::zero) += count.clone();
+            }
+        }
+        window
+    }
+}
+
+impl<T, const EPOCHS: usize, N> EpochCounter<T, EPOCHS, N>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: AddAssign + Zero + Clone + Ord,
+{
+    /// Returns the `k` most common items in the window, with their counts summed over the
+    /// epochs, in the order of [`Counter::k_most_common_ordered`].
+    pub fn windowed_most_common(&self, k: usize) -> Vec<(T, N)> {
+        self.windowed_counter().k_most_common_ordered(k)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{KeyConversionError, NonZeroCountError, OverflowError, PlanError};
+
+use alloc::boxed::Box;
+use core::fmt;
+
+#[cfg(feature = "csv")]
+use crate::csv::CsvError;
+#[cfg(feature = "std")]
+use crate::DelimitedError;
+
+/// Any error produced by this crate.
+///
+/// The fallible methods of [`Counter`](crate::Counter) return the most specific error they
+/// can, such as a [`PlanError`] naming the failing step. Each of those errors converts into an
+/// `Error` with [`From`], so a service which only needs to report what went wrong can use `?`
+/// on all of them and handle a single type.
+///
+/// Methods which panic on overflow or allocation failure have `try_` counterparts which return
+/// an `Error` instead, such as [`Counter::try_update_slice`](crate::Counter::try_update_slice).
+///
+/// ```rust
+/// # use counter::{Counter, Error, UpdatePlan};
+/// fn restock(counter: &mut Counter<&'static str>) -> Result<(), Error> {
+///     let plan = UpdatePlan::new().decrement("apples", 3).increment("pears", 2);
+///     counter.try_reserve(1)?;
+///     let _ = counter.apply_update_plan(&plan)?;
+///     Ok(())
+/// }
+///
+/// let mut counter = Counter::from([("apples", 2)]);
+/// let err = restock(&mut counter).unwrap_err();
+/// assert!(matches!(err, Error::InvalidPlan(_)));
+/// assert_eq!(counter[&"apples"], 2);
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A count, or a total of counts, does not fit in its type.
+    Overflow,
+    /// Memory could not be allocated for the counter or its output.
+    CapacityExhausted,
+    /// An [`UpdatePlan`](crate::UpdatePlan) could not be applied; the source is the
+    /// [`PlanError`].
+    InvalidPlan(Box<dyn core::error::Error + Send + Sync>),
+    /// An item or count could not be converted to another type; the source is the
+    /// [`KeyConversionError`] or [`NonZeroCountError`].
+    Conversion(Box<dyn core::error::Error + Send + Sync>),
+    /// A counter could not be read from its serialized form.
+    Parse(Box<dyn core::error::Error + Send + Sync>),
+    /// Reading or writing a file failed, such as a run of a `SpillingCounter`.
+    #[cfg(feature = "std")]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Overflow => f.write_str("a count does not fit in its type"),
+            Error::CapacityExhausted => f.write_str("memory could not be allocated"),
+            Error::InvalidPlan(err) => write!(f, "invalid update plan: {err}"),
+            Error::Conversion(err) => write!(f, "conversion failed: {err}"),
+            Error::Parse(err) => write!(f, "parse failed: {err}"),
+            #[cfg(feature = "std")]
+            Error::Io(err) => write!(f, "I/O failed: {err}"),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::Overflow | Error::CapacityExhausted => None,
+            Error::InvalidPlan(err) | Error::Conversion(err) | Error::Parse(err) => Some(&**err),
+            #[cfg(feature = "std")]
+            Error::Io(err) => Some(err),
+        }
+    }
+}
+
+impl<T, N> From<PlanError<T, N>> for Error
+where
+    T: fmt::Debug + Send + Sync + 'static,
+    N: fmt::Debug + Send + Sync + 'static,
+{
+    fn from(err: PlanError<T, N>) -> Self {
+        Error::InvalidPlan(Box::new(err))
+    }
+}
+
+impl<T, N> From<OverflowError<T, N>> for Error {
+    fn from(_: OverflowError<T, N>) -> Self {
+        Error::Overflow
+    }
+}
+
+impl<T, E> From<KeyConversionError<T, E>> for Error
+where
+    T: fmt::Debug + Send + Sync + 'static,
+    E: core::error::Error + Send + Sync + 'static,
+{
+    fn from(err: KeyConversionError<T, E>) -> Self {
+        Error::Conversion(Box::new(err))
+    }
+}
+
+impl<T, N> From<NonZeroCountError<T, N>> for Error
+where
+    T: fmt::Debug + Send + Sync + 'static,
+    N: fmt::Debug + Send + Sync + 'static,
+{
+    fn from(err: NonZeroCountError<T, N>) -> Self {
+        Error::Conversion(Box::new(err))
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<CsvError> for Error {
+    fn from(err: CsvError) -> Self {
+        Error::Parse(Box::new(err))
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<DelimitedError> for Error {
+    fn from(err: DelimitedError) -> Self {
+        Error::Parse(Box::new(err))
+    }
+}
@@ -0,0 +1,77 @@
+//! Counters using fast, non-cryptographic hashers.
+//!
+//! The default hasher resists hash-flooding attacks, at a cost in speed which matters when
+//! counting many small items, such as words or integers. When the items do not come from an
+//! adversary, these aliases name a counter with a faster hasher, without spelling out the
+//! hasher parameter of [`Counter`].
+
+use crate::Counter;
+
+use crate::num::Zero;
+
+use core::hash::Hash;
+
+/// Implements the constructor of a counter with a concrete hasher.
+macro_rules! fast_hash_counter {
+    ($hasher:ty, $new_hasher:expr, $constructor:ident, $alias:literal) => {
+        impl<T, N> Counter<T, N, $hasher>
+        where
+            T: Hash + Eq,
+            N: Zero,
+        {
+            #[doc = concat!("Create a new, empty [`", $alias, "`].")]
+            pub fn $constructor() -> Self {
+                Counter::with_hasher($new_hasher)
+            }
+        }
+    };
+}
+
+/// A [`Counter`] using the `FxHash` algorithm of `rustc-hash`, which is very fast for small
+/// items such as integers, but has no protection against hash flooding.
+///
+/// ```rust
+/// # use counter::{Counter, FxCounter};
+/// let counter = "abbccc".chars().collect::<FxCounter<_>>();
+/// assert_eq!(counter[&'c'], 3);
+///
+/// let mut counter: FxCounter<u64> = Counter::with_fx_hasher();
+/// counter.update([1, 2, 2]);
+/// assert_eq!(counter[&2], 2);
+/// ```
+#[cfg(feature = "rustc-hash")]
+pub type FxCounter<T, N = usize> = Counter<T, N, rustc_hash::FxBuildHasher>;
+
+#[cfg(feature = "rustc-hash")]
+fast_hash_counter!(
+    rustc_hash::FxBuildHasher,
+    rustc_hash::FxBuildHasher,
+    with_fx_hasher,
+    "FxCounter"
+);
+
+/// A [`Counter`] using `ahash`, which is fast for items of any size.
+///
+/// With the `std` feature, each counter's hasher is randomly seeded. Without it, the seeds are
+/// fixed, and only [`with_ahasher`](Counter::with_ahasher) can create the counter, because
+/// `ahash` does not then implement [`Default`] for its hasher.
+///
+/// ```rust
+/// # use counter::{AHashCounter, Counter};
+/// let counter = "the cat and the hat".split(' ').collect::<AHashCounter<_>>();
+/// assert_eq!(counter[&"the"], 2);
+///
+/// let mut counter: AHashCounter<&str> = Counter::with_ahasher();
+/// counter.update(["a", "b", "a"]);
+/// assert_eq!(counter[&"a"], 2);
+/// ```
+#[cfg(feature = "ahash")]
+pub type AHashCounter<T, N = usize> = Counter<T, N, ahash::RandomState>;
+
+#[cfg(feature = "ahash")]
+fast_hash_counter!(
+    ahash::RandomState,
+    ahash::RandomState::new(),
+    with_ahasher,
+    "AHashCounter"
+);
@@ -0,0 +1,113 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, MulAssign};
+
+/// Sums any number of counters into one.
+///
+/// This is the reduce step of hierarchical counting, such as merging the counters of each
+/// shard of a job or each value of a `HashMap<K, Counter<T>>`. The largest counter is reused as
+/// the result, so its items are not rehashed, and the map is reserved for the others up front.
+///
+/// ```rust
+/// # use counter::Counter;
+/// # use std::collections::HashMap;
+/// let mut by_author = HashMap::new();
+/// by_author.insert("alice", "abb".chars().collect::<Counter<_>>());
+/// by_author.insert("bob", "bcc".chars().collect::<Counter<_>>());
+///
+/// let total = counter::flatten(by_author.into_values());
+/// assert_eq!(total, "abbbcc".chars().collect());
+/// ```
+pub fn flatten<T, N, S, I>(counters: I) -> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher + Default,
+    I: IntoIterator<Item = Counter<T, N, S>>,
+{
+    let mut counters: Vec<_> = counters.into_iter().collect();
+    let Some(mut total) = take_largest(&mut counters, |counter| counter.map.len()) else {
+        return Counter {
+            map: HashMap::default(),
+            zero: N::zero(),
+        };
+    };
+    reserve_for(&mut total, counters.iter().map(|counter| counter.map.len()));
+    for counter in counters {
+        for (item, count) in counter.map {
+            *total.map.entry(item).or_insert_with(N::zero) += count;
+        }
+    }
+    total
+}
+
+/// Sums any number of counters into one, multiplying the counts of each by its weight.
+///
+/// Items whose weighted count is zero are left out. See [`flatten`].
+///
+/// ```rust
+/// # use counter::Counter;
+/// let daily = "aab".chars().collect::<Counter<_>>();
+/// let weekend = "bc".chars().collect::<Counter<_>>();
+///
+/// let week = counter::flatten_weighted([(daily, 5), (weekend, 2)]);
+/// assert_eq!(week[&'a'], 10);
+/// assert_eq!(week[&'b'], 7);
+/// assert_eq!(week[&'c'], 2);
+/// ```
+pub fn flatten_weighted<T, N, S, I>(counters: I) -> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + MulAssign + Zero + Clone,
+    S: BuildHasher + Default,
+    I: IntoIterator<Item = (Counter<T, N, S>, N)>,
+{
+    let mut counters: Vec<_> = counters.into_iter().collect();
+    let Some((largest, weight)) = take_largest(&mut counters, |(counter, _)| counter.map.len())
+    else {
+        return Counter {
+            map: HashMap::default(),
+            zero: N::zero(),
+        };
+    };
+    let mut total = largest * weight;
+    reserve_for(
+        &mut total,
+        counters.iter().map(|(counter, _)| counter.map.len()),
+    );
+    for (counter, weight) in counters {
+        for (item, mut count) in counter.map {
+            count *= weight.clone();
+            if !count.is_zero() {
+                *total.map.entry(item).or_insert_with(N::zero) += count;
+            }
+        }
+    }
+    total
+}
+
+/// Removes and returns the element of `items` with the most items, without shifting the rest.
+fn take_largest<E>(items: &mut Vec<E>, len: impl Fn(&E) -> usize) -> Option<E> {
+    let largest = (0..items.len()).max_by_key(|&i| len(&items[i]))?;
+    Some(items.swap_remove(largest))
+}
+
+/// Reserves room for incoming counters of the given lengths, assuming, as `HashMap::extend`
+/// does for a non-empty map, that about half of their items are new.
+fn reserve_for<T, N, S>(total: &mut Counter<T, N, S>, lengths: impl Iterator<Item = usize>)
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    let incoming: usize = lengths.sum();
+    if total.map.is_empty() {
+        total.map.reserve(incoming);
+    } else {
+        total.map.reserve(incoming.div_ceil(2));
+    }
+}
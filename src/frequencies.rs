@@ -0,0 +1,135 @@
+use crate::collections::HashMap;
+use crate::{Counter, OrderBy};
+
+use crate::num::ToPrimitive;
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+
+/// Converts a count to `f64`, mapping counts which cannot be represented to NaN.
+pub(crate) fn count_to_f64<N: ToPrimitive>(count: &N) -> f64 {
+    count.to_f64().unwrap_or(f64::NAN)
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+{
+    /// Returns the sum of the counts as an `f64`, the denominator of every frequency.
+    fn total_f64(&self) -> f64 {
+        self.map.values().map(count_to_f64).sum()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// Returns the relative frequency of `key`: its count divided by the sum of all counts.
+    ///
+    /// Missing keys have a probability of `0.0`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aaab".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.probability(&'a'), 0.75);
+    /// assert_eq!(counter.probability(&'z'), 0.0);
+    /// ```
+    pub fn probability<Q>(&self, key: &Q) -> f64
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(count) => count_to_f64(count) / self.total_f64(),
+            None => 0.0,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: ToPrimitive,
+    S: BuildHasher + Clone,
+{
+    /// Returns a map from each item to its relative frequency: its count divided by the sum of
+    /// all counts.
+    ///
+    /// The frequencies sum to one, up to rounding. Counts which cannot be represented as an
+    /// `f64` produce NaN.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbb".chars().collect::<Counter<_>>();
+    /// let frequencies = counter.frequencies();
+    /// assert_eq!(frequencies[&'a'], 0.25);
+    /// assert_eq!(frequencies[&'b'], 0.75);
+    /// ```
+    pub fn frequencies(&self) -> HashMap<T, f64, S> {
+        let total = self.total_f64();
+        let mut frequencies =
+            HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        frequencies.extend(
+            self.map
+                .iter()
+                .map(|(item, count)| (item.clone(), count_to_f64(count) / total)),
+        );
+        frequencies
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: Clone + Ord + ToPrimitive,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted from most to least common, where
+    /// each frequency is the count divided by the sum of all counts.
+    ///
+    /// Items are ordered as by [`most_common_ordered`](Counter::most_common_ordered).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// let frequencies = counter.most_common_frequencies();
+    /// assert_eq!(frequencies[0], ('a', 5.0 / 11.0));
+    /// assert_eq!(frequencies[1], ('b', 2.0 / 11.0));
+    /// ```
+    pub fn most_common_frequencies(&self) -> Vec<(T, f64)> {
+        let total = self.total_f64();
+        self.to_ordered_pairs(OrderBy::CountDesc, None)
+            .into_iter()
+            .map(|(item, count)| (item, count_to_f64(&count) / total))
+            .collect()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Hash + Eq + Clone,
+{
+    /// Returns a counter of the counts: how many items were seen once, how many twice, and so
+    /// on.
+    ///
+    /// This frequency-of-frequencies table is the basis of Good–Turing smoothing and of
+    /// checking whether items follow Zipf's law.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let char_counts = "mississippi".chars().collect::<Counter<_>>();
+    /// let count_of_counts = char_counts.count_of_counts();
+    /// // 'm' is seen once, 'p' twice, and 'i' and 's' four times each.
+    /// assert_eq!(count_of_counts[&1], 1);
+    /// assert_eq!(count_of_counts[&2], 1);
+    /// assert_eq!(count_of_counts[&4], 2);
+    /// ```
+    pub fn count_of_counts(&self) -> Counter<N> {
+        self.map.values().cloned().collect()
+    }
+}
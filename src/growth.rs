@@ -0,0 +1,206 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::{ToPrimitive, Zero};
+
+use core::hash::{BuildHasher, Hash};
+use core::ops::AddAssign;
+
+/// When a [`Counter`] reserves and releases memory for its items.
+///
+/// A counter built up to millions of items and then pruned to a few thousand keeps the
+/// capacity it grew to, because hash maps never shrink on their own. A `GrowthPolicy` sets the
+/// capacity a counter starts with, and how sparse it may become before the excess is released.
+///
+/// The pruning methods each have a variant taking a policy, such as
+/// [`retain_per_policy`](Counter::retain_per_policy), which shrinks the counter as the policy
+/// allows once it has been pruned. [`shrink_per_policy`](Counter::shrink_per_policy) applies a
+/// policy after any other change.
+///
+/// The growth factor and maximum load factor are properties of the hash table backing the
+/// counter, which does not let them be configured: it grows by doubling its number of buckets,
+/// and keeps them at most seven-eighths full.
+///
+/// ```rust
+/// # use counter::{Counter, GrowthPolicy};
+/// let policy = GrowthPolicy::new().initial_capacity(64).shrink_below(0.25);
+///
+/// let mut counter = Counter::<u32>::with_growth_policy(&policy);
+/// assert!(counter.capacity() >= 64);
+///
+/// counter.update(0..10_000);
+/// let grown = counter.capacity();
+/// counter.retain_per_policy(&policy, |&item, _| item % 1000 == 0);
+/// assert!(counter.capacity() >= 64);
+/// assert!(counter.capacity() < grown / 10);
+/// ```
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GrowthPolicy {
+    initial_capacity: usize,
+    shrink_below: f64,
+}
+
+impl GrowthPolicy {
+    /// Create the default policy: no capacity is reserved up front, and counters shrink once
+    /// fewer than a quarter of their capacity is in use.
+    pub const fn new() -> Self {
+        GrowthPolicy {
+            initial_capacity: 0,
+            shrink_below: 0.25,
+        }
+    }
+
+    /// Reserve space for `capacity` distinct items when a counter is created. Counters are also
+    /// never shrunk below this capacity.
+    pub const fn initial_capacity(mut self, capacity: usize) -> Self {
+        self.initial_capacity = capacity;
+        self
+    }
+
+    /// Shrink a counter once the fraction of its capacity in use falls below `fraction`.
+    ///
+    /// A fraction of `0.0` never shrinks; a fraction of `1.0` shrinks whenever there is any
+    /// excess capacity at all.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not between 0 and 1.
+    pub fn shrink_below(mut self, fraction: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "shrink fraction must be between 0 and 1"
+        );
+        self.shrink_below = fraction;
+        self
+    }
+}
+
+impl Default for GrowthPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: BuildHasher + Default,
+{
+    /// Create a new, empty `Counter` with the initial capacity of `policy`.
+    pub fn with_growth_policy(policy: &GrowthPolicy) -> Self {
+        Counter {
+            map: HashMap::with_capacity_and_hasher(policy.initial_capacity, S::default()),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Reserves room for at least `additional` more distinct items, whatever their counts.
+    ///
+    /// Counting an item already in the counter never allocates, so reserve for the number of
+    /// new items expected, not the number of observations.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abc".chars().collect::<Counter<_>>();
+    /// counter.reserve(100);
+    /// assert!(counter.capacity() >= 103);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Shrinks the capacity to fit the distinct items in the counter, releasing the memory
+    /// kept after pruning.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = (0..1000).collect::<Counter<_>>();
+    /// counter.retain_keys(|&item| item < 10);
+    /// counter.shrink_to_fit();
+    /// assert!(counter.capacity() < 1000);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Releases excess capacity if less of it is in use than `policy` allows, typically after
+    /// pruning. Returns the counter, so it can end a chain of pruning steps.
+    ///
+    /// The counter is shrunk to fit its items, but never below the policy's initial capacity.
+    pub fn shrink_per_policy(&mut self, policy: &GrowthPolicy) -> &mut Self {
+        let capacity = self.map.capacity();
+        let in_use = self.map.len() as f64 / capacity.max(1) as f64;
+        if capacity > policy.initial_capacity && in_use < policy.shrink_below {
+            trace_span!("shrink_per_policy", len = self.map.len(), capacity);
+            self.map
+                .shrink_to(self.map.len().max(policy.initial_capacity));
+            trace_event!(capacity = self.map.capacity(), "shrank counter");
+        }
+        self
+    }
+
+    /// Retain only the items for which the predicate returns `true`, as
+    /// [`retain`](Counter::retain) does, then release excess capacity as `policy` allows.
+    pub fn retain_per_policy<F>(&mut self, policy: &GrowthPolicy, predicate: F) -> &mut Self
+    where
+        F: FnMut(&T, &mut N) -> bool,
+    {
+        self.retain(predicate).shrink_per_policy(policy)
+    }
+
+    /// Retain only the items which satisfy the predicate, as
+    /// [`retain_keys`](Counter::retain_keys) does, then release excess capacity as `policy`
+    /// allows.
+    pub fn retain_keys_per_policy<F>(&mut self, policy: &GrowthPolicy, predicate: F) -> &mut Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_keys(predicate).shrink_per_policy(policy)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd,
+    S: BuildHasher,
+{
+    /// Consume this counter, keeping only the items with a count of at least `min` as
+    /// [`min_count`](Counter::min_count) does, then release excess capacity as `policy` allows.
+    pub fn min_count_per_policy(self, min: N, policy: &GrowthPolicy) -> Self {
+        let mut counter = self.min_count(min);
+        counter.shrink_per_policy(policy);
+        counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + AddAssign + Zero + Clone + ToPrimitive,
+    S: BuildHasher,
+{
+    /// Remove the least common items, as
+    /// [`prune_tail_fraction`](Counter::prune_tail_fraction) does, then release excess
+    /// capacity as `policy` allows. Returns the total count removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not between 0 and 1.
+    pub fn prune_tail_fraction_per_policy(&mut self, fraction: f64, policy: &GrowthPolicy) -> N {
+        let removed = self.prune_tail_fraction(fraction);
+        self.shrink_per_policy(policy);
+        removed
+    }
+}
@@ -1,14 +1,24 @@
 mod add_iterable;
 mod add_self;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "borsh")]
+mod borsh;
 mod create;
 mod deref;
 mod extend;
 mod from_iterator;
+mod from_pairs;
 mod index;
 mod intersection;
 mod into_iterator;
+mod merge;
+mod neg;
+mod retain;
+mod scale;
 #[cfg(feature = "serde")]
 mod serialize;
 mod sub_iterable;
 mod sub_self;
+mod symmetric_difference;
 mod union;
@@ -1,16 +1,17 @@
 use crate::Counter;
 
-use num_traits::Zero;
+use crate::num::Zero;
 
-use std::hash::Hash;
-use std::ops::{Add, AddAssign};
+use core::hash::{BuildHasher, Hash};
+use core::ops::{Add, AddAssign};
 
-impl<T, N> Add for Counter<T, N>
+impl<T, N, S, S2> Add<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Clone + Hash + Eq,
     N: AddAssign + Zero,
+    S: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
     /// Add two counters together.
     ///
@@ -27,16 +28,17 @@ where
     /// let expect = [('a', 4), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn add(mut self, rhs: Counter<T, N>) -> Self::Output {
+    fn add(mut self, rhs: Counter<T, N, S2>) -> Self::Output {
         self += rhs;
         self
     }
 }
 
-impl<T, N> AddAssign for Counter<T, N>
+impl<T, N, S, S2> AddAssign<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: Zero + AddAssign,
+    S: BuildHasher,
 {
     /// Add another counter to this counter.
     ///
@@ -53,7 +55,13 @@ where
     /// let expect = [('a', 4), ('b', 3)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(c.into_map(), expect);
     /// ```
-    fn add_assign(&mut self, rhs: Self) {
+    fn add_assign(&mut self, rhs: Counter<T, N, S2>) {
+        trace_span!(
+            self.map.len() + rhs.map.len();
+            "add_assign",
+            len = self.map.len(),
+            other_len = rhs.map.len()
+        );
         for (key, value) in rhs.map {
             let entry = self.map.entry(key).or_insert_with(N::zero);
             *entry += value;
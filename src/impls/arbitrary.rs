@@ -0,0 +1,110 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash};
+
+/// Generates a counter of arbitrary items with positive counts, like those built by counting.
+///
+/// Each count is an arbitrary value of `N`, or one if that value is zero or negative, so every
+/// generated item is kept and the number of items follows the input as it does for a
+/// `HashMap`. To generate any counts, for code which must handle them, use
+/// [`Counter::arbitrary_with_any_counts`].
+impl<'a, T, N, S> Arbitrary<'a> for Counter<T, N, S>
+where
+    T: Arbitrary<'a> + Hash + Eq,
+    N: Arbitrary<'a> + PartialOrd + Zero + One,
+    S: BuildHasher + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(positive_counts(HashMap::arbitrary(u)?))
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        Ok(positive_counts(HashMap::arbitrary_take_rest(u)?))
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        <HashMap<T, N, S> as Arbitrary<'a>>::size_hint(depth)
+    }
+}
+
+impl<'a, T, N, S> Counter<T, N, S>
+where
+    T: Arbitrary<'a> + Hash + Eq,
+    N: Arbitrary<'a> + PartialOrd + Zero + One,
+    S: BuildHasher + Default,
+{
+    /// Generate an arbitrary counter with at most `max_len` distinct items, all with positive
+    /// counts, which are generated as for [`Arbitrary`].
+    ///
+    /// This is useful in fuzz targets whose cost grows with the size of the counter.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use arbitrary::Unstructured;
+    /// let mut u = Unstructured::new(&[0xff; 1024]);
+    /// let counter = Counter::<u8, u16>::arbitrary_with_max_len(&mut u, 3).unwrap();
+    /// assert!(counter.len() <= 3);
+    /// ```
+    pub fn arbitrary_with_max_len(u: &mut Unstructured<'a>, max_len: usize) -> Result<Self> {
+        Ok(positive_counts(
+            Self::arbitrary_with_any_counts(u, max_len)?.map,
+        ))
+    }
+}
+
+/// Replaces the counts which are not positive by one.
+fn positive_counts<T, N, S>(mut map: HashMap<T, N, S>) -> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero + One,
+{
+    for count in map.values_mut() {
+        // NaN, for floats, is not positive either.
+        if (*count).partial_cmp(&N::zero()) != Some(Ordering::Greater) {
+            *count = N::one();
+        }
+    }
+    Counter {
+        map,
+        zero: N::zero(),
+    }
+}
+
+impl<'a, T, N, S> Counter<T, N, S>
+where
+    T: Arbitrary<'a> + Hash + Eq,
+    N: Arbitrary<'a> + Zero,
+    S: BuildHasher + Default,
+{
+    /// Generate an arbitrary counter with at most `max_len` distinct items, whose counts are
+    /// arbitrary values of `N`, so they may be zero or, for signed types, negative.
+    ///
+    /// Use this to fuzz code which must cope with any counter, such as one built with
+    /// [`signed_sub`](Counter::signed_sub).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use arbitrary::Unstructured;
+    /// let mut u = Unstructured::new(&[0x80; 1024]);
+    /// let counter = Counter::<u8, i8>::arbitrary_with_any_counts(&mut u, usize::MAX).unwrap();
+    /// assert!(counter.values().all(|&count| count < 0));
+    /// ```
+    pub fn arbitrary_with_any_counts(u: &mut Unstructured<'a>, max_len: usize) -> Result<Self> {
+        let len = u.arbitrary_len::<(T, N)>()?.min(max_len);
+        let mut map = HashMap::with_capacity_and_hasher(len, S::default());
+        for _ in 0..len {
+            let (item, count) = <(T, N)>::arbitrary(u)?;
+            map.insert(item, count);
+        }
+        Ok(Counter {
+            map,
+            zero: N::zero(),
+        })
+    }
+}
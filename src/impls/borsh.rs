@@ -0,0 +1,81 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use ::borsh::io::{Error, ErrorKind, Read, Result, Write};
+use ::borsh::{BorshDeserialize, BorshSerialize};
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+/// Serializes the way borsh serializes a map: the number of items as a `u32`, then each
+/// `(item, count)` pair in increasing order of the items.
+///
+/// The order is canonical, so equal counters always serialize to the same bytes, and a counter
+/// can be read back as a `BTreeMap<T, N>` or `HashMap<T, N>`.
+///
+/// ```rust
+/// # use counter::Counter;
+/// # use std::collections::BTreeMap;
+/// let counter = ["b", "a", "b"].map(String::from).into_iter().collect::<Counter<_, u64>>();
+/// let bytes = borsh::to_vec(&counter).unwrap();
+///
+/// let map = borsh::from_slice::<BTreeMap<String, u64>>(&bytes).unwrap();
+/// assert_eq!(map, BTreeMap::from([("a".into(), 1), ("b".into(), 2)]));
+/// assert_eq!(borsh::from_slice::<Counter<String, u64>>(&bytes).unwrap(), counter);
+/// ```
+impl<T, N, S> BorshSerialize for Counter<T, N, S>
+where
+    T: BorshSerialize + Hash + Eq + Ord,
+    N: BorshSerialize,
+{
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let mut pairs = self.map.iter().collect::<Vec<_>>();
+        pairs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+        u32::try_from(pairs.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "too many items to serialize"))?
+            .serialize(writer)?;
+        for pair in pairs {
+            pair.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes from the canonical form written by [`BorshSerialize`]: the items must be in
+/// strictly increasing order, so input with an item out of order or repeated is rejected with
+/// [`ErrorKind::InvalidData`], rather than read in a way which would not round-trip.
+impl<T, N, S> BorshDeserialize for Counter<T, N, S>
+where
+    T: BorshDeserialize + Hash + Eq + Ord,
+    N: BorshDeserialize + Zero,
+    S: BuildHasher + Default,
+{
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)? as usize;
+        // Don't trust an untrusted length with an arbitrarily large allocation.
+        let mut map = HashMap::with_capacity_and_hasher(len.min(4096), S::default());
+        // The last pair read is held back to check the order of the next.
+        let mut last: Option<(T, N)> = None;
+        for _ in 0..len {
+            let pair = <(T, N)>::deserialize_reader(reader)?;
+            if last.as_ref().is_some_and(|(item, _)| *item >= pair.0) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "counter items are not in strictly increasing order",
+                ));
+            }
+            if let Some((item, count)) = last.replace(pair) {
+                map.insert(item, count);
+            }
+        }
+        if let Some((item, count)) = last {
+            map.insert(item, count);
+        }
+        Ok(Counter {
+            map,
+            zero: N::zero(),
+        })
+    }
+}
@@ -1,9 +1,9 @@
+use crate::collections::HashMap;
 use crate::Counter;
 
-use num_traits::Zero;
+use crate::num::Zero;
 
-use std::collections::HashMap;
-use std::hash::Hash;
+use core::hash::{BuildHasher, Hash};
 
 impl<T, N> Counter<T, N>
 where
@@ -31,10 +31,81 @@ where
     }
 }
 
-impl<T, N> Default for Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `Counter` with the given hasher and zero count, in a `const`
+    /// context.
+    ///
+    /// This lets a counter live in a `static`, such as a metrics registry, without a lazy
+    /// initialization wrapper. The hasher must be constructible in a `const` context, as
+    /// [`SeededState`](crate::SeededState) and [`BuildHasherDefault`] are, and the zero count is
+    /// passed explicitly because `Zero::zero` cannot be called there.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, SeededState};
+    /// # use std::sync::Mutex;
+    /// static REQUESTS: Mutex<Counter<&str, u64, SeededState>> =
+    ///     Mutex::new(Counter::const_new(SeededState::new(0), 0));
+    ///
+    /// REQUESTS.lock().unwrap()[&"/index.html"] += 1;
+    /// assert_eq!(REQUESTS.lock().unwrap()[&"/index.html"], 1);
+    /// ```
+    ///
+    /// [`BuildHasherDefault`]: core::hash::BuildHasherDefault
+    pub const fn const_new(hasher: S, zero: N) -> Self {
+        Counter {
+            map: HashMap::with_hasher(hasher),
+            zero,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `Counter` which hashes its items with `hasher`.
+    ///
+    /// This accepts a hasher which is configured at runtime, such as one seeded from
+    /// configuration, where [`default`](Default::default) could only build an unconfigured
+    /// one.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, SeededState};
+    /// let seed = 0x5eed;
+    /// let mut counter = Counter::<_, usize, _>::with_hasher(SeededState::new(seed));
+    /// counter.update("abbccc".chars());
+    /// assert_eq!(counter[&'c'], 3);
+    /// assert_eq!(counter.hasher(), &SeededState::new(seed));
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        Counter {
+            map: HashMap::with_hasher(hasher),
+            zero: N::zero(),
+        }
+    }
+
+    /// Create a new, empty `Counter` with room for `capacity` distinct items, which hashes
+    /// its items with `hasher`.
+    ///
+    /// As for [`with_capacity`](Counter::with_capacity), the capacity counts distinct items,
+    /// not the total of their counts.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Counter {
+            map: HashMap::with_capacity_and_hasher(capacity, hasher),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> Default for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: Default,
+    S: Default,
 {
     fn default() -> Self {
         Self {
@@ -43,3 +114,87 @@ where
         }
     }
 }
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Consumes the counter and returns one with the same counts, using a hasher of type `S2`.
+    ///
+    /// Every item is re-hashed into a new map with the default `S2` hasher, so this needs
+    /// neither the items nor the counts to be `Clone`.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, SeededState};
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let seeded = counter.into_hasher::<SeededState>();
+    /// assert_eq!(seeded[&'c'], 3);
+    /// ```
+    pub fn into_hasher<S2>(self) -> Counter<T, N, S2>
+    where
+        S2: BuildHasher + Default,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), S2::default());
+        map.extend(self.map);
+        Counter {
+            map,
+            zero: self.zero,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Clone,
+{
+    /// Returns a copy of the counter, using a hasher of type `S2`.
+    ///
+    /// This is [`into_hasher`](Counter::into_hasher) without giving up the original.
+    pub fn clone_into_hasher<S2>(&self) -> Counter<T, N, S2>
+    where
+        S2: BuildHasher + Default,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), S2::default());
+        map.extend(
+            self.map
+                .iter()
+                .map(|(item, count)| (item.clone(), count.clone())),
+        );
+        Counter {
+            map,
+            zero: self.zero.clone(),
+        }
+    }
+
+    /// Returns a copy of the counter with room for at least `capacity` distinct items.
+    ///
+    /// [`clone`](Clone::clone) keeps the capacity of the original, which may be far more than
+    /// a pruned counter needs, or less than it is about to grow to.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let copy = counter.clone_with_capacity(100);
+    /// assert!(copy.capacity() >= 100);
+    /// assert_eq!(copy, counter);
+    /// ```
+    pub fn clone_with_capacity(&self, capacity: usize) -> Self
+    where
+        S: BuildHasher + Clone,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(
+            capacity.max(self.map.len()),
+            self.map.hasher().clone(),
+        );
+        map.extend(
+            self.map
+                .iter()
+                .map(|(item, count)| (item.clone(), count.clone())),
+        );
+        Counter {
+            map,
+            zero: self.zero.clone(),
+        }
+    }
+}
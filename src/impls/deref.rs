@@ -1,26 +1,26 @@
+use crate::collections::HashMap;
 use crate::Counter;
 
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::ops::{Deref, DerefMut};
+use core::hash::Hash;
+use core::ops::{Deref, DerefMut};
 
-type CounterMap<T, N> = HashMap<T, N>;
+type CounterMap<T, N, S> = HashMap<T, N, S>;
 
-impl<T, N> Deref for Counter<T, N>
+impl<T, N, S> Deref for Counter<T, N, S>
 where
     T: Hash + Eq,
 {
-    type Target = CounterMap<T, N>;
-    fn deref(&self) -> &CounterMap<T, N> {
+    type Target = CounterMap<T, N, S>;
+    fn deref(&self) -> &CounterMap<T, N, S> {
         &self.map
     }
 }
 
-impl<T, N> DerefMut for Counter<T, N>
+impl<T, N, S> DerefMut for Counter<T, N, S>
 where
     T: Hash + Eq,
 {
-    fn deref_mut(&mut self) -> &mut CounterMap<T, N> {
+    fn deref_mut(&mut self) -> &mut CounterMap<T, N, S> {
         &mut self.map
     }
 }
@@ -1,15 +1,16 @@
 use crate::Counter;
 
-use num_traits::{One, Zero};
+use crate::num::{One, Zero};
 
-use std::hash::Hash;
-use std::iter;
-use std::ops::AddAssign;
+use core::hash::{BuildHasher, Hash};
+use core::iter;
+use core::ops::AddAssign;
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
     N: AddAssign + Zero + One,
+    S: BuildHasher + Default,
 {
     /// Create a new `Counter` initialized with the given iterable.
     #[deprecated = "prefer the `FromIterator`/`collect` interface"]
@@ -21,10 +22,36 @@ where
     }
 }
 
-impl<T, N> iter::FromIterator<T> for Counter<T, N>
+impl<T, N> Counter<T, N>
 where
     T: Hash + Eq,
     N: AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the keys `key` extracts from the elements of the given
+    /// iterable, as by [`update_by`](Counter::update_by).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = ["apple", "avocado", "banana", "blueberry", "cherry"];
+    /// let by_initial = Counter::<char>::count_by(words, |word| word.chars().next().unwrap());
+    /// assert_eq!(by_initial, Counter::from([('a', 2), ('b', 2), ('c', 1)]));
+    /// ```
+    pub fn count_by<I, F>(iterable: I, key: F) -> Self
+    where
+        I: IntoIterator,
+        F: FnMut(&I::Item) -> T,
+    {
+        let mut counter = Self::new();
+        counter.update_by(iterable, key);
+        counter
+    }
+}
+
+impl<T, N, S> iter::FromIterator<T> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+    S: BuildHasher + Default,
 {
     /// Produce a `Counter` from an iterator of items. This is called automatically
     /// by [`Iterator::collect()`].
@@ -41,20 +68,23 @@ where
     /// ```
     ///
     fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
-        let mut counter = Counter::new();
+        let mut counter = Counter::with_hasher(S::default());
         counter.update(iterable);
         counter
     }
 }
 
-impl<T, N> iter::FromIterator<(T, N)> for Counter<T, N>
+impl<T, N, S> iter::FromIterator<(T, N)> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: AddAssign + Zero,
+    S: BuildHasher + Default,
 {
     /// Creates a counter from `(item, count)` tuples.
     ///
-    /// The counts of duplicate items are summed.
+    /// The counts of duplicate items are summed. The counter reserves space for the lower bound
+    /// of the iterator's [`size_hint`](Iterator::size_hint) up front.
+    ///
     /// ```rust
     /// # use counter::Counter;
     /// # use std::collections::HashMap;
@@ -65,11 +95,8 @@ where
     /// assert_eq!(counter.into_map(), expect);
     /// ```
     fn from_iter<I: IntoIterator<Item = (T, N)>>(iter: I) -> Self {
-        let mut cnt = Counter::new();
-        for (item, item_count) in iter {
-            let entry = cnt.map.entry(item).or_insert_with(N::zero);
-            *entry += item_count;
-        }
-        cnt
+        let iter = iter.into_iter();
+        let (capacity, _) = iter.size_hint();
+        Self::from_pairs(iter, capacity)
     }
 }
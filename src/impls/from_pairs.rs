@@ -0,0 +1,143 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use core::ops::AddAssign;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    pub(super) fn from_pairs<I>(pairs: I, capacity: usize) -> Self
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        let mut map = HashMap::with_capacity_and_hasher(capacity, S::default());
+        for (item, count) in pairs {
+            let entry = map.entry(item).or_insert_with(N::zero);
+            *entry += count;
+        }
+        Counter {
+            map,
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Creates a counter from pre-aggregated `(item, count)` pairs, in which each item appears
+    /// only once, such as a dump of [`most_common_ordered`](Counter::most_common_ordered).
+    ///
+    /// Unlike collecting the pairs, this does not look up each item to add to its existing
+    /// count: every pair is inserted directly. If an item does appear more than once, its last
+    /// count replaces the earlier ones rather than being added to them; debug builds panic
+    /// instead. The order of the pairs does not affect the result, but sorted dumps are the
+    /// typical source.
+    ///
+    /// # Time complexity
+    ///
+    /// This makes one hash and one insertion per pair, in expected *O*(*n*) time overall. Space
+    /// for the lower bound of the iterator's [`size_hint`](Iterator::size_hint) is reserved up
+    /// front, so exact-sized sources such as vectors allocate only once.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let dump = vec![("apple", 12), ("banana", 7), ("cherry", 3)];
+    /// let counter = Counter::<_>::from_sorted_pairs_unchecked(dump);
+    /// assert_eq!(counter[&"banana"], 7);
+    /// assert_eq!(counter.len(), 3);
+    /// ```
+    pub fn from_sorted_pairs_unchecked<I>(sorted_pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        let pairs = sorted_pairs.into_iter();
+        let (capacity, _) = pairs.size_hint();
+        let mut map = HashMap::with_capacity_and_hasher(capacity, Default::default());
+        for (item, count) in pairs {
+            let previous = map.insert(item, count);
+            debug_assert!(
+                previous.is_none(),
+                "from_sorted_pairs_unchecked given a duplicate item"
+            );
+        }
+        Counter {
+            map,
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> From<Vec<(T, N)>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    /// Creates a counter from a vector of `(item, count)` pairs, such as the output of
+    /// [`most_common_ordered`](Counter::most_common_ordered).
+    ///
+    /// The counts of duplicate items are summed.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// let top: Counter<_> = counter.k_most_common_ordered(2).into();
+    /// assert_eq!(top, "aaaaabb".chars().collect());
+    /// ```
+    fn from(pairs: Vec<(T, N)>) -> Self {
+        let capacity = pairs.len();
+        Self::from_pairs(pairs, capacity)
+    }
+}
+
+impl<T, N, S> From<&[(T, N)]> for Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + Clone,
+    S: BuildHasher + Default,
+{
+    /// Creates a counter from a slice of `(item, count)` pairs.
+    ///
+    /// The counts of duplicate items are summed.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let pairs = [('a', 1), ('b', 2), ('a', 3)];
+    /// let counter = Counter::<_>::from(&pairs[..]);
+    /// assert_eq!(counter[&'a'], 4);
+    /// assert_eq!(counter[&'b'], 2);
+    /// ```
+    fn from(pairs: &[(T, N)]) -> Self {
+        Self::from_pairs(pairs.iter().cloned(), pairs.len())
+    }
+}
+
+impl<T, N, S, const M: usize> From<[(T, N); M]> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+    S: BuildHasher + Default,
+{
+    /// Creates a counter from an array of `(item, count)` pairs.
+    ///
+    /// The counts of duplicate items are summed.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_>::from([('a', 1), ('b', 2)]);
+    /// assert_eq!(counter, "abb".chars().collect());
+    /// ```
+    fn from(pairs: [(T, N); M]) -> Self {
+        Self::from_pairs(pairs, M)
+    }
+}
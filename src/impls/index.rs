@@ -1,16 +1,19 @@
 use crate::Counter;
 
-use num_traits::Zero;
+use crate::num::Zero;
 
-use std::borrow::Borrow;
-use std::hash::Hash;
-use std::ops::{Index, IndexMut};
+use alloc::borrow::ToOwned;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{Index, IndexMut};
 
-impl<T, Q, N> Index<&'_ Q> for Counter<T, N>
+impl<T, Q, N, S> Index<&'_ Q> for Counter<T, N, S>
 where
     T: Hash + Eq + Borrow<Q>,
     Q: Hash + Eq,
     N: Zero,
+    S: BuildHasher,
 {
     type Output = N;
 
@@ -48,11 +51,12 @@ where
     }
 }
 
-impl<T, Q, N> IndexMut<&'_ Q> for Counter<T, N>
+impl<T, Q, N, S> IndexMut<&'_ Q> for Counter<T, N, S>
 where
     T: Hash + Eq + Borrow<Q>,
     Q: Hash + Eq + ToOwned<Owned = T>,
     N: Zero,
+    S: BuildHasher,
 {
     /// Index in mutable contexts.
     ///
@@ -89,3 +93,39 @@ where
         self.map.entry(key.to_owned()).or_insert_with(N::zero)
     }
 }
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Clone,
+    S: BuildHasher,
+{
+    /// Look up the counts of many keys at once, in the order the keys are given.
+    ///
+    /// As with indexing, missing keys have a count of zero.
+    ///
+    /// ```
+    /// # use counter::Counter;
+    /// let counter = "the cat and the hat".split_whitespace().collect::<Counter<_>>();
+    /// let features = counter.counts_for(["the", "cat", "dog"].iter());
+    /// assert_eq!(features, vec![2, 1, 0]);
+    /// ```
+    ///
+    /// Keys may be any borrowed form of the counter's key type:
+    ///
+    /// ```
+    /// # use counter::Counter;
+    /// let counter = ["a", "b", "a"].into_iter().map(String::from).collect::<Counter<_>>();
+    /// assert_eq!(counter.counts_for(["a", "z"]), vec![2, 0]);
+    /// ```
+    pub fn counts_for<'a, Q, I>(&self, keys: I) -> Vec<N>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized + 'a,
+        I: IntoIterator<Item = &'a Q>,
+    {
+        keys.into_iter()
+            .map(|key| self.map.get(key).unwrap_or(&self.zero).clone())
+            .collect()
+    }
+}
@@ -1,16 +1,18 @@
 use crate::Counter;
 
-use num_traits::Zero;
+use crate::num::Zero;
 
-use std::hash::Hash;
-use std::ops::{BitAnd, BitAndAssign};
+use core::hash::{BuildHasher, Hash};
+use core::ops::{BitAnd, BitAndAssign};
 
-impl<T, N> BitAnd for Counter<T, N>
+impl<T, N, S, S2> BitAnd<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: Ord + Zero,
+    S: BuildHasher,
+    S2: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
     /// Returns the intersection of `self` and `rhs` as a new `Counter`.
     ///
@@ -27,24 +29,25 @@ where
     /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn bitand(self, mut rhs: Counter<T, N>) -> Self::Output {
-        use std::cmp::min;
-
-        let mut counter = Counter::new();
-        for (key, lhs_count) in self.map {
-            if let Some(rhs_count) = rhs.remove(&key) {
-                let count = min(lhs_count, rhs_count);
-                counter.map.insert(key, count);
+    fn bitand(mut self, mut rhs: Counter<T, N, S2>) -> Self::Output {
+        self.map.retain(|key, lhs_count| match rhs.map.remove(key) {
+            Some(rhs_count) => {
+                if rhs_count < *lhs_count {
+                    *lhs_count = rhs_count;
+                }
+                true
             }
-        }
-        counter
+            None => false,
+        });
+        self
     }
 }
 
-impl<T, N> BitAndAssign for Counter<T, N>
+impl<T, N, S, S2> BitAndAssign<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: Ord + Zero,
+    S: BuildHasher,
 {
     /// Updates `self` with the intersection of `self` and `rhs`
     ///
@@ -61,7 +64,7 @@ where
     /// let expect = [('a', 1), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(c.into_map(), expect);
     /// ```
-    fn bitand_assign(&mut self, mut rhs: Counter<T, N>) {
+    fn bitand_assign(&mut self, mut rhs: Counter<T, N, S2>) {
         for (key, rhs_count) in rhs.drain() {
             if rhs_count < self[&key] {
                 self.map.insert(key, rhs_count);
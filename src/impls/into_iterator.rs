@@ -1,25 +1,25 @@
 use crate::Counter;
 
-use std::hash::Hash;
+use core::hash::Hash;
 
-impl<'a, T, N> IntoIterator for &'a Counter<T, N>
+impl<'a, T, N, S> IntoIterator for &'a Counter<T, N, S>
 where
     T: Hash + Eq,
 {
     type Item = (&'a T, &'a N);
-    type IntoIter = std::collections::hash_map::Iter<'a, T, N>;
+    type IntoIter = crate::collections::hash_map::Iter<'a, T, N>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.map.iter()
     }
 }
 
-impl<T, N> IntoIterator for Counter<T, N>
+impl<T, N, S> IntoIterator for Counter<T, N, S>
 where
     T: Hash + Eq,
 {
     type Item = (T, N);
-    type IntoIter = std::collections::hash_map::IntoIter<T, N>;
+    type IntoIter = crate::collections::hash_map::IntoIter<T, N>;
 
     /// Consumes the `Counter` to produce an iterator that owns the values it returns.
     ///
@@ -40,18 +40,17 @@ where
     ///     }
     /// }
     /// ```
-
     fn into_iter(self) -> Self::IntoIter {
         self.map.into_iter()
     }
 }
 
-impl<'a, T, N> IntoIterator for &'a mut Counter<T, N>
+impl<'a, T, N, S> IntoIterator for &'a mut Counter<T, N, S>
 where
     T: Hash + Eq,
 {
     type Item = (&'a T, &'a mut N);
-    type IntoIter = std::collections::hash_map::IterMut<'a, T, N>;
+    type IntoIter = crate::collections::hash_map::IterMut<'a, T, N>;
 
     /// Creates an iterator that provides mutable references to the counts, but keeps the keys immutable.
     ///
@@ -0,0 +1,122 @@
+use crate::collections::hash_map::Entry;
+use crate::Counter;
+
+use core::hash::{BuildHasher, Hash};
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Merge another counter into this one, combining the counts of items present in both
+    /// with `combine`.
+    ///
+    /// `combine` is called with this counter's count, to update in place, and the other
+    /// counter's count. Items present only in `other` are inserted with their counts. Items
+    /// whose combined count is zero are kept; remove them with
+    /// [`retain`](Counter::retain) if needed.
+    ///
+    /// This covers combinations the operators don't, such as keeping the newer count:
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut stock = Counter::<_>::from([("apples", 5), ("pears", 2)]);
+    /// let recount = Counter::<_>::from([("pears", 0), ("plums", 4)]);
+    ///
+    /// stock.merge_with(recount, |count, recounted| *count = recounted);
+    /// assert_eq!(stock, Counter::from([("apples", 5), ("pears", 0), ("plums", 4)]));
+    /// ```
+    ///
+    /// `|count, other| *count += other` is equivalent to `+=`, and
+    /// `|count, other| *count = (*count).max(other)` to `|=`.
+    pub fn merge_with<S2, F>(&mut self, other: Counter<T, N, S2>, mut combine: F)
+    where
+        F: FnMut(&mut N, N),
+    {
+        trace_span!(
+            self.map.len() + other.map.len();
+            "merge_with",
+            len = self.map.len(),
+            other_len = other.map.len()
+        );
+        for (item, count) in other.map {
+            match self.map.entry(item) {
+                Entry::Occupied(mut entry) => combine(entry.get_mut(), count),
+                Entry::Vacant(entry) => {
+                    entry.insert(count);
+                }
+            }
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord,
+    S: BuildHasher,
+{
+    /// Merge `(item, count)` pairs into this counter, keeping the larger count of each item
+    /// rather than their sum.
+    ///
+    /// This suits high-water marks, such as the peak number of concurrent users per region
+    /// reported by several servers, where adding the counts would be meaningless. Items not
+    /// yet in the counter are inserted with their counts.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut peaks = Counter::<_>::from([("eu", 40), ("us", 75)]);
+    /// peaks.extend_max([("eu", 52), ("us", 60), ("ap", 18), ("eu", 45)]);
+    /// assert_eq!(peaks, Counter::from([("eu", 52), ("us", 75), ("ap", 18)]));
+    /// ```
+    ///
+    /// For another counter, `|=` does the same.
+    pub fn extend_max<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        for (item, count) in iterable {
+            match self.map.entry(item) {
+                Entry::Occupied(mut entry) => {
+                    if count > *entry.get() {
+                        entry.insert(count);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(count);
+                }
+            }
+        }
+    }
+
+    /// Merge `(item, count)` pairs into this counter, keeping the smaller count of each item
+    /// rather than their sum.
+    ///
+    /// Items not yet in the counter are inserted with their counts, so each item ends up with
+    /// the lowest count seen for it. This differs from `&=`, which treats items missing from
+    /// either side as having a count of zero.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut lows = Counter::<_>::from([("eu", 40), ("us", 75)]);
+    /// lows.extend_min([("eu", 52), ("us", 60), ("ap", 18), ("ap", 25)]);
+    /// assert_eq!(lows, Counter::from([("eu", 40), ("us", 60), ("ap", 18)]));
+    /// ```
+    pub fn extend_min<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        for (item, count) in iterable {
+            match self.map.entry(item) {
+                Entry::Occupied(mut entry) => {
+                    if count < *entry.get() {
+                        entry.insert(count);
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(count);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use crate::Counter;
+
+use core::hash::Hash;
+use core::ops::Neg;
+
+impl<T, N, S> Neg for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Neg<Output = N> + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Negate every count, keeping every item.
+    ///
+    /// `out = -c;` -> `out[x] == -c[x]` for all `x`
+    ///
+    /// Unlike Python's unary `-`, no items are dropped; use [`negative`] for that.
+    ///
+    /// [`negative`]: Counter::negative
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let c: Counter<_, i32> = Counter::from([('a', 2), ('b', -1)]);
+    /// assert_eq!(-c, Counter::from([('a', -2), ('b', 1)]));
+    /// ```
+    fn neg(mut self) -> Self::Output {
+        for count in self.map.values_mut() {
+            *count = -count.clone();
+        }
+        self
+    }
+}
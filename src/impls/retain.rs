@@ -0,0 +1,161 @@
+use crate::frequencies::count_to_f64;
+use crate::Counter;
+
+use crate::num::{ToPrimitive, Zero};
+
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::ops::AddAssign;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Retain only the items whose count satisfies the predicate.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbcccdddd".chars().collect::<Counter<_>>();
+    /// counter.retain_count(|&count| count % 2 == 0);
+    /// assert_eq!(counter, "bbdddd".chars().collect());
+    /// ```
+    pub fn retain_count<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&N) -> bool,
+    {
+        #[cfg(feature = "tracing")]
+        let len = self.map.len();
+        trace_span!(len; "retain_count", len);
+        self.map.retain(|_, count| predicate(count));
+        trace_event!(len; retained = self.map.len(), "pruned counter");
+    }
+
+    /// Retain only the items for which the predicate returns `true`, given each item and its
+    /// count. Returns the counter, so pruning steps can be chained.
+    ///
+    /// This shadows [`HashMap::retain`], which the counter derefs to, and like it passes the
+    /// count mutably.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let text = "a cat and a hat and the bat";
+    /// let mut counter = text.split_whitespace().collect::<Counter<_>>();
+    /// counter
+    ///     .retain(|word, _| word.len() > 1)
+    ///     .retain(|_, &mut count| count > 1);
+    /// assert_eq!(counter, Counter::from([("and", 2)]));
+    /// ```
+    ///
+    /// [`HashMap::retain`]: std::collections::HashMap::retain
+    pub fn retain<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: FnMut(&T, &mut N) -> bool,
+    {
+        #[cfg(feature = "tracing")]
+        let len = self.map.len();
+        trace_span!(len; "retain", len);
+        self.map.retain(predicate);
+        trace_event!(len; retained = self.map.len(), "pruned counter");
+        self
+    }
+
+    /// Retain only the items which satisfy the predicate, whatever their counts. Returns the
+    /// counter, so pruning steps can be chained.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// const STOPWORDS: [&str; 3] = ["a", "and", "the"];
+    ///
+    /// let mut counter = "the cat and the hat".split_whitespace().collect::<Counter<_>>();
+    /// counter.retain_keys(|word| !STOPWORDS.contains(word));
+    /// assert_eq!(counter, Counter::from([("cat", 1), ("hat", 1)]));
+    /// ```
+    pub fn retain_keys<F>(&mut self, mut predicate: F) -> &mut Self
+    where
+        F: FnMut(&T) -> bool,
+    {
+        #[cfg(feature = "tracing")]
+        let len = self.map.len();
+        trace_span!(len; "retain_keys", len);
+        self.map.retain(|item, _| predicate(item));
+        trace_event!(len; retained = self.map.len(), "pruned counter");
+        self
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd,
+{
+    /// Consume this counter, keeping only the items with a count of at least `min`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbcccdddd".chars().collect::<Counter<_>>();
+    /// let frequent = counter.min_count(3);
+    /// assert_eq!(frequent, "cccdddd".chars().collect());
+    /// ```
+    pub fn min_count(mut self, min: N) -> Self {
+        self.retain_count(|count| *count >= min);
+        self
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + AddAssign + Zero + Clone + ToPrimitive,
+{
+    /// Remove the least common items, as many as make up at most `fraction` of the total of
+    /// the counts, and return the total count removed.
+    ///
+    /// Unlike [`min_count`](Counter::min_count), which prunes at a fixed count, this bounds
+    /// the share of the observations which is lost, whatever the shape of the distribution:
+    /// after `prune_tail_fraction(0.01)`, the remaining counts still account for at least 99%
+    /// of everything counted. Items with equal counts are removed or kept together, so that
+    /// the result does not depend on the iteration order of the counter.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_>::from([('a', 90), ('b', 5), ('c', 3), ('d', 1), ('e', 1)]);
+    /// assert_eq!(counter.prune_tail_fraction(0.05), 5);
+    /// assert_eq!(counter, Counter::from([('a', 90), ('b', 5)]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not between 0 and 1.
+    pub fn prune_tail_fraction(&mut self, fraction: f64) -> N {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "pruned fraction must be between 0 and 1"
+        );
+        #[cfg(feature = "tracing")]
+        let len = self.map.len();
+        trace_span!(len; "prune_tail_fraction", len, fraction);
+        let mut counts = self.map.values().cloned().collect::<Vec<_>>();
+        counts.sort_unstable();
+        let budget = fraction * counts.iter().map(count_to_f64).sum::<f64>();
+
+        let mut removed = N::zero();
+        let mut removed_f64 = 0.0;
+        let mut threshold = None;
+        for group in counts.chunk_by(|a, b| a == b) {
+            let group_f64 = count_to_f64(&group[0]) * group.len() as f64;
+            if removed_f64 + group_f64 > budget {
+                break;
+            }
+            removed_f64 += group_f64;
+            for count in group {
+                removed += count.clone();
+            }
+            threshold = Some(&group[0]);
+        }
+        if let Some(threshold) = threshold {
+            self.map.retain(|_, count| *count > *threshold);
+        }
+        trace_event!(len; retained = self.map.len(), "pruned counter");
+        removed
+    }
+}
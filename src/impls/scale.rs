@@ -0,0 +1,130 @@
+use crate::Counter;
+
+use crate::num::Zero;
+
+use core::hash::Hash;
+use core::ops::{Div, DivAssign, Mul, MulAssign};
+
+impl<T, N, S> Mul<N> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: MulAssign + Zero + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Multiply every count by a scalar.
+    ///
+    /// `out = c * n;` -> `out[x] == c[x] * n` for all `x`, keeping only items with a count
+    /// other than [`N::zero()`].
+    ///
+    /// [`N::zero()`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use std::collections::HashMap;
+    /// let c = "aab".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c * 10;
+    ///
+    /// let expect = [('a', 20), ('b', 10)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn mul(mut self, rhs: N) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<T, N, S> MulAssign<N> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: MulAssign + Zero + Clone,
+{
+    /// Multiply every count by a scalar, in place.
+    ///
+    /// `c *= n;` -> `c[x] *= n` for all `x`, keeping only items with a count other than
+    /// [`N::zero()`].
+    ///
+    /// [`N::zero()`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(feature = "num")] {
+    /// let mut c = "aab".chars().collect::<Counter<_, f64>>();
+    /// c *= 0.5;
+    /// assert_eq!(c[&'a'], 1.0);
+    /// assert_eq!(c[&'b'], 0.5);
+    /// # }
+    /// ```
+    fn mul_assign(&mut self, rhs: N) {
+        self.map.retain(|_, count| {
+            *count *= rhs.clone();
+            !count.is_zero()
+        });
+    }
+}
+
+impl<T, N, S> Div<N> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: DivAssign + Zero + Clone,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Divide every count by a scalar.
+    ///
+    /// `out = c / n;` -> `out[x] == c[x] / n` for all `x`, keeping only items with a count
+    /// other than [`N::zero()`]. For integer counts, the division truncates, so items whose
+    /// counts are smaller than `n` are dropped.
+    ///
+    /// [`N::zero()`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use std::collections::HashMap;
+    /// let c = "aaaaabb".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c / 2;
+    ///
+    /// let expect = [('a', 2), ('b', 1)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn div(mut self, rhs: N) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+impl<T, N, S> DivAssign<N> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: DivAssign + Zero + Clone,
+{
+    /// Divide every count by a scalar, in place.
+    ///
+    /// `c /= n;` -> `c[x] /= n` for all `x`, keeping only items with a count other than
+    /// [`N::zero()`].
+    ///
+    /// [`N::zero()`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    ///
+    /// # Panics
+    ///
+    /// For integer counts, panics if `n` is zero and the counter is not empty.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut c = "aaab".chars().collect::<Counter<_>>();
+    /// c /= 3;
+    /// assert_eq!(c, "a".chars().collect());
+    /// ```
+    fn div_assign(&mut self, rhs: N) {
+        self.map.retain(|_, count| {
+            *count /= rhs.clone();
+            !count.is_zero()
+        });
+    }
+}
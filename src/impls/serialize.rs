@@ -1,32 +1,41 @@
+use crate::serde::as_map;
 use crate::Counter;
 
-use std::hash::Hash;
-use num_traits::Zero;
-use serde::{Serialize, Deserialize};
-use serde::ser::Serializer;
+use crate::num::Zero;
+
 use serde::de::Deserializer;
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
 
+use core::hash::{BuildHasher, Hash};
 
-impl<T, N> Serialize for Counter<T, N> 
+/// Serializes as a map from items to counts.
+///
+/// To represent a field as a sequence of `(item, count)` pairs instead, which works whatever
+/// the items are, see the [`serde`](crate::serde) module.
+impl<T, N, S> Serialize for Counter<T, N, S>
 where
     T: Serialize + Hash + Eq,
     N: Serialize,
 {
-    fn serialize<S>(&self, serializer:S) -> Result<S::Ok, S::Error>
-    where S: Serializer {
-        self.map.serialize(serializer)
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        as_map::serialize(self, serializer)
     }
 }
 
-impl<'de, T, N> Deserialize<'de> for Counter<T, N>
+impl<'de, T, N, S> Deserialize<'de> for Counter<T, N, S>
 where
     T: Deserialize<'de> + Hash + Eq,
     N: Deserialize<'de> + Zero,
+    S: BuildHasher + Default,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where D: Deserializer<'de> {
-        let map = <_>::deserialize(deserializer)?;
-        let zero = N::zero();
-        Ok(Counter { map, zero })
+    where
+        D: Deserializer<'de>,
+    {
+        as_map::deserialize(deserializer)
     }
-}
\ No newline at end of file
+}
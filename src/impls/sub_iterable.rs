@@ -1,15 +1,16 @@
 use crate::Counter;
 
-use num_traits::{One, Zero};
+use crate::num::{One, Zero};
 
-use std::hash::Hash;
-use std::ops::{Sub, SubAssign};
+use core::hash::{BuildHasher, Hash};
+use core::ops::{Sub, SubAssign};
 
-impl<I, T, N> Sub<I> for Counter<T, N>
+impl<I, T, N, S> Sub<I> for Counter<T, N, S>
 where
     I: IntoIterator<Item = T>,
     T: Hash + Eq,
     N: PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     type Output = Self;
     /// Consume `self` producing a `Counter` like `self` with the counts of the
@@ -30,11 +31,12 @@ where
     }
 }
 
-impl<I, T, N> SubAssign<I> for Counter<T, N>
+impl<I, T, N, S> SubAssign<I> for Counter<T, N, S>
 where
     I: IntoIterator<Item = T>,
     T: Hash + Eq,
     N: PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     /// Directly subtract the counts of the elements of `I` from `self`,
     /// keeping only items with a value greater than [`N::zero()`].
@@ -55,3 +57,36 @@ where
         self.subtract(rhs);
     }
 }
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Remove the counts of the elements from the given iterable, like
+    /// [`subtract`](Counter::subtract), but keep items whose count reaches zero.
+    ///
+    /// Counts still stop at zero rather than going negative, and items which the counter does
+    /// not hold are not added.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbccc".chars().collect::<Counter<_>>();
+    /// counter.subtract_keeping_zeros("abba".chars());
+    ///
+    /// assert_eq!(counter, Counter::from([('a', 0), ('b', 0), ('c', 3)]));
+    /// ```
+    pub fn subtract_keeping_zeros<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            if let Some(entry) = self.map.get_mut(&item) {
+                if *entry > N::zero() {
+                    *entry -= N::one();
+                }
+            }
+        }
+    }
+}
@@ -1,16 +1,17 @@
 use crate::Counter;
 
-use num_traits::Zero;
+use crate::num::Zero;
 
-use std::hash::Hash;
-use std::ops::{Sub, SubAssign};
+use core::hash::{BuildHasher, Hash};
+use core::ops::{Sub, SubAssign};
 
-impl<T, N> Sub for Counter<T, N>
+impl<T, N, S, S2> Sub<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
     /// Subtract (keeping only positive values).
     ///
@@ -31,21 +32,24 @@ where
     /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn sub(mut self, rhs: Counter<T, N>) -> Self::Output {
+    fn sub(mut self, rhs: Counter<T, N, S2>) -> Self::Output {
         self -= rhs;
         self
     }
 }
 
-impl<T, N> SubAssign for Counter<T, N>
+impl<T, N, S, S2> SubAssign<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + PartialEq + SubAssign + Zero,
+    S: BuildHasher,
 {
     /// Subtract (keeping only positive values).
     ///
     /// `c -= d;` -> `c[x] -= d[x]` for all `x`,
     /// keeping only items with a value greater than [`N::zero()`].
+    /// Use [`subtract_counter_keeping_zeros`](Counter::subtract_counter_keeping_zeros) to keep
+    /// items whose count reaches zero.
     ///
     /// [`N::zero()`]:
     /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
@@ -61,7 +65,7 @@ where
     /// let expect = [('a', 2)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(c.into_map(), expect);
     /// ```
-    fn sub_assign(&mut self, rhs: Self) {
+    fn sub_assign(&mut self, rhs: Counter<T, N, S2>) {
         for (key, value) in rhs.map {
             let mut remove = false;
             if let Some(entry) = self.map.get_mut(&key) {
@@ -80,3 +84,36 @@ where
         }
     }
 }
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Subtract the counts of `rhs`, like `-=`, but keep items whose count reaches zero.
+    ///
+    /// Counts still stop at zero rather than going negative, and items which `self` does not
+    /// hold are not added. An explicit zero records that an item was seen and then used up;
+    /// [`positive_iter`](Counter::positive_iter) skips such items.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut stock = Counter::<_>::from([("apples", 3), ("pears", 1)]);
+    /// stock.subtract_counter_keeping_zeros(Counter::<_>::from([("apples", 1), ("pears", 2)]));
+    ///
+    /// assert_eq!(stock, Counter::from([("apples", 2), ("pears", 0)]));
+    /// assert_eq!(stock.positive_keys().collect::<Vec<_>>(), [&"apples"]);
+    /// ```
+    pub fn subtract_counter_keeping_zeros<S2>(&mut self, rhs: Counter<T, N, S2>) {
+        for (key, value) in rhs.map {
+            if let Some(entry) = self.map.get_mut(&key) {
+                if *entry >= value {
+                    *entry -= value;
+                } else {
+                    *entry = N::zero();
+                }
+            }
+        }
+    }
+}
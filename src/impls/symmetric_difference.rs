@@ -0,0 +1,147 @@
+use crate::Counter;
+
+use crate::num::Zero;
+
+use core::hash::{BuildHasher, Hash};
+use core::mem;
+use core::ops::{BitXor, BitXorAssign, Sub};
+
+/// Returns `|a - b|`, without going below zero for unsigned counts.
+fn abs_diff<N>(a: N, b: N) -> N
+where
+    N: Ord + Sub<Output = N>,
+{
+    if a >= b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Ord + Clone + Sub<Output = N> + Zero,
+    S: BuildHasher + Clone,
+{
+    /// Returns the symmetric difference of `self` and `other` as a new `Counter`: the items
+    /// which are unbalanced between the two, each counted by how much their counts differ.
+    ///
+    /// `out = c.symmetric_difference(&d);` -> `out[x] == |c[x] - d[x]|`
+    ///
+    /// Items whose counts are equal are left out. This is the same as `c ^ d`, without
+    /// consuming either counter. To compare with a counter which uses a different hasher, use
+    /// [`symmetric_difference_with_hasher`](Counter::symmetric_difference_with_hasher).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let first = "the cat sat on the mat".split_whitespace().collect::<Counter<_>>();
+    /// let second = "the cat ate the hat".split_whitespace().collect::<Counter<_>>();
+    ///
+    /// let unbalanced = first.symmetric_difference(&second);
+    /// let expect = Counter::from([("sat", 1), ("on", 1), ("mat", 1), ("ate", 1), ("hat", 1)]);
+    /// assert_eq!(unbalanced, expect);
+    /// ```
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        self.symmetric_difference_with_hasher(other)
+    }
+
+    /// Returns the symmetric difference of `self` and `other`, which may use a different
+    /// hasher, as [`symmetric_difference`](Counter::symmetric_difference) does.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, SeededState};
+    /// let first = "aab".chars().collect::<Counter<_>>();
+    /// let second = "abc".chars().collect::<Counter<_, usize, SeededState>>();
+    /// assert_eq!(first.symmetric_difference_with_hasher(&second), Counter::from([('a', 1), ('c', 1)]));
+    /// ```
+    pub fn symmetric_difference_with_hasher<S2>(&self, other: &Counter<T, N, S2>) -> Self
+    where
+        S2: BuildHasher,
+    {
+        let mut difference = self.clone();
+        difference.map.retain(|item, count| {
+            let other_count = other.map.get(item).cloned().unwrap_or_else(N::zero);
+            *count = abs_diff(mem::replace(count, N::zero()), other_count);
+            !count.is_zero()
+        });
+        for (item, other_count) in &other.map {
+            if !self.map.contains_key(item) {
+                let count = abs_diff(N::zero(), other_count.clone());
+                if !count.is_zero() {
+                    difference.map.insert(item.clone(), count);
+                }
+            }
+        }
+        difference
+    }
+}
+
+impl<T, N, S, S2> BitXor<Counter<T, N, S2>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + Sub<Output = N> + Zero,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    type Output = Counter<T, N, S>;
+
+    /// Returns the symmetric difference of `self` and `rhs` as a new `Counter`.
+    ///
+    /// `out = c ^ d;` -> `out[x] == |c[x] - d[x]|`
+    ///
+    /// Items whose counts are equal are left out.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use std::collections::HashMap;
+    /// let c = "aaabc".chars().collect::<Counter<_>>();
+    /// let d = "abbbc".chars().collect::<Counter<_>>();
+    ///
+    /// let e = c ^ d;
+    ///
+    /// let expect = [('a', 2), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(e.into_map(), expect);
+    /// ```
+    fn bitxor(mut self, rhs: Counter<T, N, S2>) -> Self::Output {
+        self ^= rhs;
+        self
+    }
+}
+
+impl<T, N, S, S2> BitXorAssign<Counter<T, N, S2>> for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord + Sub<Output = N> + Zero,
+    S: BuildHasher,
+    S2: BuildHasher,
+{
+    /// Updates `self` with the symmetric difference of `self` and `rhs`.
+    ///
+    /// `c ^= d;` -> `c[x] == |c[x] - d[x]|`
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # use std::collections::HashMap;
+    /// let mut c = "aaabc".chars().collect::<Counter<_>>();
+    /// let d = "abbbc".chars().collect::<Counter<_>>();
+    ///
+    /// c ^= d;
+    ///
+    /// let expect = [('a', 2), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
+    /// assert_eq!(c.into_map(), expect);
+    /// ```
+    fn bitxor_assign(&mut self, mut rhs: Counter<T, N, S2>) {
+        self.map.retain(|item, count| {
+            let rhs_count = rhs.map.remove(item).unwrap_or_else(N::zero);
+            *count = abs_diff(mem::replace(count, N::zero()), rhs_count);
+            !count.is_zero()
+        });
+        for (item, rhs_count) in rhs.map {
+            let count = abs_diff(N::zero(), rhs_count);
+            if !count.is_zero() {
+                self.map.insert(item, count);
+            }
+        }
+    }
+}
@@ -1,16 +1,17 @@
 use crate::Counter;
 
-use num_traits::Zero;
+use crate::num::Zero;
 
-use std::hash::Hash;
-use std::ops::{BitOr, BitOrAssign};
+use core::hash::{BuildHasher, Hash};
+use core::ops::{BitOr, BitOrAssign};
 
-impl<T, N> BitOr for Counter<T, N>
+impl<T, N, S, S2> BitOr<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: Ord + Zero,
+    S: BuildHasher,
 {
-    type Output = Counter<T, N>;
+    type Output = Counter<T, N, S>;
 
     /// Returns the union of `self` and `rhs` as a new `Counter`.
     ///
@@ -27,7 +28,13 @@ where
     /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(e.into_map(), expect);
     /// ```
-    fn bitor(mut self, rhs: Counter<T, N>) -> Self::Output {
+    fn bitor(mut self, rhs: Counter<T, N, S2>) -> Self::Output {
+        trace_span!(
+            self.map.len() + rhs.map.len();
+            "union",
+            len = self.map.len(),
+            other_len = rhs.map.len()
+        );
         for (key, rhs_value) in rhs.map {
             let entry = self.map.entry(key).or_insert_with(N::zero);
             // We want to update the value of the now occupied entry in `self` with the maximum of
@@ -54,10 +61,11 @@ where
     }
 }
 
-impl<T, N> BitOrAssign for Counter<T, N>
+impl<T, N, S, S2> BitOrAssign<Counter<T, N, S2>> for Counter<T, N, S>
 where
     T: Hash + Eq,
     N: Ord + Zero,
+    S: BuildHasher,
 {
     /// Updates `self` with the union of `self` and `rhs`
     ///
@@ -74,7 +82,13 @@ where
     /// let expect = [('a', 3), ('b', 2)].iter().cloned().collect::<HashMap<_, _>>();
     /// assert_eq!(c.into_map(), expect);
     /// ```
-    fn bitor_assign(&mut self, mut rhs: Counter<T, N>) {
+    fn bitor_assign(&mut self, mut rhs: Counter<T, N, S2>) {
+        trace_span!(
+            self.map.len() + rhs.map.len();
+            "union",
+            len = self.map.len(),
+            other_len = rhs.map.len()
+        );
         for (key, rhs_count) in rhs.drain() {
             if rhs_count > self[&key] {
                 self.map.insert(key, rhs_count);
@@ -0,0 +1,275 @@
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, Deref, SubAssign};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A single mutation recorded by a [`JournaledCounter`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Change<T, N> {
+    /// Add the count to the item.
+    Add(T, N),
+    /// Subtract the count from the item, removing it if its count would become non-positive.
+    Subtract(T, N),
+    /// Remove the item entirely.
+    Remove(T),
+    /// Remove every item.
+    Clear,
+}
+
+/// The mutations drained from a [`JournaledCounter`], ready to be applied to a replica with
+/// [`Counter::apply_journal`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: Serialize, N: Serialize",
+        deserialize = "T: Deserialize<'de>, N: Deserialize<'de>, Counter<T, N>: Deserialize<'de>"
+    ))
+)]
+pub enum Journal<T: Hash + Eq, N> {
+    /// The changes made since the journal was last drained, in order.
+    Changes(Vec<Change<T, N>>),
+    /// The journal overflowed its capacity, so the replica must be replaced by this snapshot of
+    /// the whole counter.
+    Snapshot(Counter<T, N>),
+}
+
+/// A counter which records its mutations into a bounded journal, so that they can be shipped to
+/// and replayed on a replica without sending the whole counter.
+///
+/// The journal holds at most `capacity` changes. If more changes accumulate before it is
+/// drained, the journal is discarded and the next [`drain_journal`] instead returns a full
+/// [`Journal::Snapshot`].
+///
+/// Mutation is only possible through the methods of this type, each of which records a
+/// [`Change`]. The wrapped counter can be read through [`Deref`].
+///
+/// [`drain_journal`]: JournaledCounter::drain_journal
+///
+/// ```rust
+/// # use counter::{Counter, JournaledCounter};
+/// let mut primary = JournaledCounter::<char>::with_capacity(100);
+/// let mut replica = Counter::<char>::new();
+///
+/// primary.update("abbccc".chars());
+/// primary.subtract("cd".chars());
+/// replica.apply_journal(primary.drain_journal());
+/// assert_eq!(replica, *primary);
+///
+/// primary.remove(&'a');
+/// replica.apply_journal(primary.drain_journal());
+/// assert_eq!(replica, "bbcc".chars().collect());
+/// ```
+#[derive(Clone, Debug)]
+pub struct JournaledCounter<T: Hash + Eq, N = usize> {
+    counter: Counter<T, N>,
+    changes: Vec<Change<T, N>>,
+    capacity: usize,
+    overflowed: bool,
+}
+
+impl<T, N> JournaledCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `JournaledCounter` whose journal holds at most `capacity` changes.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_counter(Counter::new(), capacity)
+    }
+}
+
+impl<T, N> JournaledCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Start journaling changes to an existing counter.
+    ///
+    /// The replica is assumed to already hold the contents of `counter`.
+    pub fn from_counter(counter: Counter<T, N>, capacity: usize) -> Self {
+        JournaledCounter {
+            counter,
+            changes: Vec::new(),
+            capacity,
+            overflowed: false,
+        }
+    }
+
+    /// Consumes the `JournaledCounter`, discarding the journal, and returns the counter.
+    pub fn into_inner(self) -> Counter<T, N> {
+        self.counter
+    }
+
+    /// Returns the number of changes in the journal, or `None` if it has overflowed.
+    pub fn journal_len(&self) -> Option<usize> {
+        if self.overflowed {
+            None
+        } else {
+            Some(self.changes.len())
+        }
+    }
+
+    fn record(&mut self, change: Change<T, N>) {
+        if self.overflowed {
+            return;
+        }
+        if self.changes.len() < self.capacity {
+            self.changes.push(change);
+        } else {
+            self.overflowed = true;
+            self.changes = Vec::new();
+        }
+    }
+}
+
+impl<T, N> JournaledCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: Clone,
+{
+    /// Take the changes recorded since the journal was last drained.
+    ///
+    /// If the journal overflowed, a snapshot of the whole counter is returned instead.
+    pub fn drain_journal(&mut self) -> Journal<T, N> {
+        if self.overflowed {
+            self.overflowed = false;
+            Journal::Snapshot(self.counter.clone())
+        } else {
+            Journal::Changes(core::mem::take(&mut self.changes))
+        }
+    }
+}
+
+impl<T, N> JournaledCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    fn apply(&mut self, change: Change<T, N>) {
+        self.counter.apply_change(change.clone());
+        self.record(change);
+    }
+
+    /// Add `count` to the count of `item`.
+    pub fn add(&mut self, item: T, count: N) {
+        self.apply(Change::Add(item, count));
+    }
+
+    /// Subtract `count` from the count of `item`, removing it if it becomes non-positive.
+    pub fn sub(&mut self, item: T, count: N) {
+        self.apply(Change::Subtract(item, count));
+    }
+
+    /// Remove `item` from the counter, returning its count.
+    pub fn remove(&mut self, item: &T) -> Option<N> {
+        let count = self.counter.map.get(item).cloned();
+        if count.is_some() {
+            self.apply(Change::Remove(item.clone()));
+        }
+        count
+    }
+
+    /// Remove every item from the counter.
+    pub fn clear(&mut self) {
+        self.apply(Change::Clear);
+    }
+}
+
+impl<T, N> JournaledCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: PartialOrd + AddAssign + SubAssign + Zero + One + Clone,
+{
+    /// Add the counts of the elements from the given iterable to this counter.
+    ///
+    /// One [`Change`] is recorded per distinct element.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for (item, count) in iterable.into_iter().collect::<Counter<T, N>>() {
+            self.add(item, count);
+        }
+    }
+
+    /// Subtract the counts of the elements from the given iterable from this counter.
+    ///
+    /// Like `counter -= other_counter`, items whose counts become non-positive are removed. One
+    /// [`Change`] is recorded per distinct element.
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for (item, count) in iterable.into_iter().collect::<Counter<T, N>>() {
+            self.sub(item, count);
+        }
+    }
+}
+
+impl<T, N> Deref for JournaledCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Target = Counter<T, N>;
+    fn deref(&self) -> &Counter<T, N> {
+        &self.counter
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + AddAssign + SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Replay a [`Journal`] drained from a [`JournaledCounter`] onto this counter.
+    ///
+    /// Provided this counter held the same items as the journaled counter when the journal was
+    /// last drained, the two hold the same items afterwards.
+    pub fn apply_journal(&mut self, journal: Journal<T, N>) {
+        match journal {
+            Journal::Changes(changes) => {
+                for change in changes {
+                    self.apply_change(change);
+                }
+            }
+            Journal::Snapshot(snapshot) => {
+                self.map.clear();
+                self.map.extend(snapshot.map);
+            }
+        }
+    }
+
+    fn apply_change(&mut self, change: Change<T, N>) {
+        match change {
+            Change::Add(item, count) => {
+                *self.map.entry(item).or_insert_with(N::zero) += count;
+            }
+            Change::Subtract(item, count) => {
+                let mut remove = false;
+                if let Some(entry) = self.map.get_mut(&item) {
+                    if *entry > count {
+                        *entry -= count;
+                    } else {
+                        remove = true;
+                    }
+                }
+                if remove {
+                    self.map.remove(&item);
+                }
+            }
+            Change::Remove(item) => {
+                self.map.remove(&item);
+            }
+            Change::Clear => self.map.clear(),
+        }
+    }
+}
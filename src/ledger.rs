@@ -0,0 +1,177 @@
+use crate::Counter;
+
+use crate::num::{One, Signed, Zero};
+
+use core::hash::Hash;
+use core::ops::{AddAssign, SubAssign};
+
+/// A counter which records additions and removals separately.
+///
+/// Subtracting from a [`Counter`] truncates at zero and forgets that the removals happened. A
+/// `LedgerCounter` instead keeps two counters, of credits and of debits, so that both the
+/// [`net`](LedgerCounter::net) balance and the [`gross`](LedgerCounter::gross) activity of
+/// every item remain available.
+///
+/// Because net balances can be negative, the count type defaults to [`i64`] rather than
+/// `usize`.
+///
+/// ```rust
+/// # use counter::{Counter, LedgerCounter};
+/// let mut ledger = LedgerCounter::<char>::new();
+/// ledger.credit("aab".chars());
+/// ledger.debit("bbc".chars());
+///
+/// let net = ledger.net();
+/// assert_eq!(net[&'a'], 2);
+/// assert_eq!(net[&'b'], -1);
+/// assert_eq!(net[&'c'], -1);
+///
+/// assert_eq!(ledger.gross()[&'b'], 3);
+/// assert_eq!(ledger.debits().total::<i64>(), 3);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LedgerCounter<T: Hash + Eq, N = i64> {
+    credits: Counter<T, N>,
+    debits: Counter<T, N>,
+}
+
+impl<T, N> LedgerCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `LedgerCounter`.
+    pub fn new() -> Self {
+        LedgerCounter {
+            credits: Counter::new(),
+            debits: Counter::new(),
+        }
+    }
+}
+
+impl<T, N> Default for LedgerCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Default,
+{
+    fn default() -> Self {
+        LedgerCounter {
+            credits: Counter::default(),
+            debits: Counter::default(),
+        }
+    }
+}
+
+impl<T, N> LedgerCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Returns the counter of everything which was credited.
+    pub fn credits(&self) -> &Counter<T, N> {
+        &self.credits
+    }
+
+    /// Returns the counter of everything which was debited.
+    pub fn debits(&self) -> &Counter<T, N> {
+        &self.debits
+    }
+
+    /// Consumes the ledger and returns its `(credits, debits)` counters.
+    pub fn into_parts(self) -> (Counter<T, N>, Counter<T, N>) {
+        (self.credits, self.debits)
+    }
+}
+
+impl<T, N> LedgerCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+{
+    /// Credit one unit for each element of the given iterable.
+    pub fn credit<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.credits.update(iterable);
+    }
+
+    /// Debit one unit for each element of the given iterable.
+    pub fn debit<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.debits.update(iterable);
+    }
+}
+
+impl<T, N> LedgerCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Credit `amount` units of `item`.
+    ///
+    /// ```rust
+    /// # use counter::LedgerCounter;
+    /// let mut ledger = LedgerCounter::<&str>::new();
+    /// ledger.credit_by("widgets", 10);
+    /// ledger.debit_by("widgets", 4);
+    /// assert_eq!(ledger.net()[&"widgets"], 6);
+    /// ```
+    pub fn credit_by(&mut self, item: T, amount: N) {
+        *self.credits.map.entry(item).or_insert_with(N::zero) += amount;
+    }
+
+    /// Debit `amount` units of `item`.
+    pub fn debit_by(&mut self, item: T, amount: N) {
+        *self.debits.map.entry(item).or_insert_with(N::zero) += amount;
+    }
+}
+
+impl<T, N> LedgerCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: Signed + SubAssign + Clone,
+{
+    /// Returns the balance of every item: its credits minus its debits.
+    ///
+    /// Items whose credits and debits cancel out are omitted; items which were debited more
+    /// than they were credited have a negative count. `N` must therefore be a signed type, as
+    /// the default `i64` is; with an unsigned type, compare [`credits`](LedgerCounter::credits)
+    /// and [`debits`](LedgerCounter::debits) instead.
+    pub fn net(&self) -> Counter<T, N> {
+        let mut net = self.credits.clone();
+        for (item, debit) in &self.debits.map {
+            let mut remove = false;
+            if let Some(entry) = net.map.get_mut(item) {
+                *entry -= debit.clone();
+                remove = entry.is_zero();
+            } else {
+                let mut balance = N::zero();
+                balance -= debit.clone();
+                if !balance.is_zero() {
+                    net.map.insert(item.clone(), balance);
+                }
+            }
+            if remove {
+                net.map.remove(item);
+            }
+        }
+        net
+    }
+}
+
+impl<T, N> LedgerCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + Clone,
+{
+    /// Returns the total activity of every item: its credits plus its debits.
+    pub fn gross(&self) -> Counter<T, N> {
+        let mut gross = self.credits.clone();
+        for (item, debit) in &self.debits.map {
+            *gross.map.entry(item.clone()).or_insert_with(N::zero) += debit.clone();
+        }
+        gross
+    }
+}
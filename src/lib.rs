@@ -20,7 +20,7 @@
 //! ```rust
 //! use counter::Counter;
 //! let char_counts = "barefoot".chars().collect::<Counter<_>>();
-//! let counts_counts = char_counts.values().collect::<Counter<_>>();
+//! let counts_counts = char_counts.count_of_counts();
 //! ```
 //!
 //! ## Update a count
@@ -212,6 +212,21 @@
 //! // assert_eq!((*counter)[&'b'], 0);
 //! ```
 //!
+//! ## Keep items in order
+//!
+//! [`OrderedCounter`] is backed by a [`BTreeMap`] rather than a [`HashMap`], so it iterates in
+//! the natural order of its keys and supports range queries:
+//!
+//! [`BTreeMap`]: https://doc.rust-lang.org/std/collections/struct.BTreeMap.html
+//!
+//! ```rust
+//! # use counter::OrderedCounter;
+//! let counter = "the quick brown fox".split_whitespace()
+//!     .map(str::len)
+//!     .collect::<OrderedCounter<_>>();
+//! assert_eq!(counter.counts_in_range(4..).collect::<Vec<_>>(), [(&5, &2)]);
+//! ```
+//!
 //! # Advanced Usage
 //!
 //! ## Count any iterable which is `Hash + Eq`
@@ -273,36 +288,234 @@
 //! let expected: HashMap<char, i8> = [('a', 1), ('b', 2), ('c', 3)].iter().cloned().collect();
 //! assert!(counter.into_map() == expected);
 //! ```
+//!
+//! Small count types overflow sooner, and in release builds an overflowing count
+//! silently wraps around. [`Counter::try_update`] and [`Counter::try_extend`] count
+//! with checked arithmetic instead, and name the item whose count would overflow.
+//!
+//! Counts are bounded by the traits of the [`num`] module. Without the default `num` feature,
+//! the crate has no dependencies at all, and counts are limited to the primitive integers,
+//! which implement [`num::Count`].
+//!
+//! ## Use your own hasher
+//!
+//! Like [`HashMap`], `Counter` takes a third type parameter for the [`BuildHasher`] used to
+//! hash its keys. It defaults to [`RandomState`], or, without the default `std` feature, to
+//! the default hasher of `hashbrown`.
+//!
+//! Every method is available whatever the hasher, except that, as for [`HashMap`], the
+//! constructors [`new()`](Counter::new) and [`with_capacity()`](Counter::with_capacity) use
+//! the default hasher; start from [`Counter::default()`] instead.
+//!
+//! The binary operators, such as `+`, `-`, `&`, `|` and `^`, accept counters with different
+//! hashers on either side, so counters from different sources combine without rebuilding
+//! either one. The result keeps the hasher of the left-hand side.
+//!
+//! For reproducible runs without a third-party hasher, [`Counter::with_seed()`] uses a
+//! [`SeededState`], whose hashes, and so iteration order, depend only on its seed.
+//!
+//! [`BuildHasher`]: https://doc.rust-lang.org/stable/std/hash/trait.BuildHasher.html
+//! [`RandomState`]: https://doc.rust-lang.org/stable/std/collections/hash_map/struct.RandomState.html
+//!
+//! ```rust
+//! # use counter::Counter;
+//! # use std::collections::hash_map::DefaultHasher;
+//! # use std::hash::BuildHasherDefault;
+//! let mut counter: Counter<char, usize, BuildHasherDefault<DefaultHasher>> = Counter::default();
+//! counter[&'a'] += 2;
+//! counter[&'b'] += 1;
+//! assert_eq!(counter.total::<usize>(), 3);
+//!
+//! let other = "abc".chars().collect::<Counter<_>>();
+//! let sum = counter + other;
+//! let _: &BuildHasherDefault<DefaultHasher> = sum.hasher();
+//! assert_eq!(sum.total::<usize>(), 6);
+//! ```
 
 #![allow(clippy::must_use_candidate)]
+#![cfg_attr(counter_simd, feature(portable_simd))]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+// Declared first, so that its macros are visible in the modules below.
+#[macro_use]
+mod trace;
+
+#[cfg(feature = "approx")]
+pub mod approx;
+#[cfg(target_has_atomic = "64")]
+mod atomic;
+mod checked;
+mod collections;
+#[cfg(feature = "std")]
+mod concurrent;
+mod convert;
+mod count_iter;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "std")]
+mod delimited;
+mod dense;
+mod diff;
+pub mod display;
+mod elements;
+mod entry;
+mod epoch;
+mod error;
+#[cfg(any(feature = "ahash", feature = "rustc-hash"))]
+mod fast_hash;
+mod flatten;
+mod frequencies;
+mod growth;
 mod impls;
+mod journal;
+mod ledger;
+pub mod markov;
+mod ngrams;
+mod nonzero;
+mod normalizing;
+pub mod num;
+mod order_by;
+mod ordered;
+mod plan;
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "indexmap")]
+pub mod preserving;
+mod product;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(all(feature = "arc-swap", feature = "std"))]
+pub mod publish;
+#[cfg(feature = "rand")]
+mod sample;
+mod seeded;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod signed;
+#[cfg(feature = "std")]
+mod similarity;
+mod small_key;
+#[cfg(feature = "std")]
+pub mod smoothing;
+#[cfg(feature = "spill")]
+pub mod spill;
+#[cfg(feature = "std")]
+mod stats;
+mod subscriber;
+#[cfg(feature = "aho-corasick")]
+pub mod text;
+mod tracked;
+mod weighted;
+
+#[cfg(target_has_atomic = "64")]
+pub use atomic::AtomicCounter;
+pub use checked::OverflowError;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentCounter;
+pub use convert::KeyConversionError;
+pub use count_iter::{ByCountDesc, CountIteratorExt, CountsCopied, MinCount};
+#[cfg(feature = "std")]
+pub use delimited::{DelimitedError, Delimiter};
+pub use dense::DenseCounter;
+pub use diff::{CountChange, CounterDiff, DiffSummary};
+pub use elements::{Elements, IntoElements, SortedElements};
+pub use entry::{CountCursor, CounterEntry};
+pub use epoch::EpochCounter;
+pub use error::Error;
+#[cfg(feature = "ahash")]
+pub use fast_hash::AHashCounter;
+#[cfg(feature = "rustc-hash")]
+pub use fast_hash::FxCounter;
+pub use flatten::{flatten, flatten_weighted};
+pub use growth::GrowthPolicy;
+pub use journal::{Change, Journal, JournaledCounter};
+pub use ledger::LedgerCounter;
+pub use nonzero::{NonZeroCountError, NonZeroPolicy};
+pub use normalizing::NormalizingCounter;
+pub use order_by::OrderBy;
+pub use ordered::OrderedCounter;
+pub use plan::{PlanError, Rollback, UpdatePlan};
+#[cfg(feature = "std")]
+pub use pool::{CounterPool, PooledCounter};
+#[cfg(feature = "rand")]
+pub use sample::AliasTable;
+pub use seeded::{SeededHasher, SeededState};
+pub use small_key::SmallKey;
+#[cfg(feature = "std")]
+pub use stats::{ChiSquaredTest, CountMoments};
+pub use subscriber::{SubscribedCounter, Subscriber};
+pub use tracked::TrackedCounter;
+
+use crate::collections::{DefaultHashBuilder, HashMap};
+
+use crate::num::{Bounded, CheckedAdd, NumCast, One, SaturatingAdd, ToPrimitive, Zero};
+
+use alloc::collections::{BTreeMap, BinaryHeap};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Reverse;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, SubAssign};
+use core::{fmt, iter};
 
-use num_traits::{One, Zero};
+#[cfg(feature = "smallvec")]
+use smallvec::SmallVec;
 
-use std::collections::{BinaryHeap, HashMap};
-use std::hash::Hash;
-use std::iter;
-use std::ops::{AddAssign, SubAssign};
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod unit_tests;
 
-type CounterMap<T, N> = HashMap<T, N>;
+type CounterMap<T, N, S> = HashMap<T, N, S>;
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-pub struct Counter<T: Hash + Eq, N = usize> {
-    map: CounterMap<T, N>,
+#[derive(Clone)]
+pub struct Counter<T: Hash + Eq, N = usize, S = DefaultHashBuilder> {
+    map: CounterMap<T, N, S>,
     // necessary for `Index::index` since we cannot declare generic `static` variables.
     zero: N,
 }
 
-impl<T, N> Counter<T, N>
+// Not derived, because the derive would require the hasher to be `Debug`, which it need not be.
+impl<T, N, S> fmt::Debug for Counter<T, N, S>
+where
+    T: Hash + Eq + fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Counter")
+            .field("map", &self.map)
+            .field("zero", &self.zero)
+            .finish()
+    }
+}
+
+impl<T, N, S> PartialEq for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T, N, S> Eq for Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Eq,
+    S: BuildHasher,
+{
+}
+
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
 {
     /// Consumes this counter and returns a [`HashMap`] mapping the items to the counts.
     ///
     /// [`HashMap`]: https://doc.rust-lang.org/stable/std/collections/struct.HashMap.html
-    pub fn into_map(self) -> HashMap<T, N> {
+    pub fn into_map(self) -> HashMap<T, N, S> {
         self.map
     }
 
@@ -311,7 +524,14 @@ where
     /// Use [`len`] to get the number of elements in the counter and use `total` to get the sum of
     /// their counts.
     ///
+    /// The sum is computed with the [`Sum`] implementation of `M`, which for the primitive
+    /// integers panics on overflow in debug builds and wraps in release builds. Use
+    /// [`total_checked`] or [`total_saturating`] when that matters.
+    ///
     /// [`len`]: struct.Counter.html#method.len
+    /// [`Sum`]: https://doc.rust-lang.org/stable/std/iter/trait.Sum.html
+    /// [`total_checked`]: Counter::total_checked
+    /// [`total_saturating`]: Counter::total_saturating
     ///
     /// # Examples
     ///
@@ -321,20 +541,151 @@ where
     /// assert_eq!(counter.total::<usize>(), 11);
     /// assert_eq!(counter.len(), 5);
     /// ```
-    pub fn total<'a, S>(&'a self) -> S
+    pub fn total<'a, M>(&'a self) -> M
     where
-        S: iter::Sum<&'a N>,
+        M: iter::Sum<&'a N>,
     {
         self.map.values().sum()
     }
+
+    /// Returns the sum of the counts as an `M`, or `None` if it overflows `M`.
+    ///
+    /// Each count is converted to `M` before it is added, so a wider `M` can hold the total of
+    /// counts which would overflow `N`. A count which cannot be represented as an `M`, such as a
+    /// negative count when `M` is unsigned, also produces `None`.
+    ///
+    /// ```
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<char, u8>::new();
+    /// counter[&'a'] = 200;
+    /// counter[&'b'] = 100;
+    /// assert_eq!(counter.total_checked::<u8>(), None);
+    /// assert_eq!(counter.total_checked::<u16>(), Some(300));
+    /// ```
+    pub fn total_checked<M>(&self) -> Option<M>
+    where
+        M: CheckedAdd + NumCast + Zero,
+        N: ToPrimitive + Clone,
+    {
+        self.map.values().try_fold(M::zero(), |total, count| {
+            total.checked_add(&M::from(count.clone())?)
+        })
+    }
+
+    /// Returns the sum of the counts as an `M`, saturating at the bounds of `M`.
+    ///
+    /// Each count is converted to `M` before it is added, so a wider `M` can hold the total of
+    /// counts which would overflow `N`. A count which is too large or too small to be
+    /// represented as an `M` is clamped to `M`'s maximum or minimum value.
+    ///
+    /// ```
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<char, u8>::new();
+    /// counter[&'a'] = 200;
+    /// counter[&'b'] = 100;
+    /// assert_eq!(counter.total_saturating::<u8>(), 255);
+    /// assert_eq!(counter.total_saturating::<u16>(), 300);
+    /// ```
+    pub fn total_saturating<M>(&self) -> M
+    where
+        M: SaturatingAdd + Bounded + NumCast + Zero,
+        N: ToPrimitive + PartialOrd + Zero + Clone,
+    {
+        self.map.values().fold(M::zero(), |total, count| {
+            let count = M::from(count.clone()).unwrap_or_else(|| {
+                if *count < N::zero() {
+                    M::min_value()
+                } else {
+                    M::max_value()
+                }
+            });
+            total.saturating_add(&count)
+        })
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Tries to reserve capacity for at least `additional` more distinct items, returning an
+    /// error instead of aborting if the allocation fails.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Error};
+    /// let mut counter = Counter::<char>::new();
+    /// counter.try_reserve(10).unwrap();
+    /// assert!(counter.capacity() >= 10);
+    /// assert!(matches!(counter.try_reserve(usize::MAX), Err(Error::CapacityExhausted)));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CapacityExhausted`] if the capacity overflows or the allocator fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), Error> {
+        self.map
+            .try_reserve(additional)
+            .map_err(|_| Error::CapacityExhausted)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+{
+    /// Returns a vector of clones of the items in the counter, in arbitrary order.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut keys = counter.keys_cloned();
+    /// keys.sort();
+    /// assert_eq!(keys, ['a', 'b', 'c']);
+    /// ```
+    pub fn keys_cloned(&self) -> Vec<T> {
+        let mut keys = Vec::with_capacity(self.map.len());
+        keys.extend(self.map.keys().cloned());
+        keys
+    }
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Copy,
+{
+    /// Returns a vector of the counts in the counter, in arbitrary order.
+    ///
+    /// The counts are in the same order as the items returned by [`keys_cloned`], provided the
+    /// counter is not modified in between.
+    ///
+    /// [`keys_cloned`]: Counter::keys_cloned
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// let mut counts = counter.counts_copied();
+    /// counts.sort();
+    /// assert_eq!(counts, [1, 2, 3]);
+    /// ```
+    pub fn counts_copied(&self) -> Vec<N> {
+        let mut counts = Vec::with_capacity(self.map.len());
+        counts.extend(self.map.values().copied());
+        counts
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
     N: AddAssign + Zero + One,
+    S: BuildHasher,
 {
     /// Add the counts of the elements from the given iterable to this counter.
+    ///
+    /// Counts are added with `+=`, so an overflowing count wraps around in release builds; use
+    /// [`try_update`](Counter::try_update) to detect that instead.
     pub fn update<I>(&mut self, iterable: I)
     where
         I: IntoIterator<Item = T>,
@@ -344,16 +695,107 @@ where
             *entry += N::one();
         }
     }
+
+    /// Count the elements of the given iterable by the key `key` extracts from each of them.
+    ///
+    /// The elements themselves are not kept, so they need not be `Hash + Eq`, and only their
+    /// keys are stored.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut by_severity = Counter::<&str>::new();
+    /// let lines = ["WARN disk 91% full", "ERROR timeout", "WARN disk 93% full"];
+    /// by_severity.update_by(lines, |line| line.split_whitespace().next().unwrap_or(""));
+    /// assert_eq!(by_severity[&"WARN"], 2);
+    /// assert_eq!(by_severity[&"ERROR"], 1);
+    /// ```
+    pub fn update_by<I, F>(&mut self, iterable: I, mut key: F)
+    where
+        I: IntoIterator,
+        F: FnMut(&I::Item) -> T,
+    {
+        for element in iterable {
+            *self.map.entry(key(&element)).or_insert_with(N::zero) += N::one();
+        }
+    }
+}
+
+impl<A, B, N, S> Counter<(A, B), N, S>
+where
+    A: Hash + Eq,
+    B: Hash + Eq,
+    N: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Count the pairs formed by zipping the two given iterables together.
+    ///
+    /// As with [`Iterator::zip`], counting stops as soon as either iterable is exhausted.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut co_occurrences = Counter::<(char, u8)>::new();
+    /// co_occurrences.update_zipped("abab".chars(), [1, 2, 1, 2, 3]);
+    /// assert_eq!(co_occurrences[&('a', 1)], 2);
+    /// assert_eq!(co_occurrences[&('b', 2)], 2);
+    /// assert_eq!(co_occurrences.len(), 2);
+    /// ```
+    pub fn update_zipped<IA, IB>(&mut self, a: IA, b: IB)
+    where
+        IA: IntoIterator<Item = A>,
+        IB: IntoIterator<Item = B>,
+    {
+        for pair in a.into_iter().zip(b) {
+            *self.map.entry(pair).or_insert_with(N::zero) += N::one();
+        }
+    }
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<(T, T), N, S>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Count the pairs of adjacent elements of the given iterable, such as the transitions of
+    /// a sequence of states.
+    ///
+    /// An iterable of *n* elements contributes *n* - 1 pairs.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut bigrams = Counter::<(char, char)>::new();
+    /// bigrams.update_adjacent("abcab".chars());
+    /// assert_eq!(bigrams[&('a', 'b')], 2);
+    /// assert_eq!(bigrams[&('b', 'c')], 1);
+    /// assert_eq!(bigrams[&('c', 'a')], 1);
+    /// assert_eq!(bigrams.total::<usize>(), 4);
+    /// ```
+    pub fn update_adjacent<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = iterable.into_iter();
+        let Some(mut previous) = iter.next() else {
+            return;
+        };
+        for item in iter {
+            let pair = (previous, item.clone());
+            *self.map.entry(pair).or_insert_with(N::zero) += N::one();
+            previous = item;
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + SubAssign + Zero + One,
+    S: BuildHasher,
 {
     /// Remove the counts of the elements from the given iterable to this counter.
     ///
-    /// Non-positive counts are automatically removed.
+    /// Non-positive counts are automatically removed; use
+    /// [`subtract_keeping_zeros`](Counter::subtract_keeping_zeros) to keep them.
     ///
     /// ```rust
     /// # use counter::Counter;
@@ -382,7 +824,98 @@ where
     }
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero + Clone,
+    S: BuildHasher,
+{
+    /// Subtract up to `count` from the count of `item`, returning how much was actually
+    /// subtracted.
+    ///
+    /// The count stops at zero, in which case the item is removed and its previous count is
+    /// returned. Nothing is subtracted from an item which is missing or whose count is not
+    /// positive, though such an item is still removed.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<_>::from([('a', 5), ('b', 1)]);
+    /// assert_eq!(counter.remove_count(&'a', 3), 3);
+    /// assert_eq!(counter.remove_count(&'b', 3), 1);
+    /// assert_eq!(counter.remove_count(&'c', 3), 0);
+    /// assert_eq!(counter, Counter::from([('a', 2)]));
+    /// ```
+    pub fn remove_count<Q>(&mut self, item: &Q, count: N) -> N
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get_mut(item) {
+            Some(current) if *current > count => {
+                *current -= count.clone();
+                count
+            }
+            Some(_) => match self.map.remove(item) {
+                Some(removed) if removed > N::zero() => removed,
+                _ => N::zero(),
+            },
+            None => N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero,
+    S: BuildHasher,
+{
+    /// Set the count of `item` to `count`, returning its previous count.
+    ///
+    /// Unlike assigning through [`IndexMut`] or [`DerefMut`], a non-positive `count` removes
+    /// the item, as [`subtract`](Counter::subtract) would, so the counter never holds entries
+    /// with a zero count.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abb".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.set('a', 5), Some(1));
+    /// assert_eq!(counter.set('b', 0), Some(2));
+    /// assert_eq!(counter.set('c', 0), None);
+    /// assert_eq!(counter, Counter::from([('a', 5)]));
+    /// ```
+    ///
+    /// [`IndexMut`]: std::ops::IndexMut
+    /// [`DerefMut`]: std::ops::DerefMut
+    pub fn set(&mut self, item: T, count: N) -> Option<N> {
+        if count > N::zero() {
+            self.map.insert(item, count)
+        } else {
+            self.map.remove(&item)
+        }
+    }
+
+    /// Set the counts of many items at once, as by [`set`](Counter::set).
+    ///
+    /// If an item appears more than once, its last count wins.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abc".chars().collect::<Counter<_>>();
+    /// counter.set_many([('a', 3), ('b', 0), ('d', 1), ('d', 2)]);
+    /// assert_eq!(counter, Counter::from([('a', 3), ('c', 1), ('d', 2)]));
+    /// ```
+    pub fn set_many<I>(&mut self, pairs: I)
+    where
+        I: IntoIterator<Item = (T, N)>,
+    {
+        for (item, count) in pairs {
+            self.set(item, count);
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq + Clone,
     N: Clone + Ord,
@@ -398,7 +931,7 @@ where
     ///
     /// Note that the ordering of duplicates is unstable.
     pub fn most_common(&self) -> Vec<(T, N)> {
-        use std::cmp::Ordering;
+        use core::cmp::Ordering;
         self.most_common_tiebreaker(|_a, _b| Ordering::Equal)
     }
 
@@ -418,8 +951,9 @@ where
     /// ```
     pub fn most_common_tiebreaker<F>(&self, mut tiebreaker: F) -> Vec<(T, N)>
     where
-        F: FnMut(&T, &T) -> ::std::cmp::Ordering,
+        F: FnMut(&T, &T) -> ::core::cmp::Ordering,
     {
+        trace_span!(self.map.len(); "most_common", len = self.map.len());
         let mut items = self
             .map
             .iter()
@@ -434,7 +968,7 @@ where
     }
 }
 
-impl<T, N> Counter<T, N>
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq + Clone + Ord,
     N: Clone + Ord,
@@ -459,9 +993,15 @@ where
     /// the top *k* items and *k* < *n* then it can be more efficient to use
     /// [`k_most_common_ordered`].
     ///
+    /// When a large counter has only a few distinct counts, as after de-duplicating a large
+    /// input where most items were seen only a handful of times, the items are grouped by count
+    /// and each group is sorted by item alone, without comparing counts. The `most_common`
+    /// benchmark shows this taking half the time of sorting by count and item for thousands of
+    /// items, and about a sixth less for a million.
+    ///
     /// [`k_most_common_ordered`]: Counter::k_most_common_ordered
     pub fn most_common_ordered(&self) -> Vec<(T, N)> {
-        self.most_common_tiebreaker(Ord::cmp)
+        self.to_ordered_pairs(OrderBy::CountDesc, None)
     }
 
     /// Returns the `k` most common items in decreasing order of their counts.
@@ -498,57 +1038,471 @@ where
     /// be worth experimenting to see which of the two methods is faster.
     ///
     /// [`most_common_ordered`]: Counter::most_common_ordered
-    #[allow(clippy::missing_panics_doc)] // current implementation does not panic
     pub fn k_most_common_ordered(&self, k: usize) -> Vec<(T, N)> {
-        use std::cmp::Reverse;
+        self.to_ordered_pairs(OrderBy::CountDesc, Some(k))
+    }
+
+    /// Returns the `K` most common items, in the order of
+    /// [`k_most_common_ordered`](Counter::k_most_common_ordered), without allocating on the
+    /// heap.
+    ///
+    /// The selection is kept on the stack, and only the items selected are cloned, so this
+    /// suits small leaderboards computed on every event of a hot path. It takes *O*(*n* \* *K*)
+    /// time in the worst case, so for large *K*, prefer `k_most_common_ordered`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter: Counter<_> = "abracadabra".chars().collect();
+    /// let top3 = counter.k_most_common_smallvec::<3>();
+    /// assert!(!top3.spilled());
+    /// assert_eq!(top3.as_slice(), [('a', 5), ('b', 2), ('r', 2)]);
+    /// ```
+    #[cfg(feature = "smallvec")]
+    pub fn k_most_common_smallvec<const K: usize>(&self) -> SmallVec<[(T, N); K]> {
+        let mut top = SmallVec::<[(&T, &N); K]>::new();
+        for (item, count) in &self.map {
+            // Items before `rank` are more common, or as common and earlier in order.
+            let rank = top.partition_point(|&(top_item, top_count)| {
+                top_count > count || (top_count == count && top_item < item)
+            });
+            if rank < K {
+                if top.len() == K {
+                    top.pop();
+                }
+                top.insert(rank, (item, count));
+            }
+        }
+        top.into_iter()
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect()
+    }
+
+    /// Groups the items by their counts.
+    ///
+    /// Each count maps to the items which have it, sorted in increasing order. The counts are
+    /// wrapped in [`Reverse`], so the map iterates from the most common items to the least
+    /// common, like [`most_common`](Counter::most_common).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// use std::cmp::Reverse;
+    ///
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// let buckets = counter.most_common_buckets();
+    /// assert_eq!(buckets[&Reverse(2)], ['b', 'r']);
+    /// let counts = buckets.into_iter().map(|(Reverse(count), items)| (count, items));
+    /// assert_eq!(
+    ///     counts.collect::<Vec<_>>(),
+    ///     [(5, vec!['a']), (2, vec!['b', 'r']), (1, vec!['c', 'd'])],
+    /// );
+    /// ```
+    pub fn most_common_buckets(&self) -> BTreeMap<Reverse<N>, Vec<T>> {
+        let mut buckets = BTreeMap::<Reverse<N>, Vec<T>>::new();
+        for (item, count) in &self.map {
+            buckets
+                .entry(Reverse(count.clone()))
+                .or_default()
+                .push(item.clone());
+        }
+        for items in buckets.values_mut() {
+            items.sort_unstable();
+        }
+        buckets
+    }
 
+    /// Returns the `k` most common items within each group, where `group` maps each item to
+    /// its group.
+    ///
+    /// Each group's items are in the order [`k_most_common_ordered(k)`] would return them if
+    /// the counter held only that group, so ties are broken by the natural ordering of the
+    /// items. Groups are only present if they have at least one item, and the map is empty if
+    /// `k` is zero.
+    ///
+    /// The counter is scanned once, keeping a heap of at most `k` items for each group, so only
+    /// the selected items are cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let errors: Counter<_> = Counter::from([
+    ///     (("auth", "timeout"), 12),
+    ///     (("auth", "bad token"), 30),
+    ///     (("auth", "rate limited"), 4),
+    ///     (("billing", "declined"), 7),
+    /// ]);
+    ///
+    /// let top = errors.k_most_common_by_group(|&(service, _)| service, 2);
+    /// assert_eq!(top["auth"], [(("auth", "bad token"), 30), (("auth", "timeout"), 12)]);
+    /// assert_eq!(top["billing"], [(("billing", "declined"), 7)]);
+    /// ```
+    ///
+    /// [`k_most_common_ordered(k)`]: Counter::k_most_common_ordered
+    pub fn k_most_common_by_group<G, F>(&self, mut group: F, k: usize) -> HashMap<G, Vec<(T, N)>>
+    where
+        G: Hash + Eq,
+        F: FnMut(&T) -> G,
+    {
         if k == 0 {
-            return vec![];
+            return HashMap::default();
         }
+        let mut heaps = HashMap::<G, BinaryHeap<_>>::default();
+        for (item, count) in &self.map {
+            let entry = (Reverse(count.clone()), item);
+            let heap = heaps.entry(group(item)).or_default();
+            if heap.len() < k {
+                heap.push(entry);
+            } else {
+                let mut root = heap.peek_mut().expect("the heap is empty");
+                if *root > entry {
+                    *root = entry;
+                }
+            }
+        }
+        heaps
+            .into_iter()
+            .map(|(group, heap)| {
+                let items = heap
+                    .into_sorted_vec()
+                    .into_iter()
+                    .map(|(Reverse(count), item)| (item.clone(), count))
+                    .collect();
+                (group, items)
+            })
+            .collect()
+    }
+}
 
-        // The quicksort implementation used by `most_common_ordered()` is generally faster than
-        // the heapsort used below when sorting the entire counter.
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: Ord + Clone,
+    S: BuildHasher,
+{
+    /// Removes the `k` most common items from the counter and returns them in decreasing order
+    /// of their counts.
+    ///
+    /// The items and their order are the same as [`k_most_common_ordered`] would return, but
+    /// the items are moved out of the counter rather than cloned, and the rest are left in
+    /// place. This suits "peel off the heaviest, then reprocess the rest" workflows.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.drain_most_common(2), [('a', 5), ('b', 2)]);
+    /// assert_eq!(counter, "rrcd".chars().collect());
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* + *k* \* log *k*), where *n* is the number of items in the counter, plus the
+    /// cost of sorting the items whose count ties with the *k*th most common.
+    ///
+    /// [`k_most_common_ordered`]: Counter::k_most_common_ordered
+    pub fn drain_most_common(&mut self, k: usize) -> Vec<(T, N)> {
+        let by_count_desc = |a: &(T, N), b: &(T, N)| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0));
         if k >= self.map.len() {
-            return self.most_common_ordered();
+            let mut drained = self.map.drain().collect::<Vec<_>>();
+            drained.sort_unstable_by(by_count_desc);
+            return drained;
+        }
+        if k == 0 {
+            return Vec::new();
         }
 
-        // Clone the counts as we iterate over the map to eliminate an extra indirection when
-        // comparing counts.  This will be an improvement in the typical case where `N: Copy`.
-        // Defer cloning the keys until we have selected the top `k` items so that we clone only
-        // `k` keys instead of all of them.
-        let mut items = self.map.iter().map(|(t, n)| (Reverse(n.clone()), t));
-
-        // Step 1. Make a heap out of the first `k` items; this makes O(k) comparisons.
-        let mut heap: BinaryHeap<_> = items.by_ref().take(k).collect();
-
-        // Step 2. Successively compare each of the remaining `n - k` items to the top of the heap,
-        // replacing the root (and subsequently sifting down) whenever the item is less than the
-        // root.  This takes at most n - k + k * (1 + log2(k)) * (H(n) - H(k)) comparisons, where
-        // H(i) is the ith [harmonic number](https://en.wikipedia.org/wiki/Harmonic_number).  For
-        // fixed `k`, this scales as *n* + *O*(log(*n*)).
-        items.for_each(|item| {
-            // If `items` is nonempty at this point then we know the heap contains `k > 0`
-            // elements.
-            let mut root = heap.peek_mut().expect("the heap is empty");
-            if *root > item {
-                *root = item;
-            }
-        });
+        // Every item at least as common as the kth is drained; the rest, and the ties which do
+        // not make the cut by the order of their keys, are put back. `retain` can't move the
+        // items out, so the whole counter is drained, keeping its allocation.
+        let mut counts = self.map.values().collect::<Vec<_>>();
+        let (_, threshold, _) = counts.select_nth_unstable_by(k - 1, |a, b| b.cmp(a));
+        let threshold = (*threshold).clone();
+        let (mut drained, kept): (Vec<_>, Vec<_>) =
+            self.map.drain().partition(|(_, count)| *count >= threshold);
+        self.map.extend(kept);
+        drained.sort_unstable_by(by_count_desc);
+        self.map.extend(drained.drain(k..));
+        drained
+    }
 
-        // Step 3. Sort the items in the heap with the second phases of heapsort.  The number of
-        // comparisons is 2 * k * log2(k) + O(k).
-        heap.into_sorted_vec()
+    /// Removes the most common item from the counter and returns it with its count, or `None`
+    /// if the counter is empty.
+    ///
+    /// Ties are broken in favour of the lowest item, so repeated calls remove items in the
+    /// order of [`most_common_ordered`](Counter::most_common_ordered).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbcc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.pop_most_common(), Some(('b', 2)));
+    /// assert_eq!(counter.pop_most_common(), Some(('c', 2)));
+    /// assert_eq!(counter.pop_most_common(), Some(('a', 1)));
+    /// assert_eq!(counter.pop_most_common(), None);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*), where *n* is the number of items in the counter. To remove many items in
+    /// order, [`drain_most_common`](Counter::drain_most_common) is faster.
+    pub fn pop_most_common(&mut self) -> Option<(T, N)>
+    where
+        T: Clone,
+    {
+        self.pop_by(|a, b| b.cmp(a))
+    }
+
+    /// Removes the least common item from the counter and returns it with its count, or `None`
+    /// if the counter is empty.
+    ///
+    /// Ties are broken in favour of the lowest item.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbcc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.pop_least_common(), Some(('a', 1)));
+    /// assert_eq!(counter.pop_least_common(), Some(('b', 2)));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n*), where *n* is the number of items in the counter.
+    pub fn pop_least_common(&mut self) -> Option<(T, N)>
+    where
+        T: Clone,
+    {
+        self.pop_by(Ord::cmp)
+    }
+
+    /// Removes the item whose count comes first by `compare`, breaking ties by the items.
+    fn pop_by<F>(&mut self, compare: F) -> Option<(T, N)>
+    where
+        T: Clone,
+        F: Fn(&N, &N) -> core::cmp::Ordering,
+    {
+        let (target, _) = self
+            .map
+            .iter()
+            .min_by(|a, b| compare(a.1, b.1).then_with(|| a.0.cmp(b.0)))?;
+        let target = target.clone();
+        self.map.remove_entry(&target)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Clone,
+{
+    /// Returns the first `k` `(elem, frequency)` pairs in the order defined by `compare`.
+    ///
+    /// The returned vector is the same as would be obtained by sorting every pair with
+    /// `compare` and then truncating the result to length `k`, except that the order of pairs
+    /// which compare equal is unspecified. Like [`k_most_common_ordered`], this uses a bounded
+    /// heap, so only the selected items are cloned and the counter is never fully sorted.
+    ///
+    /// For example, the two longest words, breaking ties by count and then alphabetically:
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "a bb bb ccc dd eee eee".split_whitespace().collect::<Counter<_>>();
+    /// let longest = counter.k_most_common_by(2, |(a, a_count), (b, b_count)| {
+    ///     b.len().cmp(&a.len()).then(b_count.cmp(a_count)).then(a.cmp(b))
+    /// });
+    /// assert_eq!(longest, vec![("eee", 2), ("ccc", 1)]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* \* log *k*), where *n* is the number of items in the counter.
+    ///
+    /// [`k_most_common_ordered`]: Counter::k_most_common_ordered
+    pub fn k_most_common_by<F>(&self, k: usize, compare: F) -> Vec<(T, N)>
+    where
+        F: FnMut((&T, &N), (&T, &N)) -> core::cmp::Ordering,
+    {
+        k_smallest_by(self.map.iter(), k, compare)
             .into_iter()
-            .map(|(Reverse(n), t)| (t.clone(), n))
+            .map(|(item, count)| (item.clone(), count.clone()))
             .collect()
     }
 }
 
-impl<T, N> Counter<T, N>
+/// Orders partially ordered counts from greatest to least, with counts which are not comparable
+/// even to themselves, such as NaN, after all the others.
+fn partial_cmp_desc<N: PartialOrd>(a: &N, b: &N) -> core::cmp::Ordering {
+    use core::cmp::Ordering;
+
+    match (a.partial_cmp(a).is_some(), b.partial_cmp(b).is_some()) {
+        (true, true) => b.partial_cmp(a).unwrap_or(Ordering::Equal),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => Ordering::Equal,
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Clone + PartialOrd,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common, for counts
+    /// which are only [`PartialOrd`], such as floats.
+    ///
+    /// Counts which are not comparable even to themselves, such as `f64::NAN`, are placed after
+    /// all the others. Items with equal counts are in arbitrary order. To rank floats by
+    /// [`f64::total_cmp`] instead, pass it to [`k_most_common_by`](Counter::k_most_common_by).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// # #[cfg(feature = "num")] {
+    /// let mut weights = Counter::<_, f64>::new();
+    /// weights[&'a'] = 0.25;
+    /// weights[&'b'] = f64::NAN;
+    /// weights[&'c'] = 0.5;
+    /// let ranked = weights.most_common_by_partial_ord();
+    /// assert_eq!(ranked[..2], [('c', 0.5), ('a', 0.25)]);
+    /// assert_eq!(ranked[2].0, 'b');
+    /// # }
+    /// ```
+    pub fn most_common_by_partial_ord(&self) -> Vec<(T, N)> {
+        trace_span!(
+            self.map.len();
+            "most_common_by_partial_ord",
+            len = self.map.len()
+        );
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        items.sort_unstable_by(|(_, a), (_, b)| partial_cmp_desc(a, b));
+        items
+    }
+
+    /// Returns the `k` most common items, in the order of
+    /// [`most_common_by_partial_ord`](Counter::most_common_by_partial_ord).
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* \* log *k*), where *n* is the number of items in the counter.
+    pub fn k_most_common_by_partial_ord(&self, k: usize) -> Vec<(T, N)> {
+        self.k_most_common_by(k, |(_, a), (_, b)| partial_cmp_desc(a, b))
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Clone + Ord,
+{
+    /// Returns the `k` most common items in decreasing order of their counts, using the
+    /// supplied ordering function to arrange items with equal counts.
+    ///
+    /// The returned vector is the same as would be obtained by calling
+    /// [`most_common_tiebreaker`] and then truncating the result to length `k`, but uses the
+    /// same bounded-heap selection as [`k_most_common_by`].
+    ///
+    /// For example, preferring the longest of equally common words:
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "a bbb cc a bbb cc dddd".split_whitespace().collect::<Counter<_>>();
+    /// let top = counter.k_most_common_tiebreaker(2, |a, b| b.len().cmp(&a.len()));
+    /// assert_eq!(top, vec![("bbb", 2), ("cc", 2)]);
+    /// ```
+    ///
+    /// [`most_common_tiebreaker`]: Counter::most_common_tiebreaker
+    /// [`k_most_common_by`]: Counter::k_most_common_by
+    pub fn k_most_common_tiebreaker<F>(&self, k: usize, mut tiebreaker: F) -> Vec<(T, N)>
+    where
+        F: FnMut(&T, &T) -> core::cmp::Ordering,
+    {
+        self.k_most_common_by(k, |(a_item, a_count), (b_item, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| tiebreaker(a_item, b_item))
+        })
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
 where
     T: Hash + Eq,
     N: PartialOrd + Zero,
+    S: BuildHasher,
 {
+    /// Returns `true` if `key` has a positive count.
+    ///
+    /// Unlike [`contains_key`], which the counter derefs to, this treats an entry with a count
+    /// of zero or less, as left by [`IndexMut`] or a signed count type, as absent.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "ab".chars().collect::<Counter<_, i32>>();
+    /// counter[&'b'] -= 1;
+    /// counter[&'c'] -= 1;
+    ///
+    /// assert!(counter.contains(&'a'));
+    /// assert!(!counter.contains(&'b'));
+    /// assert!(counter.contains_key(&'b'));
+    /// assert!(!counter.contains(&'c'));
+    /// assert!(!counter.contains(&'z'));
+    /// ```
+    ///
+    /// [`contains_key`]: std::collections::HashMap::contains_key
+    /// [`IndexMut`]: std::ops::IndexMut
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).is_some_and(|count| *count > N::zero())
+    }
+
+    /// Returns an iterator over the items with a positive count and their counts, in arbitrary
+    /// order: the support of the multiset.
+    ///
+    /// Entries with a count of zero or less, as left by [`IndexMut`] or a signed count type,
+    /// are skipped, so this agrees with [`contains`](Counter::contains).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "aab".chars().collect::<Counter<_, i32>>();
+    /// counter[&'b'] -= 1;
+    /// counter[&'c'] -= 2;
+    ///
+    /// assert_eq!(counter.len(), 3);
+    /// assert_eq!(counter.positive_iter().collect::<Vec<_>>(), [(&'a', &2)]);
+    /// ```
+    ///
+    /// [`IndexMut`]: std::ops::IndexMut
+    pub fn positive_iter(&self) -> impl Iterator<Item = (&T, &N)> + '_ {
+        self.map.iter().filter(|(_, count)| **count > N::zero())
+    }
+
+    /// Returns an iterator over the items with a positive count, in arbitrary order.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_, i32>::from([('a', 1), ('b', 0)]);
+    /// assert_eq!(counter.positive_keys().collect::<Vec<_>>(), [&'a']);
+    /// ```
+    pub fn positive_keys(&self) -> impl Iterator<Item = &T> + '_ {
+        self.positive_iter().map(|(item, _)| item)
+    }
+
+    /// Returns an iterator over the positive counts, in arbitrary order.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<_, i32>::from([('a', 3), ('b', -1)]);
+    /// assert_eq!(counter.positive_values().sum::<i32>(), 3);
+    /// ```
+    pub fn positive_values(&self) -> impl Iterator<Item = &N> + '_ {
+        self.positive_iter().map(|(_, count)| count)
+    }
+
     /// Test whether this counter is a superset of another counter.
     /// This is true if for all elements in this counter and the other,
     /// the count in this counter is greater than or equal to the count in the other.
@@ -565,7 +1519,26 @@ where
     /// d[&'e'] = 1;
     /// assert!(!c.is_superset(&d));
     /// ```
+    ///
+    /// To compare with a counter which uses a different hasher, use
+    /// [`is_superset_with_hasher`](Counter::is_superset_with_hasher).
     pub fn is_superset(&self, other: &Self) -> bool {
+        self.is_superset_with_hasher(other)
+    }
+
+    /// Test whether this counter is a superset of another counter, which may use a different
+    /// hasher, as [`is_superset`](Counter::is_superset) does.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, SeededState};
+    /// let c = "aaabbc".chars().collect::<Counter<_>>();
+    /// let d = "abb".chars().collect::<Counter<_, usize, SeededState>>();
+    /// assert!(c.is_superset_with_hasher(&d));
+    /// ```
+    pub fn is_superset_with_hasher<S2>(&self, other: &Counter<T, N, S2>) -> bool
+    where
+        S2: BuildHasher,
+    {
         // need to test keys from both counters, because if N is signed, counts in `self`
         // could be < 0 for elements missing in `other`. For the unsigned case, only elements
         // from `other` would need to be tested.
@@ -590,7 +1563,26 @@ where
     /// c[&'e'] = 1;
     /// assert!(!c.is_subset(&d));
     /// ```
+    ///
+    /// To compare with a counter which uses a different hasher, use
+    /// [`is_subset_with_hasher`](Counter::is_subset_with_hasher).
     pub fn is_subset(&self, other: &Self) -> bool {
+        self.is_subset_with_hasher(other)
+    }
+
+    /// Test whether this counter is a subset of another counter, which may use a different
+    /// hasher, as [`is_subset`](Counter::is_subset) does.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, SeededState};
+    /// let c = "abb".chars().collect::<Counter<_>>();
+    /// let d = "aaabbc".chars().collect::<Counter<_, usize, SeededState>>();
+    /// assert!(c.is_subset_with_hasher(&d));
+    /// ```
+    pub fn is_subset_with_hasher<S2>(&self, other: &Counter<T, N, S2>) -> bool
+    where
+        S2: BuildHasher,
+    {
         // need to test keys from both counters, because if N is signed, counts in `other`
         // could be < 0 for elements missing in `self`. For the unsigned case, only elements
         // from `self` would need to be tested.
@@ -599,3 +1591,100 @@ where
             .all(|key| self[key] <= other[key])
     }
 }
+
+/// Select the `k` most common of `items`, where `0 < k < items.len()`, breaking ties by the
+/// natural ordering of the keys.
+///
+/// See [`Counter::k_most_common_ordered`] for the analysis of this algorithm.
+fn k_most_common_ordered<'a, T, N, I>(items: I, k: usize) -> Vec<(T, N)>
+where
+    T: 'a + Clone + Ord,
+    N: 'a + Clone + Ord,
+    I: Iterator<Item = (&'a T, &'a N)>,
+{
+    // Clone the counts as we iterate over the map to eliminate an extra indirection when
+    // comparing counts.  This will be an improvement in the typical case where `N: Copy`.
+    // Defer cloning the keys until we have selected the top `k` items so that we clone only
+    // `k` keys instead of all of them.
+    let mut items = items.map(|(t, n)| (Reverse(n.clone()), t));
+
+    // Step 1. Make a heap out of the first `k` items; this makes O(k) comparisons.
+    let mut heap: BinaryHeap<_> = items.by_ref().take(k).collect();
+
+    // Step 2. Successively compare each of the remaining `n - k` items to the top of the heap,
+    // replacing the root (and subsequently sifting down) whenever the item is less than the
+    // root.  This takes at most n - k + k * (1 + log2(k)) * (H(n) - H(k)) comparisons, where
+    // H(i) is the ith [harmonic number](https://en.wikipedia.org/wiki/Harmonic_number).  For
+    // fixed `k`, this scales as *n* + *O*(log(*n*)).
+    items.for_each(|item| {
+        // If `items` is nonempty at this point then we know the heap contains `k > 0`
+        // elements.
+        let mut root = heap.peek_mut().expect("the heap is empty");
+        if *root > item {
+            *root = item;
+        }
+    });
+
+    // Step 3. Sort the items in the heap with the second phases of heapsort.  The number of
+    // comparisons is 2 * k * log2(k) + O(k).
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|(Reverse(n), t)| (t.clone(), n))
+        .collect()
+}
+
+/// Select the `k` smallest of `items` according to `compare`, in increasing order.
+///
+/// A max-heap of the `k` smallest items seen so far is kept in a vector; each further item
+/// which is smaller than the root replaces it. This is the algorithm of
+/// [`k_most_common_ordered`](fn@k_most_common_ordered), with a comparison function in place of
+/// `Ord`.
+fn k_smallest_by<'a, T, N, I, F>(items: I, k: usize, mut compare: F) -> Vec<(&'a T, &'a N)>
+where
+    I: Iterator<Item = (&'a T, &'a N)>,
+    F: FnMut((&T, &N), (&T, &N)) -> core::cmp::Ordering,
+{
+    use core::cmp::Ordering;
+
+    if k == 0 {
+        return Vec::new();
+    }
+
+    fn sift_down<T, F>(heap: &mut [T], mut parent: usize, compare: &mut F)
+    where
+        T: Copy,
+        F: FnMut(T, T) -> Ordering,
+    {
+        loop {
+            let left = 2 * parent + 1;
+            if left >= heap.len() {
+                return;
+            }
+            let right = left + 1;
+            let child = if right < heap.len() && compare(heap[right], heap[left]).is_gt() {
+                right
+            } else {
+                left
+            };
+            if compare(heap[child], heap[parent]).is_le() {
+                return;
+            }
+            heap.swap(parent, child);
+            parent = child;
+        }
+    }
+
+    let mut items = items;
+    let mut heap: Vec<_> = items.by_ref().take(k).collect();
+    for parent in (0..heap.len() / 2).rev() {
+        sift_down(&mut heap, parent, &mut compare);
+    }
+    for item in items {
+        if compare(item, heap[0]) == Ordering::Less {
+            heap[0] = item;
+            sift_down(&mut heap, 0, &mut compare);
+        }
+    }
+    heap.sort_unstable_by(|&a, &b| compare(a, b));
+    heap
+}
@@ -0,0 +1,208 @@
+//! Counting the transitions between the states of a sequence, as for a Markov chain.
+//!
+//! A [`TransitionCounter`] keeps one [`Counter`] of next states for every state which has been
+//! seen to transition to another, so the empirical transition probabilities out of any state
+//! can be read off directly.
+//!
+//! ```rust
+//! # use counter::markov::TransitionCounter;
+//! let chain = "the cat sat on the mat"
+//!     .split_whitespace()
+//!     .collect::<TransitionCounter<_>>();
+//!
+//! assert_eq!(chain.count(&"the", &"cat"), 1);
+//! assert_eq!(chain.most_likely_next(&"sat"), Some(&"on"));
+//! assert_eq!(chain.probabilities_from(&"the")[&"mat"], 0.5);
+//! assert_eq!(chain.most_likely_next(&"mat"), None);
+//! ```
+
+use crate::collections::HashMap;
+use crate::frequencies::count_to_f64;
+use crate::Counter;
+
+use crate::num::{One, ToPrimitive, Zero};
+
+use core::borrow::Borrow;
+use core::hash::Hash;
+use core::iter::FromIterator;
+use core::ops::AddAssign;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Counts of the transitions `state -> next_state` observed in one or more sequences.
+///
+/// With the `serde` feature, serializes as a map from each state to the counter of its next
+/// states.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        transparent,
+        bound(
+            serialize = "T: Serialize, N: Serialize",
+            deserialize = "T: Deserialize<'de>, Counter<T, N>: Deserialize<'de>"
+        )
+    )
+)]
+pub struct TransitionCounter<T: Hash + Eq, N = usize> {
+    transitions: HashMap<T, Counter<T, N>>,
+}
+
+impl<T, N> TransitionCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty `TransitionCounter`.
+    pub fn new() -> Self {
+        TransitionCounter {
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Consumes the `TransitionCounter` and returns a map from each state to the counter of its
+    /// next states.
+    pub fn into_map(self) -> HashMap<T, Counter<T, N>> {
+        self.transitions
+    }
+
+    /// Returns the counter of the states which followed `state`, or `None` if no transition
+    /// out of `state` has been seen.
+    pub fn transitions_from<Q>(&self, state: &Q) -> Option<&Counter<T, N>>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.transitions.get(state)
+    }
+
+    /// Returns an iterator over the states from which at least one transition has been seen,
+    /// in arbitrary order.
+    pub fn states(&self) -> impl Iterator<Item = &T> {
+        self.transitions.keys()
+    }
+}
+
+impl<T, N> Default for TransitionCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N> TransitionCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + Zero,
+{
+    /// Returns the number of times `from` was followed by `to`.
+    pub fn count<Q>(&self, from: &Q, to: &Q) -> N
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.transitions
+            .get(from)
+            .and_then(|next| next.get(to))
+            .cloned()
+            .unwrap_or_else(N::zero)
+    }
+}
+
+impl<T, N> TransitionCounter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+{
+    /// Count a single transition from `from` to `to`.
+    pub fn add_transition(&mut self, from: T, to: T) {
+        let next = self.transitions.entry(from).or_insert_with(Counter::new);
+        *next.map.entry(to).or_insert_with(N::zero) += N::one();
+    }
+}
+
+impl<T, N> TransitionCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+{
+    /// Count the transitions between adjacent elements of the given sequence.
+    ///
+    /// Separate calls count separate sequences: no transition is counted from the last element
+    /// of one sequence to the first element of the next.
+    pub fn update<I>(&mut self, sequence: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut iter = sequence.into_iter();
+        let Some(mut previous) = iter.next() else {
+            return;
+        };
+        for state in iter {
+            self.add_transition(previous, state.clone());
+            previous = state;
+        }
+    }
+}
+
+impl<T, N> FromIterator<T> for TransitionCounter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+{
+    /// Count the transitions of a single sequence.
+    fn from_iter<I: IntoIterator<Item = T>>(sequence: I) -> Self {
+        let mut counter = TransitionCounter::new();
+        counter.update(sequence);
+        counter
+    }
+}
+
+impl<T, N> TransitionCounter<T, N>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+{
+    /// Returns the empirical probability of each state which followed `state`: the number of
+    /// transitions to it divided by the number of transitions out of `state`.
+    ///
+    /// The map is empty if no transition out of `state` has been seen.
+    pub fn probabilities_from<Q>(&self, state: &Q) -> HashMap<&T, f64>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(next) = self.transitions.get(state) else {
+            return HashMap::new();
+        };
+        let total: f64 = next.values().map(count_to_f64).sum();
+        next.iter()
+            .map(|(item, count)| (item, count_to_f64(count) / total))
+            .collect()
+    }
+}
+
+impl<T, N> TransitionCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Ord,
+{
+    /// Returns the state which most often followed `state`, or `None` if no transition out of
+    /// `state` has been seen.
+    ///
+    /// If several states are equally likely, which of them is returned is unspecified.
+    pub fn most_likely_next<Q>(&self, state: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.transitions
+            .get(state)?
+            .iter()
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(item, _)| item)
+    }
+}
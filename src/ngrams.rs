@@ -0,0 +1,143 @@
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use alloc::vec::Vec;
+use core::array;
+use core::hash::{BuildHasher, Hash};
+use core::ops::AddAssign;
+
+/// Calls `count` with each window of `n` consecutive elements of `iterable`.
+fn for_each_window<T, I, F>(iterable: I, n: usize, mut count: F)
+where
+    I: IntoIterator<Item = T>,
+    F: FnMut(&[T]),
+{
+    assert!(n > 0, "n-grams must have a length of at least one");
+    // The window is the tail of a buffer of up to `2 * n` elements, so that it is contiguous.
+    // Shifting the last `n - 1` elements back to the start every `n` elements costs about one
+    // move per element.
+    let mut buffer = Vec::with_capacity(2 * n);
+    for item in iterable {
+        if buffer.len() == 2 * n {
+            buffer.drain(..n + 1);
+        }
+        buffer.push(item);
+        if buffer.len() >= n {
+            count(&buffer[buffer.len() - n..]);
+        }
+    }
+}
+
+impl<T, N> Counter<Vec<T>, N>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the n-grams of the given iterable: its windows of `n`
+    /// consecutive elements.
+    ///
+    /// An iterable of *m* ≥ *n* elements contributes *m* - *n* + 1 n-grams. A vector is only
+    /// allocated for each distinct n-gram; when `n` is known at compile time,
+    /// [`from_array_ngrams`](Counter::from_array_ngrams) avoids even those.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let trigrams = Counter::<Vec<_>>::from_ngrams("the cat and the cat sat".split(' '), 3);
+    /// assert_eq!(trigrams[&vec!["the", "cat", "and"]], 1);
+    /// assert_eq!(trigrams.total::<usize>(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn from_ngrams<I>(iterable: I, n: usize) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter = Counter::new();
+        counter.update_ngrams(iterable, n);
+        counter
+    }
+}
+
+impl<T, N, S> Counter<Vec<T>, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Count the n-grams of the given iterable: its windows of `n` consecutive elements.
+    ///
+    /// N-grams do not span calls, so each call counts the n-grams of a separate sequence, such
+    /// as a sentence. Each window is looked up as a slice, so a vector is only allocated for an
+    /// n-gram which the counter does not hold yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn update_ngrams<I>(&mut self, iterable: I, n: usize)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for_each_window(iterable, n, |window| match self.map.get_mut(window) {
+            Some(count) => *count += N::one(),
+            None => {
+                self.map.insert(window.to_vec(), N::one());
+            }
+        });
+    }
+}
+
+impl<T, N, const K: usize> Counter<[T; K], N>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+{
+    /// Create a new `Counter` of the n-grams of the given iterable, as
+    /// [`from_ngrams`](Counter::from_ngrams) does, with each n-gram stored as an array of
+    /// length `K`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let bigrams = Counter::<[char; 2]>::from_array_ngrams("banana".chars());
+    /// assert_eq!(bigrams[&['a', 'n']], 2);
+    /// assert_eq!(bigrams[&['n', 'a']], 2);
+    /// assert_eq!(bigrams[&['b', 'a']], 1);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is zero.
+    pub fn from_array_ngrams<I>(iterable: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let mut counter = Counter::new();
+        counter.update_array_ngrams(iterable);
+        counter
+    }
+}
+
+impl<T, N, S, const K: usize> Counter<[T; K], N, S>
+where
+    T: Hash + Eq + Clone,
+    N: AddAssign + Zero + One,
+    S: BuildHasher,
+{
+    /// Count the n-grams of the given iterable, as [`update_ngrams`](Counter::update_ngrams)
+    /// does, with each n-gram stored as an array of length `K`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `K` is zero.
+    pub fn update_array_ngrams<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for_each_window(iterable, K, |window| {
+            let ngram = array::from_fn(|index| window[index].clone());
+            *self.map.entry(ngram).or_insert_with(N::zero) += N::one();
+        });
+    }
+}
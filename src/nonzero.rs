@@ -0,0 +1,108 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::num::NonZeroUsize;
+
+/// How [`Counter::into_nonzero_map`] treats items whose count is zero or negative.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum NonZeroPolicy {
+    /// Silently leave such items out of the map.
+    Drop,
+    /// Fail with a [`NonZeroCountError`] naming the first such item encountered.
+    Reject,
+}
+
+/// An item whose count could not be represented as a [`NonZeroUsize`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NonZeroCountError<T, N> {
+    /// The offending item.
+    pub item: T,
+    /// Its count.
+    pub count: N,
+}
+
+impl<T, N> fmt::Display for NonZeroCountError<T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "count {:?} of item {:?} is not a non-zero usize",
+            self.count, self.item
+        )
+    }
+}
+
+impl<T, N> core::error::Error for NonZeroCountError<T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero + Clone,
+    S: BuildHasher + Clone,
+    usize: TryFrom<N>,
+{
+    /// Consumes this counter and returns a [`HashMap`] whose counts are [`NonZeroUsize`].
+    ///
+    /// This lets APIs which encode "present implies at least one" in their types consume a
+    /// counter directly. Zero and negative counts, which can appear through `IndexMut`,
+    /// `DerefMut` or signed count types, are handled according to `policy`. Positive counts
+    /// which do not fit in a `usize` are always an error. The map uses a clone of the
+    /// counter's hasher.
+    ///
+    /// [`HashMap`]: https://doc.rust-lang.org/stable/std/collections/struct.HashMap.html
+    ///
+    /// ```rust
+    /// # use counter::{Counter, NonZeroPolicy};
+    /// # use std::num::NonZeroUsize;
+    /// let mut counter = "aab".chars().collect::<Counter<_, i32>>();
+    /// counter[&'c'] = 0;
+    ///
+    /// let map = counter.clone().into_nonzero_map(NonZeroPolicy::Drop).unwrap();
+    /// assert_eq!(map.len(), 2);
+    /// assert_eq!(map[&'a'], NonZeroUsize::new(2).unwrap());
+    ///
+    /// let err = counter.into_nonzero_map(NonZeroPolicy::Reject).unwrap_err();
+    /// assert_eq!((err.item, err.count), ('c', 0));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NonZeroCountError`] if some count is non-positive and `policy` is
+    /// [`NonZeroPolicy::Reject`], or if some count is too large for a `usize`.
+    pub fn into_nonzero_map(
+        self,
+        policy: NonZeroPolicy,
+    ) -> Result<HashMap<T, NonZeroUsize, S>, NonZeroCountError<T, N>> {
+        let mut map = HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        for (item, count) in self.map {
+            if count <= N::zero() {
+                match policy {
+                    NonZeroPolicy::Drop => continue,
+                    NonZeroPolicy::Reject => return Err(NonZeroCountError { item, count }),
+                }
+            }
+            match usize::try_from(count.clone())
+                .ok()
+                .and_then(NonZeroUsize::new)
+            {
+                Some(nonzero) => {
+                    map.insert(item, nonzero);
+                }
+                None => return Err(NonZeroCountError { item, count }),
+            }
+        }
+        Ok(map)
+    }
+}
@@ -0,0 +1,271 @@
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{AddAssign, Deref, Index, IndexMut, SubAssign};
+
+/// A counter which passes every item through a normalizing function before counting it, so
+/// that equivalent items, such as words differing only in case, are always counted together.
+///
+/// Every way of counting through this type normalizes: [`add`](NormalizingCounter::add),
+/// [`update`](NormalizingCounter::update), [`subtract`](NormalizingCounter::subtract),
+/// [`Extend`], `+=` and `-=` with a [`Counter`], and indexing. Lookups with
+/// [`get`](NormalizingCounter::get) and indexing normalize the item they are given too, so
+/// `counter["Apple".into()]` finds the count of `"apple"`.
+///
+/// The wrapped counter can be read through [`Deref`], but there is deliberately no `DerefMut`:
+/// changing the counts through a plain `&mut Counter` would bypass the normalizer. Note that
+/// methods reached through `Deref` compare items as they are stored, without normalizing them.
+///
+/// ```rust
+/// # use counter::NormalizingCounter;
+/// let mut words = NormalizingCounter::new(|word: String| word.trim().to_lowercase());
+/// words.update(["Apple", " apple", "APPLE ", "pear"].map(String::from));
+/// words[" Pear".into()] += 2;
+///
+/// assert_eq!(*words.get("apple".into()), 3);
+/// assert_eq!(words.most_common_ordered(), [("apple".into(), 3), ("pear".into(), 3)]);
+/// ```
+///
+/// The normalizer can be any function from an item to an item, such as Unicode normalization
+/// with the `unicode-normalization` crate: `|s: String| s.nfc().collect()`.
+#[derive(Clone)]
+pub struct NormalizingCounter<T: Hash + Eq, F, N = usize> {
+    counter: Counter<T, N>,
+    normalize: F,
+}
+
+impl<T, F, N> NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: Zero,
+{
+    /// Create a new, empty `NormalizingCounter`, which normalizes items with `normalize`.
+    pub fn new(normalize: F) -> Self {
+        NormalizingCounter {
+            counter: Counter::new(),
+            normalize,
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Consumes this counter and wraps it in a [`NormalizingCounter`], which normalizes every
+    /// item with `normalize`.
+    ///
+    /// The items already counted are normalized too, and the counts of those which normalize
+    /// to the same item are summed.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = ["Cat", "cat", "dog"].map(String::from).into_iter().collect::<Counter<_>>();
+    /// let normalized = counter.with_normalizer(|word: String| word.to_lowercase());
+    /// assert_eq!(normalized["CAT".into()], 2);
+    /// assert_eq!(normalized.len(), 2);
+    /// ```
+    pub fn with_normalizer<F>(self, normalize: F) -> NormalizingCounter<T, F, N>
+    where
+        F: Fn(T) -> T,
+    {
+        let mut normalizing = NormalizingCounter {
+            counter: Counter::with_capacity(self.map.len()),
+            normalize,
+        };
+        for (item, count) in self.map {
+            normalizing.add(item, count);
+        }
+        normalizing
+    }
+}
+
+impl<T, F, N> NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+{
+    /// Returns `item` as this counter would store it.
+    pub fn normalize(&self, item: T) -> T {
+        (self.normalize)(item)
+    }
+
+    /// Returns the count of `item` after normalizing it, or zero if it has not been counted.
+    pub fn get(&self, item: T) -> &N {
+        &self.counter[&(self.normalize)(item)]
+    }
+
+    /// Remove `item`, after normalizing it, from the counter, returning its count.
+    pub fn remove(&mut self, item: T) -> Option<N> {
+        self.counter.map.remove(&(self.normalize)(item))
+    }
+}
+
+impl<T, F, N> NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+{
+    /// Consumes the `NormalizingCounter`, discarding the normalizer, and returns the counter.
+    pub fn into_inner(self) -> Counter<T, N> {
+        self.counter
+    }
+}
+
+impl<T, F, N> NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: AddAssign + Zero,
+{
+    /// Add `count` to the count of `item`, after normalizing it.
+    pub fn add(&mut self, item: T, count: N) {
+        let item = (self.normalize)(item);
+        *self.counter.map.entry(item).or_insert_with(N::zero) += count;
+    }
+
+    /// Add one to the count of each element of the given iterable, after normalizing it.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+        N: One,
+    {
+        for item in iterable {
+            self.add(item, N::one());
+        }
+    }
+}
+
+impl<T, F, N> NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: PartialOrd + SubAssign + Zero + One,
+{
+    /// Remove one from the count of each element of the given iterable, after normalizing it.
+    ///
+    /// Like [`Counter::subtract`], non-positive counts are removed.
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self.counter
+            .subtract(iterable.into_iter().map(|item| (self.normalize)(item)));
+    }
+}
+
+impl<T, F, N> Extend<T> for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: AddAssign + Zero + One,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, F, N> Extend<(T, N)> for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: AddAssign + Zero,
+{
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
+        for (item, count) in iter {
+            self.add(item, count);
+        }
+    }
+}
+
+impl<T, F, N> AddAssign<Counter<T, N>> for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: AddAssign + Zero,
+{
+    /// Add the counts of another counter, normalizing its items, as `counter += other` does.
+    fn add_assign(&mut self, rhs: Counter<T, N>) {
+        self.extend(rhs.map);
+    }
+}
+
+impl<T, F, N> SubAssign<Counter<T, N>> for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: PartialOrd + SubAssign + Zero,
+{
+    /// Subtract the counts of another counter, normalizing its items, as `counter -= other`
+    /// does.
+    ///
+    /// The counts of items in `rhs` which normalize to the same item are subtracted in turn.
+    fn sub_assign(&mut self, rhs: Counter<T, N>) {
+        for (item, count) in rhs.map {
+            let item = (self.normalize)(item);
+            let mut remove = false;
+            if let Some(entry) = self.counter.map.get_mut(&item) {
+                if *entry > count {
+                    *entry -= count;
+                } else {
+                    remove = true;
+                }
+            }
+            if remove {
+                self.counter.map.remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, F, N> Index<T> for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+{
+    type Output = N;
+
+    /// Index by the normalized item: items which have not been counted have a count of zero.
+    fn index(&self, item: T) -> &N {
+        self.get(item)
+    }
+}
+
+impl<T, F, N> IndexMut<T> for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+    F: Fn(T) -> T,
+    N: Zero,
+{
+    /// Index mutably by the normalized item, inserting it with a count of zero if needed.
+    fn index_mut(&mut self, item: T) -> &mut N {
+        let item = (self.normalize)(item);
+        self.counter.map.entry(item).or_insert_with(N::zero)
+    }
+}
+
+impl<T, F, N> Deref for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq,
+{
+    type Target = Counter<T, N>;
+    fn deref(&self) -> &Counter<T, N> {
+        &self.counter
+    }
+}
+
+impl<T, F, N> fmt::Debug for NormalizingCounter<T, F, N>
+where
+    T: Hash + Eq + fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NormalizingCounter")
+            .field("counter", &self.counter)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,227 @@
+//! The numeric traits which bound the counts of a [`Counter`](crate::Counter).
+//!
+//! With the default `num` feature, these are re-exports of the traits of the same names from
+//! [`num-traits`](https://docs.rs/num-traits), so counts can be any type which implements them,
+//! including floats and big integers.
+//!
+//! Counts need not be `Copy`: methods which hand out counts clone them, and the selection of the
+//! most common items clones each count once. Arbitrary-precision integers such as
+//! `num_bigint::BigUint` and `BigInt` can therefore be used as counts, and since they are
+//! unbounded, their counts never overflow; sum them with
+//! [`Counter::total`](crate::Counter::total), since the [`Bounded`] result of `total_saturating`
+//! cannot be one of them.
+//!
+//! Without the `num` feature, the crate has no dependencies, and this module instead defines
+//! minimal traits of the same names, with the subset of methods this crate uses, implemented for
+//! the primitive integers. The [`Count`] trait is implemented for every type which implements
+//! all of them, in either configuration.
+
+#[cfg(feature = "num")]
+pub use num_traits::{
+    Bounded, CheckedAdd, CheckedMul, NumCast, One, SaturatingAdd, Signed, ToPrimitive, Zero,
+};
+
+#[cfg(not(feature = "num"))]
+pub use self::fallback::*;
+
+/// A count which behaves like a primitive integer, whether or not the `num` feature is enabled.
+///
+/// It is implemented for every type which implements all of its supertraits, which without the
+/// `num` feature are exactly the primitive integers. Code generic over counts which must build
+/// in both configurations can bound them by `Count`.
+pub trait Count:
+    Copy + Ord + Zero + One + Bounded + CheckedAdd + CheckedMul + SaturatingAdd + ToPrimitive + NumCast
+{
+}
+
+impl<C> Count for C where
+    C: Copy
+        + Ord
+        + Zero
+        + One
+        + Bounded
+        + CheckedAdd
+        + CheckedMul
+        + SaturatingAdd
+        + ToPrimitive
+        + NumCast
+{
+}
+
+#[cfg(not(feature = "num"))]
+mod fallback {
+    use core::ops::{Add, Mul, Neg};
+
+    /// A type with an additive identity.
+    pub trait Zero: Sized + Add<Self, Output = Self> {
+        /// Returns the additive identity, `0`.
+        fn zero() -> Self;
+
+        /// Returns `true` if `self` is equal to the additive identity.
+        fn is_zero(&self) -> bool;
+    }
+
+    /// A type with a multiplicative identity.
+    pub trait One: Sized + Mul<Self, Output = Self> {
+        /// Returns the multiplicative identity, `1`.
+        fn one() -> Self;
+    }
+
+    /// A type with lower and upper bounds.
+    pub trait Bounded {
+        /// Returns the smallest finite value of the type.
+        fn min_value() -> Self;
+
+        /// Returns the largest finite value of the type.
+        fn max_value() -> Self;
+    }
+
+    /// Addition which returns `None` instead of overflowing.
+    pub trait CheckedAdd: Sized + Add<Self, Output = Self> {
+        /// Adds two values, returning `None` on overflow.
+        fn checked_add(&self, v: &Self) -> Option<Self>;
+    }
+
+    /// Multiplication which returns `None` instead of overflowing.
+    pub trait CheckedMul: Sized + Mul<Self, Output = Self> {
+        /// Multiplies two values, returning `None` on overflow.
+        fn checked_mul(&self, v: &Self) -> Option<Self>;
+    }
+
+    /// Addition which saturates at the bounds of the type instead of overflowing.
+    pub trait SaturatingAdd: Sized + Add<Self, Output = Self> {
+        /// Adds two values, saturating at the bounds of the type.
+        fn saturating_add(&self, v: &Self) -> Self;
+    }
+
+    /// Conversion of a value to primitive numbers.
+    pub trait ToPrimitive {
+        /// Converts the value to an `i128`, or returns `None` if it does not fit.
+        fn to_i128(&self) -> Option<i128>;
+
+        /// Converts the value to a `u128`, or returns `None` if it does not fit.
+        fn to_u128(&self) -> Option<u128>;
+
+        /// Converts the value to a `usize`, or returns `None` if it does not fit.
+        fn to_usize(&self) -> Option<usize> {
+            self.to_u128().and_then(|value| usize::try_from(value).ok())
+        }
+
+        /// Converts the value to the nearest `f64`.
+        fn to_f64(&self) -> Option<f64>;
+    }
+
+    /// Conversion between primitive numbers, failing if the value does not fit.
+    pub trait NumCast: Sized + ToPrimitive {
+        /// Converts `n` to this type, or returns `None` if it does not fit.
+        fn from<T: ToPrimitive>(n: T) -> Option<Self>;
+    }
+
+    /// A signed number.
+    pub trait Signed: Zero + One + Neg<Output = Self> {
+        /// Returns `true` if the value is less than zero.
+        fn is_negative(&self) -> bool;
+
+        /// Returns `true` if the value is greater than zero.
+        fn is_positive(&self) -> bool;
+
+        /// Returns the absolute value.
+        fn abs(&self) -> Self;
+    }
+
+    macro_rules! impl_integer {
+        ($($t:ty),*) => {
+            $(
+                impl Zero for $t {
+                    fn zero() -> Self {
+                        0
+                    }
+
+                    fn is_zero(&self) -> bool {
+                        *self == 0
+                    }
+                }
+
+                impl One for $t {
+                    fn one() -> Self {
+                        1
+                    }
+                }
+
+                impl Bounded for $t {
+                    fn min_value() -> Self {
+                        <$t>::MIN
+                    }
+
+                    fn max_value() -> Self {
+                        <$t>::MAX
+                    }
+                }
+
+                impl CheckedAdd for $t {
+                    fn checked_add(&self, v: &Self) -> Option<Self> {
+                        <$t>::checked_add(*self, *v)
+                    }
+                }
+
+                impl CheckedMul for $t {
+                    fn checked_mul(&self, v: &Self) -> Option<Self> {
+                        <$t>::checked_mul(*self, *v)
+                    }
+                }
+
+                impl SaturatingAdd for $t {
+                    fn saturating_add(&self, v: &Self) -> Self {
+                        <$t>::saturating_add(*self, *v)
+                    }
+                }
+
+                impl ToPrimitive for $t {
+                    fn to_i128(&self) -> Option<i128> {
+                        i128::try_from(*self).ok()
+                    }
+
+                    fn to_u128(&self) -> Option<u128> {
+                        u128::try_from(*self).ok()
+                    }
+
+                    fn to_f64(&self) -> Option<f64> {
+                        Some(*self as f64)
+                    }
+                }
+
+                impl NumCast for $t {
+                    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+                        match n.to_i128() {
+                            Some(value) => <$t>::try_from(value).ok(),
+                            None => n.to_u128().and_then(|value| <$t>::try_from(value).ok()),
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    macro_rules! impl_signed {
+        ($($t:ty),*) => {
+            $(
+                impl Signed for $t {
+                    fn is_negative(&self) -> bool {
+                        *self < 0
+                    }
+
+                    fn is_positive(&self) -> bool {
+                        *self > 0
+                    }
+
+                    fn abs(&self) -> Self {
+                        <$t>::abs(*self)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    impl_signed!(i8, i16, i32, i64, i128, isize);
+}
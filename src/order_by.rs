@@ -0,0 +1,206 @@
+use crate::{k_most_common_ordered, Counter};
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::Hash;
+
+pub(crate) type PairComparator<'a, T, N> = dyn Fn((&T, &N), (&T, &N)) -> Ordering + 'a;
+
+/// The order in which [`Counter::to_ordered_pairs`] returns `(item, count)` pairs.
+pub enum OrderBy<'a, T, N> {
+    /// Most common first; items with equal counts in increasing order.
+    CountDesc,
+    /// Least common first; items with equal counts in increasing order.
+    CountAsc,
+    /// In increasing order of the items.
+    KeyAsc,
+    /// In decreasing order of the items.
+    KeyDesc,
+    /// In the order given by a comparison function on `(item, count)` pairs of references. The
+    /// order of pairs which compare equal is unspecified.
+    Custom(&'a PairComparator<'a, T, N>),
+}
+
+impl<T, N> Clone for OrderBy<'_, T, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, N> Copy for OrderBy<'_, T, N> {}
+
+impl<T, N> fmt::Debug for OrderBy<'_, T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderBy::CountDesc => f.write_str("CountDesc"),
+            OrderBy::CountAsc => f.write_str("CountAsc"),
+            OrderBy::KeyAsc => f.write_str("KeyAsc"),
+            OrderBy::KeyDesc => f.write_str("KeyDesc"),
+            OrderBy::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl<T, N> OrderBy<'_, T, N>
+where
+    T: Ord,
+    N: Ord,
+{
+    /// Compares two `(item, count)` pairs in this order.
+    pub(crate) fn compare(&self, a: (&T, &N), b: (&T, &N)) -> Ordering {
+        match self {
+            OrderBy::CountDesc => b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)),
+            OrderBy::CountAsc => a.1.cmp(b.1).then_with(|| a.0.cmp(b.0)),
+            OrderBy::KeyAsc => a.0.cmp(b.0),
+            OrderBy::KeyDesc => b.0.cmp(a.0),
+            OrderBy::Custom(compare) => compare(a, b),
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: Clone + Ord,
+{
+    /// Create a vector of `(elem, frequency)` pairs in the given order, keeping only the first
+    /// `limit` pairs if a limit is given.
+    ///
+    /// The more specific sorted-output methods, such as [`most_common_ordered`] and
+    /// [`k_most_common_ordered`], are shorthands for particular orders.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, OrderBy};
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    ///
+    /// let rarest = counter.to_ordered_pairs(OrderBy::CountAsc, Some(2));
+    /// assert_eq!(rarest, [('c', 1), ('d', 1)]);
+    ///
+    /// let alphabetical = counter.to_ordered_pairs(OrderBy::KeyAsc, None);
+    /// assert_eq!(alphabetical, [('a', 5), ('b', 2), ('c', 1), ('d', 1), ('r', 2)]);
+    ///
+    /// let by_count_parity = |(a, a_count): (&char, &usize), (b, b_count): (&char, &usize)| {
+    ///     (a_count % 2).cmp(&(b_count % 2)).then(a.cmp(b))
+    /// };
+    /// let evens_first = counter.to_ordered_pairs(OrderBy::Custom(&by_count_parity), Some(3));
+    /// assert_eq!(evens_first, [('b', 2), ('r', 2), ('a', 5)]);
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* \* log *n*), where *n* is the number of items in the counter. With a limit
+    /// *k* < *n*, this improves to *O*(*n* + *k* \* log *k*); see also [`k_most_common_ordered`].
+    ///
+    /// [`most_common_ordered`]: Counter::most_common_ordered
+    /// [`k_most_common_ordered`]: Counter::k_most_common_ordered
+    pub fn to_ordered_pairs(&self, order: OrderBy<'_, T, N>, limit: Option<usize>) -> Vec<(T, N)> {
+        trace_span!(self.map.len(); "to_ordered_pairs", len = self.map.len(), ?limit);
+        let limit = limit.unwrap_or(usize::MAX);
+        if limit == 0 {
+            return Vec::new();
+        }
+        if let OrderBy::CountDesc = order {
+            // The heap selects the top `k` while cloning only the selected keys. When the whole
+            // counter is wanted, the general sort below is generally faster.
+            if limit < self.map.len() {
+                return k_most_common_ordered(self.map.iter(), limit);
+            }
+            if let Some(pairs) = bucket_sorted(self.map.iter(), self.map.len()) {
+                return pairs;
+            }
+        }
+
+        // Only the pairs which are kept are cloned, once they have been sorted.
+        let compare = |a: &(&T, &N), b: &(&T, &N)| order.compare(*a, *b);
+        let mut pairs = self.map.iter().collect::<Vec<_>>();
+        if limit < pairs.len() {
+            pairs.select_nth_unstable_by(limit, compare);
+            pairs.truncate(limit);
+        }
+        pairs.sort_unstable_by(compare);
+        pairs
+            .into_iter()
+            .map(|(item, count)| (item.clone(), count.clone()))
+            .collect()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: Ord,
+{
+    /// Consumes the counter and returns its `(item, count)` pairs in decreasing order of their
+    /// counts, with items of equal count in increasing order.
+    ///
+    /// The order is the same as [`most_common_ordered`](Counter::most_common_ordered), but the
+    /// items and counts are moved out of the counter instead of cloned. This suits the common
+    /// pattern of counting, ranking once, and discarding the counter, especially when the items
+    /// are expensive to clone.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let words = "the cat and the hat".split_whitespace().map(String::from);
+    /// let counter = words.collect::<Counter<_>>();
+    /// let ranked = counter.sort_into_vec();
+    /// assert_eq!(ranked[0], ("the".to_string(), 2));
+    /// assert_eq!(ranked[1], ("and".to_string(), 1));
+    /// ```
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* \* log *n*), where *n* is the number of items in the counter.
+    pub fn sort_into_vec(self) -> Vec<(T, N)> {
+        let mut pairs = self.map.into_iter().collect::<Vec<_>>();
+        pairs.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        pairs
+    }
+}
+
+/// The most distinct counts for which [`bucket_sorted`] groups the items by count.
+const MAX_BUCKETS: usize = 16;
+
+/// The fewest items for which [`bucket_sorted`] groups the items by count.
+const MIN_BUCKETED_LEN: usize = 1 << 11;
+
+/// Sorts the pairs most common first, with items of equal count in increasing order, by
+/// grouping the items by count and sorting each group by item alone.
+///
+/// When many items share a few counts, as after de-duplicating a large input, this spares the
+/// sort from comparing counts at all. Returns `None`, leaving the pairs to the general sort, if
+/// there are fewer than [`MIN_BUCKETED_LEN`] pairs or more than [`MAX_BUCKETS`] distinct counts.
+fn bucket_sorted<'a, T, N, I>(pairs: I, len: usize) -> Option<Vec<(T, N)>>
+where
+    T: 'a + Clone + Ord,
+    N: 'a + Clone + Ord,
+    I: Iterator<Item = (&'a T, &'a N)> + Clone,
+{
+    if len < MIN_BUCKETED_LEN {
+        return None;
+    }
+    // The distinct counts, in decreasing order.
+    let mut counts = Vec::<&N>::new();
+    let bucket = |counts: &[&N], count: &N| counts.binary_search_by(|other| count.cmp(other));
+    for (_, count) in pairs.clone() {
+        if let Err(index) = bucket(&counts, count) {
+            if counts.len() == MAX_BUCKETS {
+                return None;
+            }
+            counts.insert(index, count);
+        }
+    }
+
+    let mut buckets = counts.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+    for (item, count) in pairs {
+        if let Ok(index) = bucket(&counts, count) {
+            buckets[index].push(item);
+        }
+    }
+    let mut sorted = Vec::with_capacity(len);
+    for (count, mut items) in counts.into_iter().zip(buckets) {
+        items.sort_unstable();
+        sorted.extend(items.into_iter().map(|item| (item.clone(), count.clone())));
+    }
+    Some(sorted)
+}
@@ -0,0 +1,459 @@
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use alloc::borrow::ToOwned;
+use alloc::collections::btree_map::{self, BTreeMap};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+use core::iter;
+use core::ops::{AddAssign, Deref, DerefMut, Index, IndexMut, RangeBounds, SubAssign};
+
+/// A counter whose items are kept in the natural order of their keys.
+///
+/// `OrderedCounter<T, N>` is backed by a [`BTreeMap`] instead of a `HashMap`. Iteration is
+/// therefore deterministic, and the counts of a contiguous range of keys can be queried
+/// efficiently with [`counts_in_range()`].
+///
+/// It supports the same counting API as [`Counter`]; the two can be converted into each other
+/// with [`From`].
+///
+/// [`counts_in_range()`]: OrderedCounter::counts_in_range
+///
+/// ```rust
+/// # use counter::OrderedCounter;
+/// let counter = "abracadabra".chars().collect::<OrderedCounter<_>>();
+/// let items = counter.iter().collect::<Vec<_>>();
+/// assert_eq!(items, [(&'a', &5), (&'b', &2), (&'c', &1), (&'d', &1), (&'r', &2)]);
+/// assert_eq!(counter.total_in_range::<usize, _>('b'..='d'), 4);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct OrderedCounter<T: Ord, N = usize> {
+    map: BTreeMap<T, N>,
+    // necessary for `Index::index` since we cannot declare generic `static` variables.
+    zero: N,
+}
+
+impl<T, N> OrderedCounter<T, N>
+where
+    T: Ord,
+    N: Zero,
+{
+    /// Create a new, empty `OrderedCounter`.
+    pub fn new() -> Self {
+        OrderedCounter {
+            map: BTreeMap::new(),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N> Default for OrderedCounter<T, N>
+where
+    T: Ord,
+    N: Default,
+{
+    fn default() -> Self {
+        Self {
+            map: BTreeMap::default(),
+            zero: N::default(),
+        }
+    }
+}
+
+impl<T, N> OrderedCounter<T, N>
+where
+    T: Ord,
+{
+    /// Consumes this counter and returns a [`BTreeMap`] mapping the items to the counts.
+    pub fn into_map(self) -> BTreeMap<T, N> {
+        self.map
+    }
+
+    /// Returns the sum of the counts.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let counter = "abracadabra".chars().collect::<OrderedCounter<_>>();
+    /// assert_eq!(counter.total::<usize>(), 11);
+    /// ```
+    pub fn total<'a, S>(&'a self) -> S
+    where
+        S: iter::Sum<&'a N>,
+    {
+        self.map.values().sum()
+    }
+
+    /// Returns an iterator over the `(item, count)` pairs whose items lie within `range`, in
+    /// ascending order of the items.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let counter = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5].into_iter().collect::<OrderedCounter<_>>();
+    /// let low = counter.counts_in_range(..4).collect::<Vec<_>>();
+    /// assert_eq!(low, [(&1, &2), (&2, &1), (&3, &2)]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`BTreeMap::range`]: if the start of the range is
+    /// greater than its end, or if both bounds are equal and excluded.
+    pub fn counts_in_range<R>(&self, range: R) -> btree_map::Range<'_, T, N>
+    where
+        R: RangeBounds<T>,
+    {
+        self.map.range(range)
+    }
+
+    /// Returns the sum of the counts of the items which lie within `range`.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let counter = [3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5].into_iter().collect::<OrderedCounter<_>>();
+    /// assert_eq!(counter.total_in_range::<usize, _>(4..), 6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics under the same conditions as [`counts_in_range()`].
+    ///
+    /// [`counts_in_range()`]: OrderedCounter::counts_in_range
+    pub fn total_in_range<'a, S, R>(&'a self, range: R) -> S
+    where
+        S: iter::Sum<&'a N>,
+        R: RangeBounds<T>,
+    {
+        self.map.range(range).map(|(_, count)| count).sum()
+    }
+}
+
+impl<T, N> OrderedCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero + One,
+{
+    /// Add the counts of the elements from the given iterable to this counter.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+        }
+    }
+}
+
+impl<T, N> OrderedCounter<T, N>
+where
+    T: Ord,
+    N: PartialOrd + SubAssign + Zero + One,
+{
+    /// Remove the counts of the elements from the given iterable to this counter.
+    ///
+    /// Non-positive counts are automatically removed.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let mut counter = "abbccc".chars().collect::<OrderedCounter<_>>();
+    /// counter.subtract("abba".chars());
+    /// assert_eq!(counter.into_iter().collect::<Vec<_>>(), [('c', 3)]);
+    /// ```
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(&item) {
+                if *entry > N::zero() {
+                    *entry -= N::one();
+                }
+                remove = *entry == N::zero();
+            }
+            if remove {
+                self.map.remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, N> OrderedCounter<T, N>
+where
+    T: Ord + Clone,
+    N: Ord + Clone,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// Unlike [`Counter::most_common`], items with equal counts are always returned in
+    /// ascending order of their keys, since that is the order in which they are stored.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let mc = "pappaopolo".chars().collect::<OrderedCounter<_>>().most_common();
+    /// let expected = vec![('p', 4), ('o', 3), ('a', 2), ('l', 1)];
+    /// assert_eq!(mc, expected);
+    /// ```
+    pub fn most_common(&self) -> Vec<(T, N)> {
+        let mut items = self
+            .map
+            .iter()
+            .map(|(key, count)| (key.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        // The items are already sorted by key, so a stable sort breaks ties by key.
+        items.sort_by(|(_, a_count), (_, b_count)| b_count.cmp(a_count));
+        items
+    }
+
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// In the event that two keys have an equal frequency, use the supplied ordering function
+    /// to further arrange the results.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let counter = "eaddbbccc".chars().collect::<OrderedCounter<_>>();
+    /// let by_common = counter.most_common_tiebreaker(|&a, &b| b.cmp(&a));
+    /// let expected = vec![('c', 3), ('d', 2), ('b', 2), ('e', 1), ('a', 1)];
+    /// assert_eq!(by_common, expected);
+    /// ```
+    pub fn most_common_tiebreaker<F>(&self, mut tiebreaker: F) -> Vec<(T, N)>
+    where
+        F: FnMut(&T, &T) -> ::core::cmp::Ordering,
+    {
+        let mut items = self
+            .map
+            .iter()
+            .map(|(key, count)| (key.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        items.sort_by(|(a_item, a_count), (b_item, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| tiebreaker(a_item, b_item))
+        });
+        items
+    }
+
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// In the event that two keys have an equal frequency, use the natural ordering of the keys
+    /// to further sort the results. For an `OrderedCounter` this is the same as
+    /// [`most_common()`](OrderedCounter::most_common).
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let mc = "abracadabra".chars().collect::<OrderedCounter<_>>().most_common_ordered();
+    /// let expect = vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)];
+    /// assert_eq!(mc, expect);
+    /// ```
+    pub fn most_common_ordered(&self) -> Vec<(T, N)> {
+        self.most_common()
+    }
+
+    /// Returns the `k` most common items in decreasing order of their counts.
+    ///
+    /// This behaves exactly like [`Counter::k_most_common_ordered`], and uses the same
+    /// algorithm.
+    ///
+    /// ```rust
+    /// # use counter::OrderedCounter;
+    /// let counter: OrderedCounter<_> = "abracadabra".chars().collect();
+    /// let top3 = counter.k_most_common_ordered(3);
+    /// assert_eq!(top3, vec![('a', 5), ('b', 2), ('r', 2)]);
+    /// ```
+    pub fn k_most_common_ordered(&self, k: usize) -> Vec<(T, N)> {
+        if k == 0 {
+            return vec![];
+        }
+
+        if k >= self.map.len() {
+            return self.most_common_ordered();
+        }
+
+        crate::k_most_common_ordered(self.map.iter(), k)
+    }
+}
+
+impl<T, N> Deref for OrderedCounter<T, N>
+where
+    T: Ord,
+{
+    type Target = BTreeMap<T, N>;
+    fn deref(&self) -> &BTreeMap<T, N> {
+        &self.map
+    }
+}
+
+impl<T, N> DerefMut for OrderedCounter<T, N>
+where
+    T: Ord,
+{
+    fn deref_mut(&mut self) -> &mut BTreeMap<T, N> {
+        &mut self.map
+    }
+}
+
+impl<T, Q, N> Index<&'_ Q> for OrderedCounter<T, N>
+where
+    T: Ord + core::borrow::Borrow<Q>,
+    Q: Ord + ?Sized,
+    N: Zero,
+{
+    type Output = N;
+
+    /// Index in immutable contexts.
+    ///
+    /// Returns a reference to a [`zero`] value for missing keys.
+    ///
+    /// [`zero`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    fn index(&self, key: &'_ Q) -> &N {
+        self.map.get(key).unwrap_or(&self.zero)
+    }
+}
+
+impl<T, Q, N> IndexMut<&'_ Q> for OrderedCounter<T, N>
+where
+    T: Ord + core::borrow::Borrow<Q>,
+    Q: Ord + ToOwned<Owned = T> + ?Sized,
+    N: Zero,
+{
+    /// Index in mutable contexts.
+    ///
+    /// If the given key is not present, creates a new entry and initializes it with a [`zero`]
+    /// value.
+    ///
+    /// [`zero`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    fn index_mut(&mut self, key: &'_ Q) -> &mut N {
+        self.map.entry(key.to_owned()).or_insert_with(N::zero)
+    }
+}
+
+impl<T, N> iter::FromIterator<T> for OrderedCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero + One,
+{
+    /// Produce an `OrderedCounter` from an iterator of items.
+    fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
+        let mut counter = OrderedCounter::new();
+        counter.update(iterable);
+        counter
+    }
+}
+
+impl<T, N> iter::FromIterator<(T, N)> for OrderedCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero,
+{
+    /// Creates an `OrderedCounter` from `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn from_iter<I: IntoIterator<Item = (T, N)>>(iter: I) -> Self {
+        let mut counter = OrderedCounter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T, N> Extend<T> for OrderedCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero + One,
+{
+    /// Extend an `OrderedCounter` with an iterator of items.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, N> Extend<(T, N)> for OrderedCounter<T, N>
+where
+    T: Ord,
+    N: AddAssign + Zero,
+{
+    /// Extend an `OrderedCounter` with `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
+        for (item, item_count) in iter {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += item_count;
+        }
+    }
+}
+
+impl<'a, T, N> IntoIterator for &'a OrderedCounter<T, N>
+where
+    T: Ord,
+{
+    type Item = (&'a T, &'a N);
+    type IntoIter = btree_map::Iter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<'a, T, N> IntoIterator for &'a mut OrderedCounter<T, N>
+where
+    T: Ord,
+{
+    type Item = (&'a T, &'a mut N);
+    type IntoIter = btree_map::IterMut<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut()
+    }
+}
+
+impl<T, N> IntoIterator for OrderedCounter<T, N>
+where
+    T: Ord,
+{
+    type Item = (T, N);
+    type IntoIter = btree_map::IntoIter<T, N>;
+
+    /// Consumes the `OrderedCounter` to produce an iterator over its `(item, count)` pairs in
+    /// ascending order of the items.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<T, N, S> From<Counter<T, N, S>> for OrderedCounter<T, N>
+where
+    T: Hash + Ord,
+{
+    /// Sort the items of a [`Counter`] into an `OrderedCounter`.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, OrderedCounter};
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// let ordered = OrderedCounter::from(counter.clone());
+    /// assert_eq!(ordered.keys().collect::<String>(), "abcdr");
+    /// assert_eq!(Counter::from(ordered), counter);
+    /// ```
+    fn from(counter: Counter<T, N, S>) -> Self {
+        OrderedCounter {
+            map: counter.map.into_iter().collect(),
+            zero: counter.zero,
+        }
+    }
+}
+
+impl<T, N> From<OrderedCounter<T, N>> for Counter<T, N>
+where
+    T: Hash + Ord,
+{
+    /// Move the items of an [`OrderedCounter`] into a hash-based [`Counter`].
+    fn from(counter: OrderedCounter<T, N>) -> Self {
+        Counter {
+            map: counter.map.into_iter().collect(),
+            zero: counter.zero,
+        }
+    }
+}
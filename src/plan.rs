@@ -0,0 +1,220 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{AddAssign, SubAssign};
+
+/// A single step of an [`UpdatePlan`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Step<T, N> {
+    Increment(T, N),
+    Decrement(T, N),
+    Set(T, N),
+}
+
+/// A batch of increments, decrements and assignments, applied all at once by
+/// [`Counter::apply_update_plan`].
+///
+/// The steps are applied in the order they were added to the plan. The plan is validated
+/// before the counter is touched, so it is applied either entirely or not at all.
+///
+/// ```rust
+/// # use counter::{Counter, UpdatePlan};
+/// let mut counter = "aabbb".chars().collect::<Counter<_>>();
+///
+/// let plan = UpdatePlan::new().increment('a', 3).decrement('b', 3).set('c', 2);
+/// let rollback = counter.apply_update_plan(&plan).unwrap();
+/// assert_eq!(counter, "aaaaacc".chars().collect());
+///
+/// counter.rollback(rollback);
+/// assert_eq!(counter, "aabbb".chars().collect());
+///
+/// let overdrawn = UpdatePlan::new().increment('a', 1).decrement('b', 4);
+/// let err = counter.apply_update_plan(&overdrawn).unwrap_err();
+/// assert_eq!((err.step, err.item, err.count), (1, 'b', 3));
+/// assert_eq!(counter, "aabbb".chars().collect());
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct UpdatePlan<T, N = usize> {
+    steps: Vec<Step<T, N>>,
+}
+
+impl<T, N> Default for UpdatePlan<T, N> {
+    fn default() -> Self {
+        UpdatePlan { steps: Vec::new() }
+    }
+}
+
+impl<T, N> UpdatePlan<T, N> {
+    /// Create a new, empty `UpdatePlan`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `by` to the count of `item`.
+    pub fn increment(mut self, item: T, by: N) -> Self {
+        self.steps.push(Step::Increment(item, by));
+        self
+    }
+
+    /// Subtract `by` from the count of `item`. The plan fails if the count would become
+    /// negative.
+    pub fn decrement(mut self, item: T, by: N) -> Self {
+        self.steps.push(Step::Decrement(item, by));
+        self
+    }
+
+    /// Set the count of `item` to `count`. The plan fails if `count` is negative.
+    pub fn set(mut self, item: T, count: N) -> Self {
+        self.steps.push(Step::Set(item, count));
+        self
+    }
+
+    /// Returns the number of steps in the plan.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns `true` if the plan has no steps.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// The step of an [`UpdatePlan`] which would have made a count negative.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PlanError<T, N> {
+    /// The index of the failing step in the plan.
+    pub step: usize,
+    /// The item whose count would have become negative.
+    pub item: T,
+    /// The count of the item just before the failing step.
+    pub count: N,
+}
+
+impl<T, N> fmt::Display for PlanError<T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step {} of the update plan would make the count {:?} of item {:?} negative",
+            self.step, self.count, self.item
+        )
+    }
+}
+
+impl<T, N> core::error::Error for PlanError<T, N>
+where
+    T: fmt::Debug,
+    N: fmt::Debug,
+{
+}
+
+/// The previous counts of the items touched by an applied [`UpdatePlan`], which
+/// [`Counter::rollback`] restores.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[must_use = "a plan can only be rolled back with the `Rollback` returned by applying it"]
+pub struct Rollback<T, N> {
+    previous: Vec<(T, Option<N>)>,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: Clone + PartialOrd + AddAssign + SubAssign + Zero,
+    S: BuildHasher,
+{
+    /// Apply every step of `plan`, or, if any step would make a count negative, none of them.
+    ///
+    /// Items whose count ends up zero are removed. On success, returns a [`Rollback`] which
+    /// undoes the plan when passed to [`rollback`](Counter::rollback).
+    ///
+    /// See [`UpdatePlan`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PlanError`] describing the first step which would make a count negative. The
+    /// counter is left unchanged.
+    pub fn apply_update_plan(
+        &mut self,
+        plan: &UpdatePlan<T, N>,
+    ) -> Result<Rollback<T, N>, PlanError<T, N>> {
+        // Work out the final count of every touched item without mutating the counter.
+        let mut pending: HashMap<&T, N> = HashMap::default();
+        for (index, step) in plan.steps.iter().enumerate() {
+            let (item, new_count) = match step {
+                Step::Increment(item, by) => {
+                    let mut count = self.pending_count(&pending, item);
+                    count += by.clone();
+                    (item, count)
+                }
+                Step::Decrement(item, by) => {
+                    let mut count = self.pending_count(&pending, item);
+                    // Compare before subtracting, so unsigned counts cannot underflow.
+                    if *by > count {
+                        return Err(plan_error(index, item, count));
+                    }
+                    count -= by.clone();
+                    (item, count)
+                }
+                Step::Set(item, count) => (item, count.clone()),
+            };
+            if new_count < N::zero() {
+                let count = self.pending_count(&pending, item);
+                return Err(plan_error(index, item, count));
+            }
+            pending.insert(item, new_count);
+        }
+
+        let mut previous = Vec::with_capacity(pending.len());
+        for (item, count) in pending {
+            let old = if count.is_zero() {
+                self.map.remove(item)
+            } else {
+                self.map.insert(item.clone(), count)
+            };
+            previous.push((item.clone(), old));
+        }
+        Ok(Rollback { previous })
+    }
+
+    fn pending_count(&self, pending: &HashMap<&T, N>, item: &T) -> N {
+        pending
+            .get(item)
+            .or_else(|| self.map.get(item))
+            .cloned()
+            .unwrap_or_else(N::zero)
+    }
+
+    /// Undo an [`UpdatePlan`], restoring the counts its items had before it was applied.
+    ///
+    /// Changes made to those items since the plan was applied are overwritten; changes to other
+    /// items are kept.
+    pub fn rollback(&mut self, rollback: Rollback<T, N>) {
+        for (item, count) in rollback.previous {
+            match count {
+                Some(count) => {
+                    self.map.insert(item, count);
+                }
+                None => {
+                    self.map.remove(&item);
+                }
+            }
+        }
+    }
+}
+
+fn plan_error<T: Clone, N>(step: usize, item: &T, count: N) -> PlanError<T, N> {
+    PlanError {
+        step,
+        item: item.clone(),
+        count,
+    }
+}
@@ -0,0 +1,215 @@
+use crate::collections::DefaultHashBuilder;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, PoisonError};
+
+/// A pool of reusable, empty [`Counter`]s.
+///
+/// Building a short-lived counter for every request of a server allocates and frees the backing
+/// map each time. A `CounterPool` instead hands out cleared counters which keep the capacity they
+/// grew to, and takes them back when the [`PooledCounter`] guard is dropped.
+///
+/// The pool is [`Sync`] when its counters are [`Send`], so a single pool can be shared between
+/// worker threads.
+///
+/// ```rust
+/// # use counter::CounterPool;
+/// let pool = CounterPool::<char>::new();
+///
+/// {
+///     let mut counter = pool.get();
+///     counter.update("abbccc".chars());
+///     assert_eq!(counter[&'c'], 3);
+/// } // the counter is cleared and returned to the pool here
+///
+/// assert_eq!(pool.idle(), 1);
+/// let counter = pool.get();
+/// assert!(counter.is_empty());
+/// assert!(counter.capacity() >= 3);
+/// ```
+///
+/// Counters handed out by a pool hash their items with a clone of the pool's hasher; see
+/// [`with_hasher`](CounterPool::with_hasher).
+#[derive(Debug)]
+pub struct CounterPool<T: Hash + Eq, N = usize, S = DefaultHashBuilder> {
+    idle: Mutex<Vec<Counter<T, N, S>>>,
+    max_idle: usize,
+    hasher: S,
+}
+
+impl<T, N> CounterPool<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty pool which retains any number of idle counters.
+    pub fn new() -> Self {
+        Self::with_max_idle(usize::MAX)
+    }
+
+    /// Create a new, empty pool which retains at most `max_idle` idle counters.
+    ///
+    /// Counters returned to a pool which is already holding `max_idle` counters are dropped
+    /// instead, which bounds the memory the pool keeps alive after a burst of concurrent use.
+    ///
+    /// ```rust
+    /// # use counter::CounterPool;
+    /// let pool = CounterPool::<char>::with_max_idle(1);
+    /// let a = pool.get();
+    /// let b = pool.get();
+    /// drop(a);
+    /// drop(b);
+    /// assert_eq!(pool.idle(), 1);
+    /// ```
+    pub fn with_max_idle(max_idle: usize) -> Self {
+        Self::with_max_idle_and_hasher(max_idle, DefaultHashBuilder::default())
+    }
+}
+
+impl<T, N, S> CounterPool<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Create a new, empty pool which retains any number of idle counters, and whose counters
+    /// hash their items with clones of `hasher`.
+    ///
+    /// ```rust
+    /// # use counter::{CounterPool, SeededState};
+    /// let pool = CounterPool::<char, usize, _>::with_hasher(SeededState::new(7));
+    /// let counter = pool.get();
+    /// assert_eq!(counter.hasher(), &SeededState::new(7));
+    /// ```
+    pub fn with_hasher(hasher: S) -> Self {
+        Self::with_max_idle_and_hasher(usize::MAX, hasher)
+    }
+
+    /// Create a new, empty pool which retains at most `max_idle` idle counters, and whose
+    /// counters hash their items with clones of `hasher`.
+    pub fn with_max_idle_and_hasher(max_idle: usize, hasher: S) -> Self {
+        CounterPool {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+            hasher,
+        }
+    }
+
+    /// Returns the number of idle counters currently held by the pool.
+    pub fn idle(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Drop all idle counters held by the pool, releasing their memory.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn put(&self, mut counter: Counter<T, N, S>) {
+        counter.map.clear();
+        let mut idle = self.lock();
+        if idle.len() < self.max_idle {
+            idle.push(counter);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Counter<T, N, S>>> {
+        // Counters are cleared before they are stored, so a panic while the lock was held
+        // cannot have left the stack in an inconsistent state.
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<T, N, S> CounterPool<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: Clone,
+{
+    /// Take an empty counter from the pool, or create a new one if the pool has none idle.
+    ///
+    /// The counter is returned to the pool when the guard is dropped.
+    pub fn get(&self) -> PooledCounter<'_, T, N, S> {
+        let counter = self
+            .lock()
+            .pop()
+            .unwrap_or_else(|| Counter::with_hasher(self.hasher.clone()));
+        PooledCounter {
+            counter: Some(counter),
+            pool: self,
+        }
+    }
+}
+
+impl<T, N, S> Default for CounterPool<T, N, S>
+where
+    T: Hash + Eq,
+    S: Default,
+{
+    fn default() -> Self {
+        Self::with_max_idle_and_hasher(usize::MAX, S::default())
+    }
+}
+
+/// A [`Counter`] borrowed from a [`CounterPool`].
+///
+/// Dereferences to the borrowed counter. When dropped, the counter is cleared and returned to
+/// the pool it came from.
+#[derive(Debug)]
+pub struct PooledCounter<'a, T: Hash + Eq, N = usize, S = DefaultHashBuilder> {
+    // Always `Some` until the guard is dropped or detached.
+    counter: Option<Counter<T, N, S>>,
+    pool: &'a CounterPool<T, N, S>,
+}
+
+impl<'a, T, N, S> PooledCounter<'a, T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Take ownership of the counter, so that it is not returned to the pool.
+    ///
+    /// ```rust
+    /// # use counter::CounterPool;
+    /// let pool = CounterPool::<char>::new();
+    /// let mut pooled = pool.get();
+    /// pooled.update("aab".chars());
+    /// let counter = pooled.detach();
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(pool.idle(), 0);
+    /// ```
+    #[allow(clippy::missing_panics_doc)] // the counter is only taken by `detach` and `drop`
+    pub fn detach(mut self) -> Counter<T, N, S> {
+        self.counter.take().expect("pooled counter is present")
+    }
+}
+
+impl<'a, T, N, S> Deref for PooledCounter<'a, T, N, S>
+where
+    T: Hash + Eq,
+{
+    type Target = Counter<T, N, S>;
+    fn deref(&self) -> &Counter<T, N, S> {
+        self.counter.as_ref().expect("pooled counter is present")
+    }
+}
+
+impl<'a, T, N, S> DerefMut for PooledCounter<'a, T, N, S>
+where
+    T: Hash + Eq,
+{
+    fn deref_mut(&mut self) -> &mut Counter<T, N, S> {
+        self.counter.as_mut().expect("pooled counter is present")
+    }
+}
+
+impl<'a, T, N, S> Drop for PooledCounter<'a, T, N, S>
+where
+    T: Hash + Eq,
+{
+    fn drop(&mut self) {
+        if let Some(counter) = self.counter.take() {
+            self.pool.put(counter);
+        }
+    }
+}
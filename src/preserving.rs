@@ -0,0 +1,444 @@
+//! A counter which remembers the order in which its items were first counted.
+//!
+//! Python's `Counter` is a `dict`, which preserves insertion order, so its iteration order and
+//! the order of the ties in `most_common()` follow the order in which items were first seen.
+//! [`Counter`] in this module is backed by an [`IndexMap`] to behave the same way, which eases
+//! porting code which depends on that order.
+//!
+//! ```rust
+//! # use counter::preserving::Counter;
+//! let counter = "the cat sat on the mat".split_whitespace().collect::<Counter<_>>();
+//! assert_eq!(counter.keys().copied().collect::<Vec<_>>(), ["the", "cat", "sat", "on", "mat"]);
+//! assert_eq!(
+//!     counter.most_common(),
+//!     [("the", 2), ("cat", 1), ("sat", 1), ("on", 1), ("mat", 1)],
+//! );
+//! ```
+
+use crate::Counter as HashCounter;
+
+use crate::num::{One, Zero};
+
+use indexmap::{map, IndexMap};
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::iter;
+use std::ops::{AddAssign, Deref, DerefMut, Index, IndexMut, SubAssign};
+
+/// A counter whose items are kept in the order in which they were first counted.
+///
+/// `preserving::Counter<T, N>` is backed by an [`IndexMap`] instead of a `HashMap`. It
+/// supports the same counting API as [`crate::Counter`]; the two can be converted into each
+/// other with [`From`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Counter<T: Hash + Eq, N = usize> {
+    map: IndexMap<T, N>,
+    // necessary for `Index::index` since we cannot declare generic `static` variables.
+    zero: N,
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `Counter`.
+    pub fn new() -> Self {
+        Counter {
+            map: IndexMap::new(),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N> Default for Counter<T, N>
+where
+    T: Hash + Eq,
+    N: Default,
+{
+    fn default() -> Self {
+        Self {
+            map: IndexMap::default(),
+            zero: N::default(),
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Consumes this counter and returns an [`IndexMap`] mapping the items to the counts.
+    pub fn into_map(self) -> IndexMap<T, N> {
+        self.map
+    }
+
+    /// Returns the sum of the counts.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.total::<usize>(), 11);
+    /// ```
+    pub fn total<'a, S>(&'a self) -> S
+    where
+        S: iter::Sum<&'a N>,
+    {
+        self.map.values().sum()
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+{
+    /// Add the counts of the elements from the given iterable to this counter.
+    ///
+    /// Items which were not yet counted are appended, in the order they are encountered.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += N::one();
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: PartialOrd + SubAssign + Zero + One,
+{
+    /// Remove the counts of the elements from the given iterable to this counter.
+    ///
+    /// Non-positive counts are automatically removed. The remaining items keep their order, so
+    /// each removal takes time proportional to the number of items after it.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let mut counter = "cabbccb".chars().collect::<Counter<_>>();
+    /// counter.subtract("a".chars());
+    /// assert_eq!(counter.into_iter().collect::<Vec<_>>(), [('c', 3), ('b', 3)]);
+    /// ```
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            let mut remove = false;
+            if let Some(entry) = self.map.get_mut(&item) {
+                if *entry > N::zero() {
+                    *entry -= N::one();
+                }
+                remove = *entry == N::zero();
+            }
+            if remove {
+                self.map.shift_remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq + Clone,
+    N: Ord + Clone,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// As in Python, items with equal counts are returned in the order in which they were
+    /// first counted.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let mc = "pappaopolo".chars().collect::<Counter<_>>().most_common();
+    /// let expected = vec![('p', 4), ('o', 3), ('a', 2), ('l', 1)];
+    /// assert_eq!(mc, expected);
+    /// ```
+    pub fn most_common(&self) -> Vec<(T, N)> {
+        let mut items = self
+            .map
+            .iter()
+            .map(|(key, count)| (key.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        // The items are in insertion order, so a stable sort breaks ties by insertion order.
+        items.sort_by(|(_, a_count), (_, b_count)| b_count.cmp(a_count));
+        items
+    }
+
+    /// Returns the `k` most common items in decreasing order of their counts, breaking ties by
+    /// the order in which the items were first counted.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.k_most_common(3), [('a', 5), ('b', 2), ('r', 2)]);
+    /// ```
+    pub fn k_most_common(&self, k: usize) -> Vec<(T, N)> {
+        let mut items = self.most_common();
+        items.truncate(k);
+        items
+    }
+
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// In the event that two keys have an equal frequency, use the supplied ordering function
+    /// to further arrange the results.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let counter = "eaddbbccc".chars().collect::<Counter<_>>();
+    /// let by_common = counter.most_common_tiebreaker(|&a, &b| b.cmp(&a));
+    /// let expected = vec![('c', 3), ('d', 2), ('b', 2), ('e', 1), ('a', 1)];
+    /// assert_eq!(by_common, expected);
+    /// ```
+    pub fn most_common_tiebreaker<F>(&self, mut tiebreaker: F) -> Vec<(T, N)>
+    where
+        F: FnMut(&T, &T) -> ::std::cmp::Ordering,
+    {
+        let mut items = self
+            .map
+            .iter()
+            .map(|(key, count)| (key.clone(), count.clone()))
+            .collect::<Vec<_>>();
+        items.sort_by(|(a_item, a_count), (b_item, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| tiebreaker(a_item, b_item))
+        });
+        items
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq + Clone + Ord,
+    N: Ord + Clone,
+{
+    /// Create a vector of `(elem, frequency)` pairs, sorted most to least common.
+    ///
+    /// In the event that two keys have an equal frequency, use the natural ordering of the keys
+    /// to further sort the results.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let mc = "abracadabra".chars().collect::<Counter<_>>().most_common_ordered();
+    /// let expect = vec![('a', 5), ('b', 2), ('r', 2), ('c', 1), ('d', 1)];
+    /// assert_eq!(mc, expect);
+    /// ```
+    pub fn most_common_ordered(&self) -> Vec<(T, N)> {
+        self.most_common_tiebreaker(Ord::cmp)
+    }
+
+    /// Returns the `k` most common items in decreasing order of their counts.
+    ///
+    /// This behaves exactly like [`crate::Counter::k_most_common_ordered`], and uses the same
+    /// algorithm.
+    ///
+    /// ```rust
+    /// # use counter::preserving::Counter;
+    /// let counter: Counter<_> = "abracadabra".chars().collect();
+    /// let top3 = counter.k_most_common_ordered(3);
+    /// assert_eq!(top3, vec![('a', 5), ('b', 2), ('r', 2)]);
+    /// ```
+    pub fn k_most_common_ordered(&self, k: usize) -> Vec<(T, N)> {
+        if k == 0 {
+            return vec![];
+        }
+
+        if k >= self.map.len() {
+            return self.most_common_ordered();
+        }
+
+        crate::k_most_common_ordered(self.map.iter(), k)
+    }
+}
+
+impl<T, N> Deref for Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Target = IndexMap<T, N>;
+    fn deref(&self) -> &IndexMap<T, N> {
+        &self.map
+    }
+}
+
+impl<T, N> DerefMut for Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    fn deref_mut(&mut self) -> &mut IndexMap<T, N> {
+        &mut self.map
+    }
+}
+
+impl<T, Q, N> Index<&'_ Q> for Counter<T, N>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+    N: Zero,
+{
+    type Output = N;
+
+    /// Index in immutable contexts.
+    ///
+    /// Returns a reference to a [`zero`] value for missing keys.
+    ///
+    /// [`zero`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    fn index(&self, key: &'_ Q) -> &N {
+        self.map.get(key).unwrap_or(&self.zero)
+    }
+}
+
+impl<T, Q, N> IndexMut<&'_ Q> for Counter<T, N>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ToOwned<Owned = T> + ?Sized,
+    N: Zero,
+{
+    /// Index in mutable contexts.
+    ///
+    /// If the given key is not present, appends a new entry and initializes it with a [`zero`]
+    /// value.
+    ///
+    /// [`zero`]:
+    /// https://docs.rs/num-traits/latest/num_traits/identities/trait.Zero.html#tymethod.zero
+    fn index_mut(&mut self, key: &'_ Q) -> &mut N {
+        self.map.entry(key.to_owned()).or_insert_with(N::zero)
+    }
+}
+
+impl<T, N> iter::FromIterator<T> for Counter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+{
+    /// Produce a `Counter` from an iterator of items.
+    fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
+        let mut counter = Counter::new();
+        counter.update(iterable);
+        counter
+    }
+}
+
+impl<T, N> iter::FromIterator<(T, N)> for Counter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Creates a `Counter` from `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn from_iter<I: IntoIterator<Item = (T, N)>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T, N> Extend<T> for Counter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero + One,
+{
+    /// Extend a `Counter` with an iterator of items.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, N> Extend<(T, N)> for Counter<T, N>
+where
+    T: Hash + Eq,
+    N: AddAssign + Zero,
+{
+    /// Extend a `Counter` with `(item, count)` tuples.
+    ///
+    /// The counts of duplicate items are summed.
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
+        for (item, item_count) in iter {
+            let entry = self.map.entry(item).or_insert_with(N::zero);
+            *entry += item_count;
+        }
+    }
+}
+
+impl<'a, T, N> IntoIterator for &'a Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Item = (&'a T, &'a N);
+    type IntoIter = map::Iter<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<'a, T, N> IntoIterator for &'a mut Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Item = (&'a T, &'a mut N);
+    type IntoIter = map::IterMut<'a, T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut()
+    }
+}
+
+impl<T, N> IntoIterator for Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Item = (T, N);
+    type IntoIter = map::IntoIter<T, N>;
+
+    /// Consumes the `Counter` to produce an iterator over its `(item, count)` pairs in the
+    /// order in which the items were first counted.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter()
+    }
+}
+
+impl<T, N, S> From<HashCounter<T, N, S>> for Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Move the items of a hash-based [`crate::Counter`] into an insertion-ordered `Counter`.
+    ///
+    /// The hash-based counter does not know the order in which its items were counted, so they
+    /// are inserted in its arbitrary iteration order.
+    fn from(counter: HashCounter<T, N, S>) -> Self {
+        Counter {
+            map: counter.map.into_iter().collect(),
+            zero: counter.zero,
+        }
+    }
+}
+
+impl<T, N> From<Counter<T, N>> for HashCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Move the items of an insertion-ordered `Counter` into a hash-based [`crate::Counter`].
+    ///
+    /// ```rust
+    /// let preserving = "abracadabra".chars().collect::<counter::preserving::Counter<_>>();
+    /// let counter = counter::Counter::from(preserving.clone());
+    /// assert_eq!(counter[&'a'], 5);
+    /// assert_eq!(counter::preserving::Counter::from(counter).len(), preserving.len());
+    /// ```
+    fn from(counter: Counter<T, N>) -> Self {
+        HashCounter {
+            map: counter.map.into_iter().collect(),
+            zero: counter.zero,
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::{CheckedMul, Zero};
+
+use core::hash::{BuildHasher, Hash};
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: CheckedMul + Zero,
+    S: BuildHasher + Default,
+{
+    /// Returns the multiset outer product of this counter and `other`: a counter of every pair
+    /// `(a, b)` of their items, where the count of `(a, b)` is `self[a] * other[b]`.
+    ///
+    /// If the two counters count independent observations, this is the baseline against which
+    /// to compare a counter of their co-occurrences.
+    ///
+    /// Returns `None` if any product overflows `N`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let colors = "rrg".chars().collect::<Counter<_>>();
+    /// let sizes = [1, 2, 2].into_iter().collect::<Counter<_>>();
+    ///
+    /// let product = colors.cartesian_product(&sizes).unwrap();
+    /// assert_eq!(product[&('r', 2)], 4);
+    /// assert_eq!(product[&('g', 1)], 1);
+    /// assert_eq!(product.len(), 4);
+    ///
+    /// let huge = Counter::<_, u8>::from([('x', 16)]);
+    /// assert_eq!(huge.cartesian_product(&huge), None);
+    /// ```
+    pub fn cartesian_product<U, S2>(
+        &self,
+        other: &Counter<U, N, S2>,
+    ) -> Option<Counter<(T, U), N, S>>
+    where
+        U: Hash + Eq + Clone,
+    {
+        let mut map =
+            HashMap::with_capacity_and_hasher(self.map.len() * other.map.len(), S::default());
+        for (a, a_count) in &self.map {
+            for (b, b_count) in &other.map {
+                map.insert((a.clone(), b.clone()), a_count.checked_mul(b_count)?);
+            }
+        }
+        Some(Counter {
+            map,
+            zero: N::zero(),
+        })
+    }
+}
@@ -0,0 +1,110 @@
+//! Strategies for generating counters in property tests.
+//!
+//! With the `proptest` feature, [`Counter`] implements [`proptest::arbitrary::Arbitrary`],
+//! generating counters whose counts are all positive, like those built by counting. For other
+//! shapes of counter, [`counter`] builds a strategy from strategies for the items and the
+//! counts, which are used as they are: pass it a strategy which can produce zero or negative
+//! counts to test code which must cope with them.
+//!
+//! ```rust
+//! # use counter::Counter;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     // Usually a `#[test]`.
+//!     fn counts_are_positive(counter in any::<Counter<char, u32>>()) {
+//!         prop_assert!(counter.values().all(|&count| count > 0));
+//!     }
+//!
+//!     fn signed_counts_can_be_negative(
+//!         counter in counter::proptest::counter(0..4u8, -5..5i32, 0..=4),
+//!     ) {
+//!         let counter: Counter<u8, i32> = counter;
+//!         prop_assert!(counter.values().all(|&count| (-5..5).contains(&count)));
+//!     }
+//! }
+//! # counts_are_positive();
+//! # signed_counts_can_be_negative();
+//! ```
+
+use crate::Counter;
+
+use crate::num::Zero;
+
+use ::proptest::arbitrary::{any_with, Arbitrary};
+use ::proptest::collection::{hash_map, HashMapStrategy, SizeRange};
+use ::proptest::strategy::{Filter, Map, Strategy};
+
+use core::fmt::Debug;
+use core::hash::{BuildHasher, Hash};
+use std::collections::HashMap;
+
+/// Generates counters of between `size.start` and `size.end` distinct items, drawn from
+/// `items`, with counts drawn from `counts`.
+///
+/// Every count produced by `counts` is kept, including zero and negative ones, so the
+/// strategy for the counts decides which counters can be generated. Fewer items than asked
+/// for are only generated if `items` cannot produce enough distinct values.
+///
+/// ```rust
+/// # use counter::Counter;
+/// use proptest::strategy::{Strategy, ValueTree};
+/// use proptest::test_runner::TestRunner;
+///
+/// let strategy = counter::proptest::counter(0..10u8, 1..=3usize, 2..=4);
+/// let counter: Counter<u8> = strategy.new_tree(&mut TestRunner::default()).unwrap().current();
+/// assert!((2..=4).contains(&counter.len()));
+/// assert!(counter.values().all(|&count| (1..=3).contains(&count)));
+/// ```
+pub fn counter<T, N, S>(
+    items: impl Strategy<Value = T>,
+    counts: impl Strategy<Value = N>,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = Counter<T, N, S>>
+where
+    T: Hash + Eq + Debug,
+    N: Zero + Debug,
+    S: BuildHasher + Default,
+{
+    hash_map(items, counts, size).prop_map(from_map)
+}
+
+/// Generates counters whose counts are all positive.
+///
+/// The counts are drawn from the strategy for `N`, rejecting each value which is zero or
+/// negative, so the number of items follows the size range as it does for [`counter`]. The
+/// parameters are the range of the number of items, and the parameters of the items and the
+/// counts. To generate counts which aren't positive, use [`counter`] instead.
+impl<T, N, S> Arbitrary for Counter<T, N, S>
+where
+    T: Arbitrary + Hash + Eq,
+    N: Arbitrary + PartialOrd + Zero,
+    S: BuildHasher + Default,
+{
+    type Parameters = (SizeRange, T::Parameters, N::Parameters);
+    type Strategy = Map<
+        HashMapStrategy<T::Strategy, Filter<N::Strategy, fn(&N) -> bool>>,
+        fn(HashMap<T, N>) -> Self,
+    >;
+
+    fn arbitrary_with((size, items, counts): Self::Parameters) -> Self::Strategy {
+        let counts = any_with::<N>(counts)
+            .prop_filter("counts must be positive", is_positive as fn(&N) -> bool);
+        hash_map(any_with::<T>(items), counts, size).prop_map(from_map)
+    }
+}
+
+fn is_positive<N: PartialOrd + Zero>(count: &N) -> bool {
+    *count > N::zero()
+}
+
+fn from_map<T, N, S>(map: HashMap<T, N>) -> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Zero,
+    S: BuildHasher + Default,
+{
+    let mut counter = Counter::with_hasher(S::default());
+    counter.map.extend(map);
+    counter
+}
@@ -0,0 +1,264 @@
+//! Read-optimized snapshots of a counter which is being updated by a single writer.
+//!
+//! A [`PublishedCounter`] wraps a [`Counter`] owned by a writer. Each call to
+//! [`publish()`](PublishedCounter::publish) freezes the current counts into an immutable
+//! [`CompactCounter`] and swaps it into a slot from which any number of [`CounterReader`]s can
+//! load it without taking a lock.
+//!
+//! ```rust
+//! # use counter::publish::PublishedCounter;
+//! let mut writer = PublishedCounter::<&str>::new();
+//! let reader = writer.reader();
+//!
+//! writer.update(["GET", "GET", "POST"]);
+//! assert_eq!(reader.load().generation(), 0);
+//! writer.publish();
+//!
+//! let handle = std::thread::spawn(move || {
+//!     let snapshot = reader.load();
+//!     assert_eq!(snapshot.generation(), 1);
+//!     snapshot.k_most_common(1).to_vec()
+//! });
+//! assert_eq!(handle.join().unwrap(), [("GET", 2)]);
+//! ```
+
+use crate::Counter;
+
+use crate::num::Zero;
+use arc_swap::ArcSwap;
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::{Deref, DerefMut, Index};
+use std::sync::Arc;
+
+/// An immutable, ranked snapshot of a [`Counter`].
+///
+/// The items are stored in decreasing order of their counts, with ties broken by the natural
+/// ordering of the items, so the top *k* items are simply a prefix of
+/// [`most_common()`](CompactCounter::most_common). An index from items to ranks supports
+/// lookups by item.
+#[derive(Clone, Debug)]
+pub struct CompactCounter<T, N = usize> {
+    ranked: Box<[(T, N)]>,
+    ranks: HashMap<T, usize>,
+    generation: u64,
+    zero: N,
+}
+
+impl<T, N> CompactCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Returns the generation of this snapshot: the number of times the writer had published
+    /// when it was taken. The empty snapshot available before the first publication is
+    /// generation 0.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the number of distinct items in the snapshot.
+    pub fn len(&self) -> usize {
+        self.ranked.len()
+    }
+
+    /// Returns `true` if the snapshot contains no items.
+    pub fn is_empty(&self) -> bool {
+        self.ranked.is_empty()
+    }
+
+    /// Returns the count of `item`, or `None` if it is not in the snapshot.
+    pub fn get<Q>(&self, item: &Q) -> Option<&N>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ranks.get(item).map(|&rank| &self.ranked[rank].1)
+    }
+
+    /// Returns the zero-based rank of `item` in [`most_common()`](CompactCounter::most_common),
+    /// or `None` if it is not in the snapshot.
+    pub fn rank<Q>(&self, item: &Q) -> Option<usize>
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ranks.get(item).copied()
+    }
+
+    /// Returns all `(item, count)` pairs, sorted from most to least common.
+    pub fn most_common(&self) -> &[(T, N)] {
+        &self.ranked
+    }
+
+    /// Returns the `k` most common `(item, count)` pairs, or all of them if there are fewer
+    /// than `k`.
+    pub fn k_most_common(&self, k: usize) -> &[(T, N)] {
+        &self.ranked[..k.min(self.ranked.len())]
+    }
+
+    /// Returns the sum of the counts.
+    pub fn total<'a, M>(&'a self) -> M
+    where
+        M: std::iter::Sum<&'a N>,
+    {
+        self.ranked.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl<T, N> CompactCounter<T, N>
+where
+    T: Hash + Eq + Ord + Clone,
+    N: Ord + Clone + Zero,
+{
+    fn new(counter: &Counter<T, N>, generation: u64) -> Self {
+        let ranked: Box<[(T, N)]> = counter.most_common_ordered().into_boxed_slice();
+        let ranks = ranked
+            .iter()
+            .enumerate()
+            .map(|(rank, (item, _))| (item.clone(), rank))
+            .collect();
+        CompactCounter {
+            ranked,
+            ranks,
+            generation,
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, Q, N> Index<&'_ Q> for CompactCounter<T, N>
+where
+    T: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = N;
+
+    /// Returns a reference to a zero value for missing items, like [`Counter`].
+    fn index(&self, item: &'_ Q) -> &N {
+        self.get(item).unwrap_or(&self.zero)
+    }
+}
+
+/// A cheaply cloneable handle from which readers load the latest published snapshot.
+#[derive(Debug)]
+pub struct CounterReader<T, N = usize> {
+    slot: Arc<ArcSwap<CompactCounter<T, N>>>,
+}
+
+impl<T, N> Clone for CounterReader<T, N> {
+    fn clone(&self) -> Self {
+        CounterReader {
+            slot: Arc::clone(&self.slot),
+        }
+    }
+}
+
+impl<T, N> CounterReader<T, N> {
+    /// Load the most recently published snapshot, without blocking the writer or other
+    /// readers.
+    pub fn load(&self) -> Arc<CompactCounter<T, N>> {
+        self.slot.load_full()
+    }
+}
+
+/// A writer-side [`Counter`] whose state can be published as immutable snapshots.
+///
+/// Dereferences to the wrapped counter, so the writer has the full counter API. Changes are
+/// not visible to readers until [`publish()`](PublishedCounter::publish) is called.
+#[derive(Debug)]
+pub struct PublishedCounter<T: Hash + Eq, N = usize> {
+    counter: Counter<T, N>,
+    slot: Arc<ArcSwap<CompactCounter<T, N>>>,
+    generation: u64,
+}
+
+impl<T, N> PublishedCounter<T, N>
+where
+    T: Hash + Eq + Ord + Clone,
+    N: Ord + Clone + Zero,
+{
+    /// Create a new, empty `PublishedCounter`. Readers see an empty snapshot until the first
+    /// call to [`publish()`](PublishedCounter::publish).
+    pub fn new() -> Self {
+        Self::from_counter(Counter::new())
+    }
+
+    /// Wrap an existing counter. Readers see an empty snapshot until the first call to
+    /// [`publish()`](PublishedCounter::publish).
+    pub fn from_counter(counter: Counter<T, N>) -> Self {
+        let empty = CompactCounter::new(&Counter::new(), 0);
+        PublishedCounter {
+            counter,
+            slot: Arc::new(ArcSwap::from_pointee(empty)),
+            generation: 0,
+        }
+    }
+
+    /// Freeze the current counts into a new snapshot and make it visible to all readers.
+    ///
+    /// Returns the new snapshot.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* \* log *n*), where *n* is the number of items in the counter, to rank them.
+    pub fn publish(&mut self) -> Arc<CompactCounter<T, N>> {
+        self.generation += 1;
+        let snapshot = Arc::new(CompactCounter::new(&self.counter, self.generation));
+        self.slot.store(Arc::clone(&snapshot));
+        snapshot
+    }
+}
+
+impl<T, N> Default for PublishedCounter<T, N>
+where
+    T: Hash + Eq + Ord + Clone,
+    N: Ord + Clone + Zero,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N> PublishedCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Create a handle from which readers can load the published snapshots.
+    pub fn reader(&self) -> CounterReader<T, N> {
+        CounterReader {
+            slot: Arc::clone(&self.slot),
+        }
+    }
+
+    /// Returns the generation of the most recently published snapshot.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Consumes the writer and returns the wrapped counter. Existing readers keep access to the
+    /// last published snapshot.
+    pub fn into_inner(self) -> Counter<T, N> {
+        self.counter
+    }
+}
+
+impl<T, N> Deref for PublishedCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Target = Counter<T, N>;
+    fn deref(&self) -> &Counter<T, N> {
+        &self.counter
+    }
+}
+
+impl<T, N> DerefMut for PublishedCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    fn deref_mut(&mut self) -> &mut Counter<T, N> {
+        &mut self.counter
+    }
+}
@@ -0,0 +1,221 @@
+use crate::elements::repetitions;
+use crate::frequencies::count_to_f64;
+use crate::{Counter, Error};
+
+use crate::num::{NumCast, ToPrimitive, Zero};
+
+use rand::distributions::Distribution;
+use rand::seq::index;
+use rand::Rng;
+use rand_distr::WeightedAliasIndex;
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Keep a uniformly random subset of `k` items, with their counts intact, and discard the
+    /// rest.
+    ///
+    /// Every subset of `k` items is equally likely to be kept, whatever the counts. If the
+    /// counter has no more than `k` items, it is unchanged.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbcccdddd".chars().collect::<Counter<_>>();
+    /// counter.downsample_keys(2, &mut rand::thread_rng());
+    /// assert_eq!(counter.len(), 2);
+    /// for (item, count) in &counter {
+    ///     assert_eq!(*count, "abcd".find(*item).unwrap() + 1);
+    /// }
+    /// ```
+    pub fn downsample_keys<R>(&mut self, k: usize, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        let len = self.map.len();
+        if k >= len {
+            return;
+        }
+        let mut keep = vec![false; len];
+        for position in index::sample(rng, len, k) {
+            keep[position] = true;
+        }
+        let mut positions = keep.into_iter();
+        self.map.retain(|_, _| positions.next().unwrap_or(false));
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive + NumCast + PartialOrd + Zero,
+{
+    /// Keep a uniformly random sample of `m` of the counted observations, drawn without
+    /// replacement, and discard the rest.
+    ///
+    /// Each item keeps, on average, its share of the `m` observations in proportion to its
+    /// count. Items none of whose observations are sampled are removed. Non-positive counts
+    /// contribute no observations. If the counter holds no more than `m` observations, it is
+    /// unchanged.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "abbcccdddd".chars().collect::<Counter<_>>();
+    /// counter.downsample_observations(4, &mut rand::thread_rng());
+    /// assert_eq!(counter.total::<usize>(), 4);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total count does not fit in a `usize`. Use
+    /// [`try_downsample_observations`](Counter::try_downsample_observations) to get an error
+    /// instead.
+    pub fn downsample_observations<R>(&mut self, m: usize, rng: &mut R)
+    where
+        R: Rng + ?Sized,
+    {
+        if let Err(err) = self.try_downsample_observations(m, rng) {
+            panic!("{err}");
+        }
+    }
+
+    /// Keep a uniformly random sample of `m` of the counted observations, as
+    /// [`downsample_observations`](Counter::downsample_observations) does, unless the total
+    /// count does not fit in a `usize`.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Error};
+    /// let mut counter = Counter::<_, u128>::from([('a', u128::MAX), ('b', 1)]);
+    /// let result = counter.try_downsample_observations(4, &mut rand::thread_rng());
+    /// assert!(matches!(result, Err(Error::Overflow)));
+    /// assert_eq!(counter[&'b'], 1);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if the total count does not fit in a `usize`. The counter is
+    /// left unchanged in that case.
+    pub fn try_downsample_observations<R>(&mut self, m: usize, rng: &mut R) -> Result<(), Error>
+    where
+        R: Rng + ?Sized,
+    {
+        let total = self
+            .map
+            .values()
+            .try_fold(0usize, |total, count| total.checked_add(repetitions(count)))
+            .ok_or(Error::Overflow)?;
+        if m >= total {
+            return Ok(());
+        }
+
+        let mut sampled = index::sample(rng, total, m).into_vec();
+        sampled.sort_unstable();
+        let mut sampled = sampled.into_iter().peekable();
+
+        // Observation `i` belongs to the item whose run of positions `start..end` contains it.
+        let mut start = 0;
+        self.map.retain(|_, count| {
+            let end = start + repetitions(count);
+            let mut kept = 0usize;
+            while sampled.next_if(|&position| position < end).is_some() {
+                kept += 1;
+            }
+            start = end;
+            match N::from(kept) {
+                Some(kept) if !kept.is_zero() => {
+                    *count = kept;
+                    true
+                }
+                _ => false,
+            }
+        });
+        Ok(())
+    }
+}
+
+/// A table for drawing items at random in proportion to their counts, in constant time per
+/// draw, built by [`Counter::to_weighted_index`].
+///
+/// This pairs a [`rand_distr::WeightedAliasIndex`] with a table of the items it indexes:
+/// building it takes time linear in the number of items, after which each draw takes one
+/// uniform index and one coin flip. The table holds its own copy of the items, in the same
+/// order as their weights, so it can be kept after the counter changes.
+///
+/// ```rust
+/// # use counter::Counter;
+/// # use rand::distributions::Distribution;
+/// let counter = "aaab".chars().collect::<Counter<_>>();
+/// let table = counter.to_weighted_index().unwrap();
+///
+/// let mut rng = rand::thread_rng();
+/// let draws = table.sample_iter(&mut rng).take(1000).collect::<Counter<_>>();
+/// assert!(draws[&'a'] > draws[&'b']);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AliasTable<T> {
+    items: Vec<T>,
+    index: WeightedAliasIndex<f64>,
+}
+
+impl<T> AliasTable<T> {
+    /// Returns the items which can be drawn, in the order of their indices.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Draw the index of an item into [`items`](AliasTable::items).
+    pub fn sample_index<R>(&self, rng: &mut R) -> usize
+    where
+        R: Rng + ?Sized,
+    {
+        self.index.sample(rng)
+    }
+
+    /// Draw a reference to an item.
+    pub fn sample_ref<R>(&self, rng: &mut R) -> &T
+    where
+        R: Rng + ?Sized,
+    {
+        &self.items[self.sample_index(rng)]
+    }
+}
+
+impl<T> Distribution<T> for AliasTable<T>
+where
+    T: Clone,
+{
+    fn sample<R>(&self, rng: &mut R) -> T
+    where
+        R: Rng + ?Sized,
+    {
+        self.sample_ref(rng).clone()
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: ToPrimitive,
+{
+    /// Build an [`AliasTable`] which draws each item with probability proportional to its
+    /// count.
+    ///
+    /// Items whose count is not positive are never drawn. Returns `None` if no item has a
+    /// positive count, or if the counts are too large for the table, such as when their total
+    /// is not finite as an `f64` or there are more than `u32::MAX` items.
+    pub fn to_weighted_index(&self) -> Option<AliasTable<T>> {
+        let (items, weights): (Vec<T>, Vec<f64>) = self
+            .map
+            .iter()
+            .map(|(item, count)| (item, count_to_f64(count)))
+            .filter(|&(_, weight)| weight > 0.0)
+            .map(|(item, weight)| (item.clone(), weight))
+            .unzip();
+        let index = WeightedAliasIndex::new(weights).ok()?;
+        Some(AliasTable { items, index })
+    }
+}
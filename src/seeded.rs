@@ -0,0 +1,101 @@
+use crate::collections::HashMap;
+use crate::Counter;
+
+use crate::num::Zero;
+
+use core::hash::{BuildHasher, Hash, Hasher};
+
+/// A [`BuildHasher`] whose hashers depend only on a fixed seed.
+///
+/// Counters built with the same seed iterate their items in the same order whenever they are
+/// built by the same sequence of operations, so runs which depend on that order, such as
+/// breaking ties in [`Counter::most_common`], are reproducible. Unlike the default hasher, it
+/// is not randomized, so it offers no protection against maliciously chosen keys.
+///
+/// The hash function is a seeded FNV-1a with a final avalanche step. It may change between
+/// versions of this crate, so do not persist the hashes or rely on the order across upgrades.
+///
+/// ```rust
+/// # use counter::{Counter, SeededState};
+/// let mut a = Counter::<char, usize, SeededState>::with_seed(42);
+/// a.update("the quick brown fox".chars());
+/// let mut b = Counter::<char, usize, SeededState>::with_seed(42);
+/// b.update("the quick brown fox".chars());
+/// assert!(a.keys().eq(b.keys()));
+/// assert_eq!(a.hasher().seed(), 42);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SeededState {
+    seed: u64,
+}
+
+impl SeededState {
+    /// Create a `SeededState` from a seed.
+    pub const fn new(seed: u64) -> Self {
+        SeededState { seed }
+    }
+
+    /// Returns the seed.
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl BuildHasher for SeededState {
+    type Hasher = SeededHasher;
+
+    fn build_hasher(&self) -> SeededHasher {
+        SeededHasher {
+            state: FNV_OFFSET_BASIS ^ self.seed.wrapping_mul(FNV_PRIME),
+        }
+    }
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// The [`Hasher`] built by a [`SeededState`].
+#[derive(Clone, Debug)]
+pub struct SeededHasher {
+    state: u64,
+}
+
+impl Hasher for SeededHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.state = (self.state ^ u64::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        // The SplitMix64 finalizer, so that the high bits depend on every input byte.
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+impl<T, N> Counter<T, N, SeededState>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `Counter` whose hasher is a [`SeededState`] with the given seed.
+    pub fn with_seed(seed: u64) -> Self {
+        Counter {
+            map: HashMap::with_hasher(SeededState::new(seed)),
+            zero: N::zero(),
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+{
+    /// Returns a reference to the counter's [`BuildHasher`].
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+}
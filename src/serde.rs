@@ -0,0 +1,376 @@
+//! Adapters choosing how a [`Counter`] field is represented, for use with
+//! `#[serde(with = "...")]`.
+//!
+//! [`Counter`] implements `Serialize` and `Deserialize` itself, as a map from items to counts.
+//! These modules pick the representation per field instead:
+//!
+//! - [`as_map`] represents the counter as a map from items to counts, which is the most natural
+//!   form when the items serialize as strings, as in JSON objects.
+//! - [`as_pairs`] represents it as a sequence of `(item, count)` pairs, which works whatever the
+//!   items are.
+//!
+//! Both are compact in binary formats such as bincode.
+//!
+//! Neither representation has a stable order: items are written in the order of the hash map
+//! backing the counter, which varies from run to run. Where the output must be reproducible,
+//! such as in golden files or diffs, [`sorted_map`] and [`sorted_pairs`] write the items in
+//! increasing order instead, and [`Counter::as_sorted_serialize`] serializes a whole counter in
+//! a chosen [`OrderBy`].
+//!
+//! ```rust
+//! # use counter::Counter;
+//! use serde::{Deserialize, Serialize};
+//! use uuid::Uuid;
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Usage {
+//!     #[serde(with = "counter::serde::as_map")]
+//!     requests_by_user: Counter<Uuid, u64>,
+//!     #[serde(with = "counter::serde::as_pairs")]
+//!     errors_by_status: Counter<(u16, bool), u64>,
+//! }
+//!
+//! let user = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+//! let usage = Usage {
+//!     requests_by_user: Counter::from([(user, 3)]),
+//!     errors_by_status: Counter::from([((503, true), 1)]),
+//! };
+//!
+//! let json = serde_json::to_string(&usage).unwrap();
+//! assert_eq!(
+//!     json,
+//!     r#"{"requests_by_user":{"67e55044-10b1-426f-9247-bb680e5fe0c8":3},"errors_by_status":[[[503,true],1]]}"#,
+//! );
+//! assert_eq!(serde_json::from_str::<Usage>(&json).unwrap(), usage);
+//!
+//! let bytes = bincode::serialize(&usage).unwrap();
+//! assert_eq!(bincode::deserialize::<Usage>(&bytes).unwrap(), usage);
+//! ```
+
+use crate::{Counter, OrderBy};
+
+use serde::{Serialize, Serializer};
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::Hash;
+
+use as_map::serialize_pairs;
+
+/// Represents a [`Counter`] as a map from items to counts.
+///
+/// Use it with `#[serde(with = "counter::serde::as_map")]`; see the [module docs](self).
+pub mod as_map {
+    use crate::collections::HashMap;
+    use crate::Counter;
+
+    use crate::num::Zero;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use core::hash::{BuildHasher, Hash};
+
+    /// Serializes `counter` as a map from items to counts.
+    pub fn serialize<T, N, S, Ser>(
+        counter: &Counter<T, N, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + Hash + Eq,
+        N: Serialize,
+        Ser: Serializer,
+    {
+        serializer.collect_map(&counter.map)
+    }
+
+    pub(super) fn serialize_pairs<'a, T, N, I, Ser>(
+        pairs: I,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + 'a,
+        N: Serialize + 'a,
+        I: IntoIterator<Item = (&'a T, &'a N)>,
+        Ser: Serializer,
+    {
+        serializer.collect_map(pairs)
+    }
+
+    /// Deserializes a counter from a map from items to counts.
+    pub fn deserialize<'de, T, N, S, D>(deserializer: D) -> Result<Counter<T, N, S>, D::Error>
+    where
+        T: Deserialize<'de> + Hash + Eq,
+        N: Deserialize<'de> + Zero,
+        S: BuildHasher + Default,
+        D: Deserializer<'de>,
+    {
+        let map = HashMap::deserialize(deserializer)?;
+        Ok(Counter {
+            map,
+            zero: N::zero(),
+        })
+    }
+}
+
+/// Represents a [`Counter`] as a sequence of `(item, count)` pairs.
+///
+/// Use it with `#[serde(with = "counter::serde::as_pairs")]`; see the [module docs](self).
+pub mod as_pairs {
+    use crate::collections::HashMap;
+    use crate::Counter;
+
+    use crate::num::Zero;
+
+    use serde::de::{SeqAccess, Visitor};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use core::fmt;
+    use core::hash::{BuildHasher, Hash};
+    use core::marker::PhantomData;
+    use core::ops::AddAssign;
+
+    /// Serializes `counter` as a sequence of `(item, count)` pairs.
+    pub fn serialize<T, N, S, Ser>(
+        counter: &Counter<T, N, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + Hash + Eq,
+        N: Serialize,
+        Ser: Serializer,
+    {
+        serializer.collect_seq(&counter.map)
+    }
+
+    pub(super) fn serialize_pairs<'a, T, N, I, Ser>(
+        pairs: I,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + 'a,
+        N: Serialize + 'a,
+        I: IntoIterator<Item = (&'a T, &'a N)>,
+        Ser: Serializer,
+    {
+        serializer.collect_seq(pairs)
+    }
+
+    /// Deserializes a counter from a sequence of `(item, count)` pairs. The counts of duplicate
+    /// items are summed.
+    pub fn deserialize<'de, T, N, S, D>(deserializer: D) -> Result<Counter<T, N, S>, D::Error>
+    where
+        T: Deserialize<'de> + Hash + Eq,
+        N: Deserialize<'de> + AddAssign + Zero,
+        S: BuildHasher + Default,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(PairsVisitor(PhantomData))
+    }
+
+    struct PairsVisitor<T, N, S>(PhantomData<(T, N, S)>);
+
+    impl<'de, T, N, S> Visitor<'de> for PairsVisitor<T, N, S>
+    where
+        T: Deserialize<'de> + Hash + Eq,
+        N: Deserialize<'de> + AddAssign + Zero,
+        S: BuildHasher + Default,
+    {
+        type Value = Counter<T, N, S>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a sequence of (item, count) pairs")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            // Don't trust an untrusted size hint with an arbitrarily large allocation.
+            let capacity = seq.size_hint().unwrap_or(0).min(4096);
+            let mut map = HashMap::with_capacity_and_hasher(capacity, S::default());
+            while let Some((item, count)) = seq.next_element::<(T, N)>()? {
+                *map.entry(item).or_insert_with(N::zero) += count;
+            }
+            Ok(Counter {
+                map,
+                zero: N::zero(),
+            })
+        }
+    }
+}
+
+/// Represents a [`Counter`] as a map from items to counts, written in
+/// increasing order of the items, so that the same counter always serializes the same way.
+///
+/// Use it with `#[serde(with = "counter::serde::sorted_map")]`. Deserializing accepts the
+/// items in any order, exactly as [`as_map`] does.
+pub mod sorted_map {
+    use crate::Counter;
+
+    use serde::{Serialize, Serializer};
+
+    use core::hash::Hash;
+
+    pub use super::as_map::deserialize;
+
+    /// Serializes `counter` as a map from items to counts, in increasing order of the items.
+    pub fn serialize<T, N, S, Ser>(
+        counter: &Counter<T, N, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + Hash + Eq + Ord,
+        N: Serialize,
+        Ser: Serializer,
+    {
+        super::as_map::serialize_pairs(super::sorted_by_item(counter), serializer)
+    }
+}
+
+/// Represents a [`Counter`] as a sequence of `(item, count)` pairs, written in
+/// increasing order of the items, so that the same counter always serializes the same way.
+///
+/// Use it with `#[serde(with = "counter::serde::sorted_pairs")]`. Deserializing accepts the
+/// pairs in any order, exactly as [`as_pairs`] does.
+pub mod sorted_pairs {
+    use crate::Counter;
+
+    use serde::{Serialize, Serializer};
+
+    use core::hash::Hash;
+
+    pub use super::as_pairs::deserialize;
+
+    /// Serializes `counter` as a sequence of `(item, count)` pairs, in increasing order of the
+    /// items.
+    pub fn serialize<T, N, S, Ser>(
+        counter: &Counter<T, N, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        T: Serialize + Hash + Eq + Ord,
+        N: Serialize,
+        Ser: Serializer,
+    {
+        super::as_pairs::serialize_pairs(super::sorted_by_item(counter), serializer)
+    }
+}
+
+fn sorted_by_item<T, N, S>(counter: &Counter<T, N, S>) -> Vec<(&T, &N)>
+where
+    T: Hash + Eq + Ord,
+{
+    let mut pairs = counter.map.iter().collect::<Vec<_>>();
+    pairs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    pairs
+}
+
+/// Serializes a [`Counter`] with its items in a fixed order; see
+/// [`Counter::as_sorted_serialize`].
+pub struct SortedSerialize<'a, T: Hash + Eq, N, S> {
+    counter: &'a Counter<T, N, S>,
+    order: OrderBy<'a, T, N>,
+    // Chosen when the wrapper is created, so that serializing needs no more bounds on the
+    // counts than the order does.
+    compare: PairCompare<'a, T, N>,
+}
+
+type PairCompare<'a, T, N> = fn(&OrderBy<'a, T, N>, (&T, &N), (&T, &N)) -> Ordering;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+{
+    /// Borrow this counter as a value which serializes with its items in `order`, so that the
+    /// output is the same every time the counter is serialized.
+    ///
+    /// The representation is the counter's own, a map from items to counts; for a sequence of
+    /// pairs, use [`sorted_pairs`] on a field instead. The pairs are sorted by reference, so
+    /// neither the items nor the counts are copied. Ordering by item works for counts which are
+    /// not [`Ord`], such as floats, with [`as_key_sorted_serialize`] instead.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, OrderBy};
+    /// let counter = "abracadabra".chars().collect::<Counter<_>>();
+    ///
+    /// let json = serde_json::to_string(&counter.as_sorted_serialize(OrderBy::KeyAsc)).unwrap();
+    /// assert_eq!(json, r#"{"a":5,"b":2,"c":1,"d":1,"r":2}"#);
+    ///
+    /// let json = serde_json::to_string(&counter.as_sorted_serialize(OrderBy::CountDesc)).unwrap();
+    /// assert_eq!(json, r#"{"a":5,"b":2,"r":2,"c":1,"d":1}"#);
+    /// assert_eq!(serde_json::from_str::<Counter<char>>(&json).unwrap(), counter);
+    /// ```
+    ///
+    /// [`as_key_sorted_serialize`]: Counter::as_key_sorted_serialize
+    pub fn as_sorted_serialize<'a>(
+        &'a self,
+        order: OrderBy<'a, T, N>,
+    ) -> SortedSerialize<'a, T, N, S>
+    where
+        N: Ord,
+    {
+        SortedSerialize {
+            counter: self,
+            order,
+            compare: OrderBy::compare,
+        }
+    }
+
+    /// Borrow this counter as a value which serializes with its items in increasing order, as
+    /// [`as_sorted_serialize`](Counter::as_sorted_serialize) does with [`OrderBy::KeyAsc`],
+    /// whatever the type of the counts.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let weights = Counter::<_, f64>::from([('b', 0.5), ('a', 1.5)]);
+    /// let json = serde_json::to_string(&weights.as_key_sorted_serialize()).unwrap();
+    /// assert_eq!(json, r#"{"a":1.5,"b":0.5}"#);
+    /// ```
+    pub fn as_key_sorted_serialize(&self) -> SortedSerialize<'_, T, N, S> {
+        SortedSerialize {
+            counter: self,
+            order: OrderBy::KeyAsc,
+            compare: |_, a, b| a.0.cmp(b.0),
+        }
+    }
+}
+
+impl<T, N, S> Serialize for SortedSerialize<'_, T, N, S>
+where
+    T: Serialize + Hash + Eq,
+    N: Serialize,
+{
+    fn serialize<Ser>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        let mut pairs = self.counter.map.iter().collect::<Vec<_>>();
+        pairs.sort_unstable_by(|a, b| (self.compare)(&self.order, *a, *b));
+        serialize_pairs(pairs, serializer)
+    }
+}
+
+impl<T, N, S> Clone for SortedSerialize<'_, T, N, S>
+where
+    T: Hash + Eq,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, N, S> Copy for SortedSerialize<'_, T, N, S> where T: Hash + Eq {}
+
+impl<T, N, S> fmt::Debug for SortedSerialize<'_, T, N, S>
+where
+    T: Hash + Eq + fmt::Debug,
+    N: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SortedSerialize")
+            .field("counter", self.counter)
+            .field("order", &self.order)
+            .finish()
+    }
+}
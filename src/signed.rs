@@ -0,0 +1,90 @@
+use crate::Counter;
+
+use crate::num::{Signed, Zero};
+
+use core::hash::{BuildHasher, Hash};
+use core::ops::SubAssign;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Signed + SubAssign,
+    S: BuildHasher,
+{
+    /// Subtract pointwise, keeping zero and negative counts.
+    ///
+    /// `out = c.signed_sub(d);` -> `out[x] == c[x] - d[x]` for all `x`, including items
+    /// only in `d`, which get a negative count. This is Python's `Counter.subtract()`, as
+    /// opposed to the `-` operator, which keeps only positive counts.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let c = "aaab".chars().collect::<Counter<_, i32>>();
+    /// let d = "abbc".chars().collect::<Counter<_, i32>>();
+    ///
+    /// let e = c.signed_sub(d);
+    /// assert_eq!(e, Counter::from([('a', 2), ('b', -1), ('c', -1)]));
+    /// ```
+    pub fn signed_sub(mut self, rhs: Self) -> Self {
+        self.signed_sub_assign(rhs);
+        self
+    }
+
+    /// Subtract pointwise in place, keeping zero and negative counts.
+    ///
+    /// `c.signed_sub_assign(d);` -> `c[x] -= d[x]` for all `x`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut c = "ab".chars().collect::<Counter<_, i64>>();
+    /// c.signed_sub_assign("bc".chars().collect());
+    /// assert_eq!(c, Counter::from([('a', 1), ('b', 0), ('c', -1)]));
+    /// ```
+    pub fn signed_sub_assign(&mut self, rhs: Self) {
+        for (item, count) in rhs.map {
+            *self.map.entry(item).or_insert_with(N::zero) -= count;
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: PartialOrd + Zero,
+{
+    /// Consume this counter, keeping only the items with a positive count.
+    ///
+    /// This is Python's unary `+c`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let c: Counter<_, i32> = Counter::from([('a', 2), ('b', 0), ('c', -1)]);
+    /// assert_eq!(c.positive(), Counter::from([('a', 2)]));
+    /// ```
+    pub fn positive(mut self) -> Self {
+        self.retain_count(|count| *count > N::zero());
+        self
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Signed + Clone,
+{
+    /// Consume this counter, keeping only the items with a negative count, and negating those
+    /// counts so that they become positive.
+    ///
+    /// This is Python's unary `-c`. To negate every count without dropping any items, use the
+    /// `-` operator.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let c: Counter<_, i32> = Counter::from([('a', 2), ('b', 0), ('c', -1)]);
+    /// assert_eq!(c.negative(), Counter::from([('c', 1)]));
+    /// ```
+    pub fn negative(mut self) -> Self {
+        self.retain_count(Signed::is_negative);
+        -self
+    }
+}
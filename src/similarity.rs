@@ -0,0 +1,123 @@
+use crate::frequencies::count_to_f64;
+use crate::Counter;
+
+use crate::num::ToPrimitive;
+
+use std::hash::{BuildHasher, Hash};
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// Calls `f` with the counts of each item in either counter, as `f64`s, treating items
+    /// missing from one counter as having a count of zero there.
+    fn for_each_pair(&self, other: &Self, mut f: impl FnMut(f64, f64)) {
+        for (item, count) in &self.map {
+            let other_count = other.map.get(item).map_or(0.0, count_to_f64);
+            f(count_to_f64(count), other_count);
+        }
+        for (item, other_count) in &other.map {
+            if !self.map.contains_key(item) {
+                f(0.0, count_to_f64(other_count));
+            }
+        }
+    }
+
+    /// Returns the Jaccard index of the two multisets: the sum of the smaller count of each
+    /// item, divided by the sum of the larger.
+    ///
+    /// This is `1.0` for counters with the same counts, and `0.0` for counters with no items in
+    /// common, or which are both empty. Counts are assumed to be non-negative.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = "aab".chars().collect::<Counter<_>>();
+    /// let b = "abc".chars().collect::<Counter<_>>();
+    /// // min: a 1, b 1, c 0; max: a 2, b 1, c 1
+    /// assert_eq!(a.jaccard_index(&b), 0.5);
+    /// ```
+    pub fn jaccard_index(&self, other: &Self) -> f64 {
+        let (mut intersection, mut union) = (0.0, 0.0);
+        self.for_each_pair(other, |a, b| {
+            intersection += a.min(b);
+            union += a.max(b);
+        });
+        if union > 0.0 {
+            intersection / union
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the cosine of the angle between the two counters, viewed as vectors of counts
+    /// indexed by item.
+    ///
+    /// Unlike the other measures, this depends only on the proportions of the counts, not on
+    /// their totals. It is `0.0` if either counter is empty. Counts are assumed to be
+    /// non-negative, so the result is between `0.0` and `1.0`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let short = "the cat".split_whitespace().collect::<Counter<_>>();
+    /// let long = "the cat the cat".split_whitespace().collect::<Counter<_>>();
+    /// assert!((short.cosine_similarity(&long) - 1.0).abs() < 1e-12);
+    /// ```
+    pub fn cosine_similarity(&self, other: &Self) -> f64 {
+        let (mut dot, mut norm_self, mut norm_other) = (0.0, 0.0, 0.0);
+        self.for_each_pair(other, |a, b| {
+            dot += a * b;
+            norm_self += a * a;
+            norm_other += b * b;
+        });
+        let norms = norm_self.sqrt() * norm_other.sqrt();
+        if norms > 0.0 {
+            dot / norms
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the overlap coefficient of the two multisets: the sum of the smaller count of
+    /// each item, divided by the smaller of the two totals.
+    ///
+    /// This is `1.0` when one counter is contained in the other, and `0.0` if either counter is
+    /// empty. Counts are assumed to be non-negative.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let part = "ab".chars().collect::<Counter<_>>();
+    /// let whole = "aabbcc".chars().collect::<Counter<_>>();
+    /// assert_eq!(part.overlap_coefficient(&whole), 1.0);
+    /// ```
+    pub fn overlap_coefficient(&self, other: &Self) -> f64 {
+        let (mut intersection, mut total_self, mut total_other) = (0.0, 0.0, 0.0);
+        self.for_each_pair(other, |a, b| {
+            intersection += a.min(b);
+            total_self += a;
+            total_other += b;
+        });
+        let smaller = total_self.min(total_other);
+        if smaller > 0.0 {
+            intersection / smaller
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the L1, or Manhattan, distance between the two counters: the sum of the absolute
+    /// differences between the counts of each item.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let a = "aab".chars().collect::<Counter<_>>();
+    /// let b = "abcc".chars().collect::<Counter<_>>();
+    /// assert_eq!(a.l1_distance(&b), 3.0);
+    /// ```
+    pub fn l1_distance(&self, other: &Self) -> f64 {
+        let mut distance = 0.0;
+        self.for_each_pair(other, |a, b| distance += (a - b).abs());
+        distance
+    }
+}
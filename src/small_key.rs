@@ -0,0 +1,397 @@
+use crate::{Counter, Error};
+
+use crate::num::{CheckedAdd, Zero};
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+}
+
+/// Key types whose domain is small enough to be counted into a dense array.
+///
+/// Counting these keys into an array indexed by the key, and only then inserting the totals
+/// into the counter, avoids hashing every element. See [`Counter::update_slice`].
+///
+/// This trait is sealed; it is implemented for [`u8`] and [`u16`].
+pub trait SmallKey: Copy + Hash + Eq + private::Sealed {
+    #[doc(hidden)]
+    fn histogram(keys: &[Self]) -> Vec<usize>;
+
+    #[doc(hidden)]
+    fn from_index(index: usize) -> Self;
+}
+
+impl SmallKey for u8 {
+    fn histogram(keys: &[u8]) -> Vec<usize> {
+        #[cfg(counter_simd)]
+        {
+            simd::histogram::<u8, 16>(keys)
+        }
+        #[cfg(not(counter_simd))]
+        {
+            striped_histogram(keys)
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // `index` comes from the histogram of `u8`s
+    fn from_index(index: usize) -> Self {
+        index as u8
+    }
+}
+
+impl SmallKey for u16 {
+    fn histogram(keys: &[u16]) -> Vec<usize> {
+        #[cfg(counter_simd)]
+        {
+            simd::histogram::<u16, 8>(keys)
+        }
+        #[cfg(not(counter_simd))]
+        {
+            striped_histogram(keys)
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)] // `index` comes from the histogram of `u16`s
+    fn from_index(index: usize) -> Self {
+        index as u16
+    }
+}
+
+/// The number of distinct values of `K`.
+fn domain<K>() -> usize {
+    1 << (8 * core::mem::size_of::<K>())
+}
+
+/// The length from which a slice of `K` is counted into a dense array.
+///
+/// Shorter slices are counted by hashing each key, since zeroing and scanning an array with a
+/// slot for every possible key would cost more than the hashing it saves.
+fn dense_threshold<K>() -> usize {
+    domain::<K>() / 4
+}
+
+/// The distinct keys of the slice with their counts, in arbitrary order.
+fn key_counts<K: SmallKey>(keys: &[K]) -> Vec<(K, usize)> {
+    if keys.len() < dense_threshold::<K>() {
+        let mut counts = Counter::<K>::new();
+        counts.update(keys.iter().copied());
+        return counts.map.into_iter().collect();
+    }
+    K::histogram(keys)
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, count)| count > 0)
+        .map(|(index, count)| (K::from_index(index), count))
+        .collect()
+}
+
+/// Scalar fallback histogram.
+///
+/// Consecutive elements are counted into separate tables which are summed at the end. Runs of
+/// equal keys, which are very common in real data, would otherwise serialize on the
+/// store-to-load dependency of incrementing the same slot over and over. Slices shorter than
+/// the tables use fewer of them, so the tables never outgrow the slice by much.
+#[cfg_attr(counter_simd, allow(dead_code))]
+fn striped_histogram<K>(keys: &[K]) -> Vec<usize>
+where
+    K: Copy + Into<usize>,
+{
+    const STRIPES: usize = 4;
+
+    let domain = domain::<K>();
+    let stripes = (keys.len() / domain).clamp(1, STRIPES);
+    let mut tables = vec![0_usize; stripes * domain];
+
+    let mut chunks = keys.chunks_exact(stripes);
+    for chunk in &mut chunks {
+        for (stripe, &key) in chunk.iter().enumerate() {
+            tables[stripe * domain + key.into()] += 1;
+        }
+    }
+    for &key in chunks.remainder() {
+        tables[key.into()] += 1;
+    }
+
+    let (totals, others) = tables.split_at_mut(domain);
+    for stripe in others.chunks_exact(domain) {
+        for (total, count) in totals.iter_mut().zip(stripe) {
+            *total += count;
+        }
+    }
+    tables.truncate(domain);
+    tables
+}
+
+/// Vectorized histogram using `std::simd`; requires a nightly compiler and
+/// `RUSTFLAGS="--cfg counter_simd"`.
+///
+/// Each lane of the vector owns a private table, so a gather/increment/scatter of a full vector
+/// of keys never has two lanes writing to the same slot.
+#[cfg(counter_simd)]
+mod simd {
+    use super::domain;
+
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use core::simd::num::SimdUint;
+    use core::simd::{Simd, SimdElement};
+
+    pub(super) fn histogram<K, const LANES: usize>(keys: &[K]) -> Vec<usize>
+    where
+        K: SimdElement + Into<usize>,
+        Simd<K, LANES>: SimdUint<Cast<usize> = Simd<usize, LANES>>,
+    {
+        let domain = domain::<K>();
+        let mut tables = vec![0_u32; LANES * domain];
+        let mut totals = vec![0_usize; domain];
+
+        let offsets = Simd::<usize, LANES>::from_array(core::array::from_fn(|lane| lane * domain));
+        let one = Simd::<u32, LANES>::splat(1);
+
+        // Each lane sees at most one key per vector, so flushing the `u32` tables every
+        // `u32::MAX` vectors keeps them from overflowing.
+        for block in keys.chunks(LANES.saturating_mul(u32::MAX as usize)) {
+            let mut vectors = block.chunks_exact(LANES);
+            for vector in &mut vectors {
+                let indices = Simd::<K, LANES>::from_slice(vector).cast::<usize>() + offsets;
+                let counts = Simd::gather_or_default(&tables, indices) + one;
+                counts.scatter(&mut tables, indices);
+            }
+            for lane in tables.chunks_exact_mut(domain) {
+                for (total, count) in totals.iter_mut().zip(lane.iter_mut()) {
+                    *total += *count as usize;
+                    *count = 0;
+                }
+            }
+            for key in vectors.remainder() {
+                totals[(*key).into()] += 1;
+            }
+        }
+
+        totals
+    }
+}
+
+impl<K, N> Counter<K, N>
+where
+    K: SmallKey,
+    N: CheckedAdd + Zero + TryFrom<usize>,
+{
+    /// Create a new `Counter` from a slice of small keys.
+    ///
+    /// This produces the same counter as collecting the slice, but counts into a dense array
+    /// first. See [`update_slice`](Counter::update_slice).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<u8>::from_slice(b"abracadabra");
+    /// assert_eq!(counter[&b'a'], 5);
+    /// assert_eq!(counter, b"abracadabra".iter().copied().collect());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count of some key does not fit in `N`.
+    pub fn from_slice(keys: &[K]) -> Self {
+        let mut counter = Counter::new();
+        counter.update_slice(keys);
+        counter
+    }
+}
+
+impl<K, N, S> Counter<K, N, S>
+where
+    K: SmallKey,
+    N: CheckedAdd + Zero + TryFrom<usize>,
+    S: BuildHasher,
+{
+    /// Add the counts of the keys in the given slice to this counter.
+    ///
+    /// Equivalent to [`update`](Counter::update), but the slice is first counted into an array
+    /// indexed by key, so that only distinct keys are hashed. For large slices of bytes this is
+    /// many times faster than the generic path. Short slices, for which the array would not pay
+    /// for itself, are counted by hashing each key as usual.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = Counter::<u16>::new();
+    /// counter.update_slice(&[1, 2, 2, 3, 3, 3]);
+    /// counter.update_slice(&[3]);
+    /// assert_eq!(counter[&3], 4);
+    /// assert_eq!(counter.len(), 3);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new count of some key does not fit in `N`. Use
+    /// [`try_update_slice`](Counter::try_update_slice) to get an error instead.
+    pub fn update_slice(&mut self, keys: &[K]) {
+        self.try_update_slice(keys)
+            .expect("the count of some key does not fit in the counter type");
+    }
+
+    /// Add the counts of the keys in the given slice to this counter, as
+    /// [`update_slice`](Counter::update_slice) does, unless some count would overflow `N`.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Error};
+    /// let mut counter = Counter::<u8, u8>::new();
+    /// counter.try_update_slice(&[7; 200]).unwrap();
+    ///
+    /// let mut keys = vec![1];
+    /// keys.extend([7; 100]);
+    /// assert!(matches!(counter.try_update_slice(&keys), Err(Error::Overflow)));
+    /// assert_eq!(counter[&7], 200);
+    /// assert_eq!(counter.get(&1), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if the new count of some key does not fit in `N`. The counter
+    /// is left unchanged in that case.
+    pub fn try_update_slice(&mut self, keys: &[K]) -> Result<(), Error> {
+        self.try_add_counts(key_counts(keys))
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: CheckedAdd + Zero + TryFrom<usize>,
+    S: BuildHasher,
+{
+    /// Add the counts of distinct items to this counter, unless some count would overflow `N`,
+    /// in which case the counter is left unchanged.
+    fn try_add_counts<I>(&mut self, counts: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (T, usize)>,
+    {
+        let counts = counts
+            .into_iter()
+            .map(|(item, count)| {
+                let count = N::try_from(count).map_err(|_| Error::Overflow)?;
+                let count = match self.map.get(&item) {
+                    Some(existing) => existing.checked_add(&count).ok_or(Error::Overflow)?,
+                    None => count,
+                };
+                Ok((item, count))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.map.extend(counts);
+        Ok(())
+    }
+}
+
+impl<N> Counter<u8, N>
+where
+    N: CheckedAdd + Zero + TryFrom<usize>,
+{
+    /// Create a new `Counter` of the bytes of a byte slice.
+    ///
+    /// This is [`from_slice`](Counter::from_slice) for bytes: the bytes are counted into a
+    /// 256-entry array, and only the distinct bytes are inserted into the counter.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<u8>::from_bytes(b"hello");
+    /// assert_eq!(counter[&b'l'], 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count of some byte does not fit in `N`.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::from_slice(bytes)
+    }
+}
+
+impl<N> Counter<char, N>
+where
+    N: CheckedAdd + Zero + TryFrom<usize>,
+{
+    /// Create a new `Counter` of the characters of a string.
+    ///
+    /// This produces the same counter as collecting `text.chars()`, but much faster for
+    /// mostly-ASCII text. See [`update_chars`](Counter::update_chars).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = Counter::<char>::from_char_frequencies("naïve café");
+    /// assert_eq!(counter[&'a'], 2);
+    /// assert_eq!(counter[&'é'], 1);
+    /// assert_eq!(counter, "naïve café".chars().collect());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the count of some character does not fit in `N`.
+    pub fn from_char_frequencies(text: &str) -> Self {
+        let mut counter = Counter::new();
+        counter.update_chars(text);
+        counter
+    }
+}
+
+impl<N, S> Counter<char, N, S>
+where
+    N: CheckedAdd + Zero + TryFrom<usize>,
+    S: BuildHasher,
+{
+    /// Add the counts of the characters of a string to this counter.
+    ///
+    /// The bytes of the string are first counted into an array, which gives the counts of the
+    /// ASCII characters without decoding or hashing them. Only if the string contains other
+    /// characters is it decoded, to count those individually. Short strings are simply decoded and
+    /// counted character by character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new count of some character does not fit in `N`. Use
+    /// [`try_update_chars`](Counter::try_update_chars) to get an error instead.
+    pub fn update_chars(&mut self, text: &str) {
+        self.try_update_chars(text)
+            .expect("the count of some character does not fit in the counter type");
+    }
+
+    /// Add the counts of the characters of a string to this counter, as
+    /// [`update_chars`](Counter::update_chars) does, unless some count would overflow `N`.
+    ///
+    /// ```rust
+    /// # use counter::{Counter, Error};
+    /// let mut counter = Counter::<char, u8>::new();
+    /// counter.try_update_chars(&"é".repeat(200)).unwrap();
+    ///
+    /// let text = format!("a{}", "é".repeat(100));
+    /// assert!(matches!(counter.try_update_chars(&text), Err(Error::Overflow)));
+    /// assert_eq!(counter[&'é'], 200);
+    /// assert_eq!(counter.get(&'a'), None);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Overflow`] if the new count of some character does not fit in `N`. The
+    /// counter is left unchanged in that case.
+    pub fn try_update_chars(&mut self, text: &str) -> Result<(), Error> {
+        if text.len() < dense_threshold::<u8>() {
+            let mut counts = Counter::<char>::new();
+            counts.update(text.chars());
+            return self.try_add_counts(counts.map);
+        }
+        let histogram = u8::histogram(text.as_bytes());
+        let mut others = Counter::<char>::new();
+        if histogram[0x80..].iter().any(|&count| count > 0) {
+            others.update(text.chars().filter(|c| !c.is_ascii()));
+        }
+        let ascii = (0..=0x7f_u8)
+            .zip(histogram)
+            .filter(|&(_, count)| count > 0)
+            .map(|(byte, count)| (char::from(byte), count));
+        self.try_add_counts(ascii.chain(others.map))
+    }
+}
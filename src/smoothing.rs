@@ -0,0 +1,193 @@
+//! Smoothed probability estimates, which reserve some probability for items a counter has not
+//! seen.
+//!
+//! The relative [`frequencies`](Counter::frequencies) of a sample assign no probability to items
+//! which happen to be missing from it, which is fatal for tasks such as language modelling.
+//! [`Counter::laplace_smoothed`] adds a pseudocount to every item, and
+//! [`Counter::simple_good_turing`] estimates the probability of unseen items from the number of
+//! items seen exactly once.
+//!
+//! ```rust
+//! # use counter::Counter;
+//! let words = "the cat saw the dog and the dog saw a bird"
+//!     .split_whitespace()
+//!     .collect::<Counter<_>>();
+//!
+//! let estimate = words.simple_good_turing();
+//! // Four of the eleven words were seen exactly once.
+//! assert_eq!(estimate.unseen, 4.0 / 11.0);
+//! assert!(estimate.probabilities[&"the"] > estimate.probabilities[&"dog"]);
+//! ```
+
+use crate::collections::HashMap;
+use crate::frequencies::count_to_f64;
+use crate::Counter;
+
+use crate::num::ToPrimitive;
+
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hash};
+
+/// The result of [`Counter::simple_good_turing`].
+#[derive(Clone, Debug)]
+pub struct GoodTuringEstimate<T, S> {
+    /// The smoothed probability of each item seen at least once.
+    pub probabilities: HashMap<T, f64, S>,
+    /// The total probability of all the items which have not been seen.
+    pub unseen: f64,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: ToPrimitive,
+    S: BuildHasher + Clone,
+{
+    /// Returns a map from each item to its add-`k` smoothed probability: its count plus `k`,
+    /// divided by the sum of all counts plus `k` for each item.
+    ///
+    /// `k = 1.0` is Laplace smoothing. The vocabulary is the set of items in the counter, so to
+    /// reserve probability for items known to exist but never observed, add them with a count
+    /// of zero first.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let mut counter = "aaab".chars().collect::<Counter<_>>();
+    /// counter[&'c'] = 0;
+    ///
+    /// let smoothed = counter.laplace_smoothed(1.0);
+    /// assert_eq!(smoothed[&'a'], 4.0 / 7.0);
+    /// assert_eq!(smoothed[&'b'], 2.0 / 7.0);
+    /// assert_eq!(smoothed[&'c'], 1.0 / 7.0);
+    /// ```
+    pub fn laplace_smoothed(&self, k: f64) -> HashMap<T, f64, S> {
+        let total = self.map.values().map(count_to_f64).sum::<f64>() + k * self.map.len() as f64;
+        let mut probabilities =
+            HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        probabilities.extend(
+            self.map
+                .iter()
+                .map(|(item, count)| (item.clone(), (count_to_f64(count) + k) / total)),
+        );
+        probabilities
+    }
+
+    /// Returns the Simple Good–Turing estimate of the probability of each item, and of all the
+    /// unseen items together.
+    ///
+    /// This is the method of Gale and Sampson (1995). The probability of the unseen items is
+    /// the proportion of the sample made up of items seen exactly once. The probabilities of
+    /// the seen items are discounted to make room for it, using the Turing estimator for small
+    /// counts and a log-linear fit of the counts of counts for larger ones, so that items seen
+    /// equally often are equally probable.
+    ///
+    /// Counts must be whole numbers; items with a count of zero or less are treated as unseen,
+    /// and left out. If no item has been seen, all the probability is unseen. See the
+    /// [`smoothing`](crate::smoothing) module.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter: Counter<_> = Counter::from([('a', 1), ('b', 1), ('c', 2), ('d', 3), ('e', 5)]);
+    /// let estimate = counter.simple_good_turing();
+    /// assert_eq!(estimate.unseen, 2.0 / 12.0);
+    ///
+    /// let seen: f64 = estimate.probabilities.values().sum();
+    /// assert!((seen + estimate.unseen - 1.0).abs() < 1e-12);
+    /// ```
+    pub fn simple_good_turing(&self) -> GoodTuringEstimate<T, S> {
+        let mut count_of_counts = BTreeMap::new();
+        for count in self.map.values() {
+            if let Some(count @ 1..) = count.to_usize() {
+                *count_of_counts.entry(count).or_insert(0_usize) += 1;
+            }
+        }
+        let adjusted = adjusted_counts(&count_of_counts);
+
+        let sample_size: f64 = count_of_counts
+            .iter()
+            .map(|(&count, &items)| count as f64 * items as f64)
+            .sum();
+        let unseen = match count_of_counts.get(&1) {
+            Some(&singletons) => singletons as f64 / sample_size,
+            None if sample_size > 0.0 => 0.0,
+            None => 1.0,
+        };
+        let adjusted_size: f64 = count_of_counts
+            .iter()
+            .map(|(count, &items)| adjusted[count] * items as f64)
+            .sum();
+
+        let mut probabilities =
+            HashMap::with_capacity_and_hasher(self.map.len(), self.map.hasher().clone());
+        for (item, count) in &self.map {
+            if let Some(count @ 1..) = count.to_usize() {
+                let probability = (1.0 - unseen) * adjusted[&count] / adjusted_size;
+                probabilities.insert(item.clone(), probability);
+            }
+        }
+        GoodTuringEstimate {
+            probabilities,
+            unseen,
+        }
+    }
+}
+
+/// Returns the Simple Good–Turing adjusted count `r*` for each count `r`, given the number of
+/// items `n_r` seen exactly `r` times.
+fn adjusted_counts(count_of_counts: &BTreeMap<usize, usize>) -> BTreeMap<usize, f64> {
+    if count_of_counts.len() < 2 {
+        // There is no slope to fit, and nothing to discount relative to.
+        return count_of_counts
+            .keys()
+            .map(|&count| (count, count as f64))
+            .collect();
+    }
+
+    // Average each n_r over the gap to its neighbouring counts, then fit
+    // log(Z_r) = a + b * log(r) by least squares.
+    let counts = count_of_counts.keys().copied().collect::<Vec<_>>();
+    let points = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| {
+            let previous = if i == 0 { 0 } else { counts[i - 1] };
+            let next = counts.get(i + 1).copied().unwrap_or(2 * count - previous);
+            let z = count_of_counts[&count] as f64 / (0.5 * (next - previous) as f64);
+            ((count as f64).ln(), z.ln())
+        })
+        .collect::<Vec<_>>();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let covariance: f64 = points
+        .iter()
+        .map(|&(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let variance: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    let slope = covariance / variance;
+
+    let mut use_fit = false;
+    let mut adjusted = BTreeMap::new();
+    for (&count, &items) in count_of_counts {
+        let r = count as f64;
+        // (r + 1) * S(r + 1) / S(r), where S is the fitted n_r.
+        let fitted = r * (1.0 + 1.0 / r).powf(slope + 1.0);
+        if !use_fit {
+            if let Some(&next_items) = count_of_counts.get(&(count + 1)) {
+                let (n_r, n_next) = (items as f64, next_items as f64);
+                let turing = (r + 1.0) * n_next / n_r;
+                let spread =
+                    1.96 * ((r + 1.0).powi(2) * n_next / n_r.powi(2) * (1.0 + n_next / n_r)).sqrt();
+                if (turing - fitted).abs() > spread {
+                    adjusted.insert(count, turing);
+                    continue;
+                }
+            }
+            // Once the estimates agree, or the Turing estimator is undefined, the fit is used
+            // for every larger count.
+            use_fit = true;
+        }
+        adjusted.insert(count, fitted);
+    }
+    adjusted
+}
@@ -0,0 +1,573 @@
+//! Exact counting of more distinct items than fit in memory, by spilling the least common
+//! items to sorted runs on disk.
+//!
+//! A [`SpillingCounter`] counts into an ordinary [`Counter`] until it holds a configured number
+//! of distinct items. It then writes the less common half of them, sorted by item, to a run
+//! file, and carries on counting. The common items stay in memory, where they are likely to be
+//! counted again. At the end, [`into_sorted_iter`](SpillingCounter::into_sorted_iter) merges
+//! the runs and the items still in memory into the exact count of each item, in item order,
+//! without ever holding all of them in memory at once.
+//!
+//! Runs are merged as they accumulate, [`MERGE_FAN_IN`] at a time, so however many items are
+//! spilled, only a few runs exist at once, and no more than [`MERGE_FAN_IN`] files are ever
+//! open. Each run keeps a sparse index of its items in memory, so looking up the count of one
+//! item reads only a short stretch of each run.
+//!
+//! Items and counts are written to the runs by a [`SpillCodec`]. [`TextCodec`] writes anything
+//! which can be formatted and parsed as text; implement the trait to use a binary format.
+//!
+//! ```rust
+//! # use counter::spill::SpillingCounter;
+//! let mut counter = SpillingCounter::<u32>::new(std::env::temp_dir(), 1_000);
+//! counter.update((0..10_000).map(|n| n % 2_500)).unwrap();
+//! assert!(counter.runs() > 0);
+//! assert_eq!(counter.get(&42).unwrap(), 4);
+//!
+//! let counts = counter.into_sorted_iter().unwrap();
+//! let counts = counts.collect::<Result<Vec<_>, _>>().unwrap();
+//! assert_eq!(counts.len(), 2_500);
+//! assert!(counts.iter().all(|&(_, count)| count == 4));
+//! ```
+//!
+//! The run files are deleted when the counter, or the iterator over its counts, is dropped.
+
+use crate::{Counter, Error};
+
+use crate::num::{One, Zero};
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt::{self, Display};
+use std::fs::{self, File, OpenOptions};
+use std::hash::Hash;
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::mem;
+use std::ops::AddAssign;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::vec;
+
+/// The most runs merged at once, and so the most run files a [`SpillingCounter`] ever has open.
+///
+/// Once this many runs of similar size have been spilled, they are merged into one.
+pub const MERGE_FAN_IN: usize = 16;
+
+/// The least `max_in_memory` a [`SpillingCounter`] accepts.
+///
+/// Each spill writes about half the items in memory, so a smaller limit would write runs of a
+/// handful of items, and spend its time creating and merging files.
+pub const MIN_IN_MEMORY: usize = 32;
+
+/// How many items of a run are read at most to find one item: the interval between the items
+/// recorded in the index of each run.
+const INDEX_INTERVAL: usize = 64;
+
+/// How a [`SpillingCounter`] writes items and their counts to its runs, and reads them back.
+pub trait SpillCodec<T, N> {
+    /// Write one item and its count.
+    fn encode<W: Write>(&self, writer: &mut W, item: &T, count: &N) -> io::Result<()>;
+
+    /// Read the next item and its count, or `None` at the end of the run.
+    fn decode<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<(T, N)>>;
+}
+
+impl<T, N, C> SpillCodec<T, N> for &C
+where
+    C: SpillCodec<T, N>,
+{
+    fn encode<W: Write>(&self, writer: &mut W, item: &T, count: &N) -> io::Result<()> {
+        (**self).encode(writer, item, count)
+    }
+
+    fn decode<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<(T, N)>> {
+        (**self).decode(reader)
+    }
+}
+
+/// A [`SpillCodec`] for items and counts which can be formatted and parsed as text.
+///
+/// Each item is written on its own line as its count, the length of the item's text and the
+/// text itself, separated by tabs. The length makes any text safe to write, including text
+/// containing tabs or line breaks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TextCodec;
+
+impl TextCodec {
+    fn read_field<R: BufRead>(reader: &mut R, buffer: &mut Vec<u8>) -> io::Result<bool> {
+        buffer.clear();
+        reader.read_until(b'\t', buffer)?;
+        if buffer.is_empty() {
+            return Ok(false);
+        }
+        if buffer.pop() != Some(b'\t') {
+            return Err(invalid_data("truncated spill record"));
+        }
+        Ok(true)
+    }
+
+    fn parse<V: FromStr>(field: &[u8]) -> io::Result<V> {
+        std::str::from_utf8(field)
+            .ok()
+            .and_then(|field| field.parse().ok())
+            .ok_or_else(|| invalid_data("malformed spill record"))
+    }
+}
+
+impl<T, N> SpillCodec<T, N> for TextCodec
+where
+    T: Display + FromStr,
+    N: Display + FromStr,
+{
+    fn encode<W: Write>(&self, writer: &mut W, item: &T, count: &N) -> io::Result<()> {
+        let item = item.to_string();
+        writeln!(writer, "{count}\t{}\t{item}", item.len())
+    }
+
+    fn decode<R: BufRead>(&self, reader: &mut R) -> io::Result<Option<(T, N)>> {
+        let mut buffer = Vec::new();
+        if !Self::read_field(reader, &mut buffer)? {
+            return Ok(None);
+        }
+        let count = Self::parse(&buffer)?;
+        if !Self::read_field(reader, &mut buffer)? {
+            return Err(invalid_data("truncated spill record"));
+        }
+        let len: usize = Self::parse(&buffer)?;
+        buffer.resize(len + 1, 0);
+        reader.read_exact(&mut buffer)?;
+        if buffer.pop() != Some(b'\n') {
+            return Err(invalid_data("malformed spill record"));
+        }
+        Ok(Some((Self::parse(&buffer)?, count)))
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// A run file, deleted when dropped.
+struct Run<T> {
+    path: PathBuf,
+    /// How many times the items of this run have been merged.
+    level: u32,
+    /// The number of items in the run.
+    len: usize,
+    /// Every [`INDEX_INTERVAL`]th item of the run, with the offset of its record.
+    index: Vec<(T, u64)>,
+}
+
+impl<T> Run<T> {
+    /// Write `pairs`, which must be in strictly increasing order of item, to a new run in `dir`.
+    fn write<N, C, I>(dir: &Path, codec: &C, level: u32, pairs: I) -> Result<Self, Error>
+    where
+        T: Clone,
+        C: SpillCodec<T, N>,
+        I: IntoIterator<Item = Result<(T, N), Error>>,
+    {
+        static NEXT_RUN: AtomicUsize = AtomicUsize::new(0);
+        let run = NEXT_RUN.fetch_add(1, AtomicOrdering::Relaxed);
+        let path = dir.join(format!("counter-spill-{}-{run}.run", process::id()));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        // From here on, the file is deleted if writing it fails.
+        let mut run = Run {
+            path,
+            level,
+            len: 0,
+            index: Vec::new(),
+        };
+        let mut writer = BufWriter::new(file);
+        let mut offset = 0;
+        let mut record = Vec::new();
+        for pair in pairs {
+            let (item, count) = pair?;
+            if run.len % INDEX_INTERVAL == 0 {
+                run.index.push((item.clone(), offset));
+            }
+            record.clear();
+            codec.encode(&mut record, &item, &count)?;
+            writer.write_all(&record)?;
+            offset += record.len() as u64;
+            run.len += 1;
+        }
+        writer
+            .into_inner()
+            .map_err(io::IntoInnerError::into_error)?;
+        Ok(run)
+    }
+
+    fn open(&self) -> io::Result<BufReader<File>> {
+        File::open(&self.path).map(BufReader::new)
+    }
+
+    /// Returns the count of `item` in this run, reading at most [`INDEX_INTERVAL`] records.
+    fn get<N, C>(&self, codec: &C, item: &T) -> io::Result<Option<N>>
+    where
+        T: Ord,
+        C: SpillCodec<T, N>,
+    {
+        let start = match self.index.partition_point(|(first, _)| first <= item) {
+            0 => return Ok(None),
+            after => self.index[after - 1].1,
+        };
+        let mut reader = self.open()?;
+        reader.seek(SeekFrom::Start(start))?;
+        while let Some((spilled, count)) = codec.decode(&mut reader)? {
+            match spilled.cmp(item) {
+                Ordering::Less => continue,
+                Ordering::Equal => return Ok(Some(count)),
+                Ordering::Greater => break,
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<T> fmt::Debug for Run<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Run")
+            .field("path", &self.path)
+            .field("level", &self.level)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for Run<T> {
+    fn drop(&mut self) {
+        // There is nothing useful to do if the file has already gone.
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A counter which spills its least common items to disk once it holds too many distinct
+/// items. See the [module documentation](self) for an example.
+#[derive(Debug)]
+pub struct SpillingCounter<T: Hash + Eq, N = usize, C = TextCodec> {
+    counter: Counter<T, N>,
+    codec: C,
+    dir: PathBuf,
+    max_in_memory: usize,
+    runs: Vec<Run<T>>,
+}
+
+impl<T, N> SpillingCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `SpillingCounter`, which writes its runs as text to files in `dir`
+    /// once it holds more than `max_in_memory` distinct items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_in_memory` is less than [`MIN_IN_MEMORY`].
+    pub fn new(dir: impl Into<PathBuf>, max_in_memory: usize) -> Self {
+        Self::with_codec(dir, max_in_memory, TextCodec)
+    }
+}
+
+impl<T, N, C> SpillingCounter<T, N, C>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `SpillingCounter`, which writes its runs with `codec` to files in
+    /// `dir` once it holds more than `max_in_memory` distinct items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_in_memory` is less than [`MIN_IN_MEMORY`].
+    pub fn with_codec(dir: impl Into<PathBuf>, max_in_memory: usize, codec: C) -> Self {
+        assert!(
+            max_in_memory >= MIN_IN_MEMORY,
+            "a spilling counter must hold at least {MIN_IN_MEMORY} items in memory"
+        );
+        SpillingCounter {
+            counter: Counter::new(),
+            codec,
+            dir: dir.into(),
+            max_in_memory,
+            runs: Vec::new(),
+        }
+    }
+}
+
+impl<T, N, C> SpillingCounter<T, N, C>
+where
+    T: Hash + Eq,
+{
+    /// Returns the items counted since they were last spilled.
+    pub fn in_memory(&self) -> &Counter<T, N> {
+        &self.counter
+    }
+
+    /// Returns the number of runs on disk.
+    ///
+    /// This grows with each spill until [`MERGE_FAN_IN`] runs are merged into one, so it stays
+    /// small however many items are spilled.
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+}
+
+impl<T, N, C> SpillingCounter<T, N, C>
+where
+    T: Hash + Eq + Ord + Clone,
+    N: Ord + AddAssign + Zero,
+    C: SpillCodec<T, N>,
+{
+    /// Add `count` to the count of `item`, spilling to disk if that makes the counter hold
+    /// too many distinct items.
+    pub fn add(&mut self, item: T, count: N) -> Result<(), Error> {
+        *self.counter.map.entry(item).or_insert_with(N::zero) += count;
+        if self.counter.len() > self.max_in_memory {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    /// Add one to the count of each element of the given iterable, spilling to disk as
+    /// needed.
+    pub fn update<I>(&mut self, iterable: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = T>,
+        N: One,
+    {
+        for item in iterable {
+            self.add(item, N::one())?;
+        }
+        Ok(())
+    }
+
+    /// Write the less common half of the items in memory to a new run now.
+    ///
+    /// This happens automatically when the counter holds too many items; call it directly to
+    /// free memory early.
+    pub fn spill(&mut self) -> Result<(), Error> {
+        trace_span!(
+            "spill",
+            len = self.counter.map.len(),
+            runs = self.runs.len()
+        );
+        let mut items = self.counter.map.drain().collect::<Vec<_>>();
+        let keep = self.max_in_memory / 2;
+        let spilled = items.len().saturating_sub(keep);
+        if spilled < items.len() {
+            items.select_nth_unstable_by(spilled, |(_, a), (_, b)| a.cmp(b));
+        }
+        self.counter.map.extend(items.drain(spilled..));
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        trace_event!(spilled = items.len(), "spilling items to a new run");
+        items.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        let run = Run::write(&self.dir, &self.codec, 0, items.into_iter().map(Ok))?;
+        self.runs.push(run);
+
+        // Merge each level once it is full, which may fill the level above.
+        let mut level = 0;
+        while self.runs.iter().filter(|run| run.level == level).count() >= MERGE_FAN_IN {
+            let (merging, kept) = mem::take(&mut self.runs)
+                .into_iter()
+                .partition(|run| run.level == level);
+            self.runs = kept;
+            self.merge(merging, level + 1)?;
+            level += 1;
+        }
+        Ok(())
+    }
+
+    /// Merge `runs` into a single run at `level`. If that fails, `runs` are kept as they were.
+    fn merge(&mut self, runs: Vec<Run<T>>, level: u32) -> Result<(), Error> {
+        trace_span!("merge_runs", runs = runs.len(), level);
+        let merged = SortedCounts::new(&self.codec, Vec::new(), &runs)
+            .and_then(|counts| Run::write(&self.dir, &self.codec, level, counts));
+        match merged {
+            Ok(run) => self.runs.push(run),
+            Err(err) => {
+                self.runs.extend(runs);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the count of `item`, or zero if it has not been counted.
+    ///
+    /// Each run is read from disk around where the item would be, so this is much slower than
+    /// looking up a count in a [`Counter`].
+    pub fn get(&self, item: &T) -> Result<N, Error>
+    where
+        N: Clone,
+    {
+        let mut total = self.counter.get(item).cloned().unwrap_or_else(N::zero);
+        for run in &self.runs {
+            if let Some(count) = run.get(&self.codec, item)? {
+                total += count;
+            }
+        }
+        Ok(total)
+    }
+
+    /// Consumes the `SpillingCounter` and returns an iterator over every item and its total
+    /// count, in ascending order of item.
+    ///
+    /// The smallest runs are merged first until at most [`MERGE_FAN_IN`] remain. The iterator
+    /// then reads those as it goes, so only one item per run is held in memory.
+    pub fn into_sorted_iter(mut self) -> Result<SortedCounts<T, N, C>, Error> {
+        trace_span!(
+            "into_sorted_iter",
+            len = self.counter.map.len(),
+            runs = self.runs.len()
+        );
+        while self.runs.len() > MERGE_FAN_IN {
+            let merging = (self.runs.len() - MERGE_FAN_IN + 1).min(MERGE_FAN_IN);
+            self.runs.sort_unstable_by_key(|run| Reverse(run.len));
+            let runs = self.runs.split_off(self.runs.len() - merging);
+            let level = runs.iter().map(|run| run.level).max().unwrap_or(0) + 1;
+            self.merge(runs, level)?;
+        }
+        let mut in_memory = self.counter.map.into_iter().collect::<Vec<_>>();
+        in_memory.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        let mut counts = SortedCounts::new(self.codec, in_memory, &self.runs)?;
+        counts.runs = self.runs;
+        Ok(counts)
+    }
+
+    /// Consumes the `SpillingCounter` and returns a [`Counter`] of everything counted, when
+    /// that fits in memory after all.
+    pub fn into_counter(self) -> Result<Counter<T, N>, Error> {
+        let mut counter = Counter::new();
+        for pair in self.into_sorted_iter()? {
+            let (item, count) = pair?;
+            counter.map.insert(item, count);
+        }
+        Ok(counter)
+    }
+}
+
+/// The next item of one source of a [`SortedCounts`]: the items in memory when `source` is
+/// `None`, or a run.
+struct Head<T, N> {
+    item: T,
+    count: N,
+    source: Option<usize>,
+}
+
+impl<T: Ord, N> PartialEq for Head<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord, N> Eq for Head<T, N> {}
+
+impl<T: Ord, N> PartialOrd for Head<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord, N> Ord for Head<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.item, self.source).cmp(&(&other.item, other.source))
+    }
+}
+
+/// An iterator over the items of a [`SpillingCounter`] and their total counts, in ascending
+/// order of item, returned by [`SpillingCounter::into_sorted_iter`].
+///
+/// After an error reading a run, the iterator ends.
+pub struct SortedCounts<T, N, C> {
+    codec: C,
+    in_memory: vec::IntoIter<(T, N)>,
+    readers: Vec<BufReader<File>>,
+    /// The runs being read, so that they are deleted along with the iterator; empty when the
+    /// runs belong to a counter merging them.
+    runs: Vec<Run<T>>,
+    heads: BinaryHeap<Reverse<Head<T, N>>>,
+    failed: bool,
+}
+
+impl<T, N, C> SortedCounts<T, N, C>
+where
+    T: Ord,
+    C: SpillCodec<T, N>,
+{
+    /// Merge `in_memory`, which must be sorted by item, with `runs`.
+    fn new(codec: C, in_memory: Vec<(T, N)>, runs: &[Run<T>]) -> Result<Self, Error> {
+        let mut counts = SortedCounts {
+            codec,
+            in_memory: in_memory.into_iter(),
+            readers: Vec::with_capacity(runs.len()),
+            runs: Vec::new(),
+            heads: BinaryHeap::new(),
+            failed: false,
+        };
+        counts.refill(None)?;
+        for run in runs {
+            counts.readers.push(run.open()?);
+            counts.refill(Some(counts.readers.len() - 1))?;
+        }
+        Ok(counts)
+    }
+
+    /// Push the next item of `source` onto the heap, if it has one.
+    fn refill(&mut self, source: Option<usize>) -> io::Result<()> {
+        let next = match source {
+            None => self.in_memory.next(),
+            Some(index) => self.codec.decode(&mut self.readers[index])?,
+        };
+        if let Some((item, count)) = next {
+            self.heads.push(Reverse(Head {
+                item,
+                count,
+                source,
+            }));
+        }
+        Ok(())
+    }
+}
+
+impl<T, N, C> Iterator for SortedCounts<T, N, C>
+where
+    T: Ord,
+    N: AddAssign,
+    C: SpillCodec<T, N>,
+{
+    type Item = Result<(T, N), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let Reverse(head) = self.heads.pop()?;
+        let (item, mut total) = (head.item, head.count);
+        let mut result = self.refill(head.source);
+        // Each source holds an item at most once, so any other heads with the same item come
+        // from other sources.
+        while self
+            .heads
+            .peek()
+            .is_some_and(|Reverse(next)| next.item == item)
+        {
+            let Reverse(next) = self.heads.pop().expect("the heap has a head");
+            total += next.count;
+            result = result.and(self.refill(next.source));
+        }
+        match result {
+            Ok(()) => Some(Ok((item, total))),
+            Err(err) => {
+                self.failed = true;
+                Some(Err(err.into()))
+            }
+        }
+    }
+}
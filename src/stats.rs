@@ -0,0 +1,503 @@
+pub(crate) mod special;
+
+use crate::frequencies::count_to_f64;
+use crate::Counter;
+
+use crate::num::ToPrimitive;
+
+use std::hash::{BuildHasher, Hash};
+
+/// The result of a chi-squared test of whether two counters are samples from the same
+/// distribution, as returned by [`Counter::same_distribution`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ChiSquaredTest {
+    /// Pearson's chi-squared statistic.
+    pub statistic: f64,
+    /// The degrees of freedom: one less than the number of distinct items in either counter.
+    pub degrees_of_freedom: usize,
+    /// The probability of a statistic at least this large if both counters were sampled from
+    /// the same distribution.
+    pub p_value: f64,
+    /// Whether the hypothesis that both counters were sampled from the same distribution
+    /// survives at the requested significance level, i.e. whether `p_value >= alpha`.
+    pub same_distribution: bool,
+}
+
+/// The mean, variance and skewness of the counts of the distinct items of a counter, as
+/// returned by [`Counter::count_moments`].
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CountMoments {
+    /// The number of distinct items.
+    pub items: usize,
+    /// The mean of the counts.
+    pub mean: f64,
+    /// The population variance of the counts.
+    pub variance: f64,
+    /// The population skewness of the counts: positive when a few items have much larger
+    /// counts than the rest. It is `0.0` when every count is the same.
+    pub skewness: f64,
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+{
+    /// Returns an iterator over the relative frequency of each item.
+    fn probabilities(&self) -> impl Iterator<Item = f64> + '_ {
+        let total: f64 = self.map.values().map(count_to_f64).sum();
+        self.map
+            .values()
+            .map(move |count| count_to_f64(count) / total)
+    }
+
+    /// Returns the Shannon entropy, in nats, of the distribution of items given by their
+    /// relative frequencies.
+    ///
+    /// Items with a count of zero do not contribute, and the entropy of an empty counter is
+    /// `0.0`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aabb".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.entropy(), 2f64.ln());
+    /// ```
+    pub fn entropy(&self) -> f64 {
+        -self
+            .probabilities()
+            .filter(|&p| p > 0.0)
+            .map(|p| p * p.ln())
+            .sum::<f64>()
+    }
+
+    /// Returns the Shannon entropy, in bits, of the distribution of items given by their
+    /// relative frequencies.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abcd".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.entropy_bits(), 2.0);
+    /// ```
+    pub fn entropy_bits(&self) -> f64 {
+        self.entropy() / std::f64::consts::LN_2
+    }
+
+    /// Returns the Gini impurity of the distribution of items: the probability that two items
+    /// drawn independently by their relative frequencies are different.
+    ///
+    /// The impurity of an empty counter is `0.0`.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "aaab".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.gini_impurity(), 0.375);
+    /// ```
+    pub fn gini_impurity(&self) -> f64 {
+        if self.map.is_empty() {
+            return 0.0;
+        }
+        1.0 - self.probabilities().map(|p| p * p).sum::<f64>()
+    }
+
+    /// Returns the mean of the counts of the distinct items, or `None` if the counter is
+    /// empty.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.count_mean(), Some(2.0));
+    /// ```
+    pub fn count_mean(&self) -> Option<f64> {
+        if self.map.is_empty() {
+            return None;
+        }
+        let sum: f64 = self.map.values().map(count_to_f64).sum();
+        Some(sum / self.map.len() as f64)
+    }
+
+    /// Returns the population variance of the counts of the distinct items, or `None` if the
+    /// counter is empty.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.count_variance(), Some(2.0 / 3.0));
+    /// ```
+    pub fn count_variance(&self) -> Option<f64> {
+        let mean = self.count_mean()?;
+        let sum_of_squares: f64 = self
+            .map
+            .values()
+            .map(|count| (count_to_f64(count) - mean).powi(2))
+            .sum();
+        Some(sum_of_squares / self.map.len() as f64)
+    }
+
+    /// Returns the mean, variance and skewness of the counts of the distinct items, or `None`
+    /// if the counter is empty.
+    ///
+    /// The moments are computed in a single pass with Welford's online algorithm, which stays
+    /// accurate when the counts are large and close together. The mean and variance are the
+    /// same as [`count_mean`](Counter::count_mean) and [`count_variance`](Counter::count_variance)
+    /// return, up to rounding.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter: Counter<_> = Counter::from([('a', 1), ('b', 1), ('c', 1), ('d', 5)]);
+    /// let moments = counter.count_moments().unwrap();
+    /// assert_eq!(moments.items, 4);
+    /// assert!((moments.mean - 2.0).abs() < 1e-12);
+    /// assert!((moments.variance - 3.0).abs() < 1e-12);
+    /// // Skewed towards the one large count.
+    /// assert!((moments.skewness - 2.0 / 3f64.sqrt()).abs() < 1e-12);
+    /// ```
+    pub fn count_moments(&self) -> Option<CountMoments> {
+        if self.map.is_empty() {
+            return None;
+        }
+        // Running mean and sums of the second and third powers of the deviations from it.
+        let (mut n, mut mean, mut m2, mut m3) = (0.0, 0.0, 0.0, 0.0);
+        for count in self.map.values() {
+            let previous_n = n;
+            n += 1.0;
+            let delta = count_to_f64(count) - mean;
+            let delta_n = delta / n;
+            let term = delta * delta_n * previous_n;
+            mean += delta_n;
+            m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * m2;
+            m2 += term;
+        }
+        let skewness = if m2 > 0.0 {
+            n.sqrt() * m3 / m2.powf(1.5)
+        } else {
+            0.0
+        };
+        Some(CountMoments {
+            items: self.map.len(),
+            mean,
+            variance: m2 / n,
+            skewness,
+        })
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: Ord,
+{
+    /// Returns the most common item, or `None` if the counter is empty.
+    ///
+    /// If several items are equally common, which of them is returned is unspecified; use
+    /// [`k_most_common_ordered(1)`](Counter::k_most_common_ordered) to break ties by the natural
+    /// ordering of the items.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let counter = "abbccc".chars().collect::<Counter<_>>();
+    /// assert_eq!(counter.mode(), Some(&'c'));
+    /// ```
+    pub fn mode(&self) -> Option<&T> {
+        self.map
+            .iter()
+            .max_by(|(_, a), (_, b)| a.cmp(b))
+            .map(|(item, _)| item)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// Test whether this counter and `other` plausibly count samples from the same
+    /// distribution, with Pearson's chi-squared test of homogeneity at significance level
+    /// `alpha`.
+    ///
+    /// The two counters are treated as the rows of a contingency table whose columns are the
+    /// distinct items of either counter. If either counter is empty, or there is only one
+    /// distinct item, there is no evidence against the hypothesis: the statistic is `0.0` and
+    /// the p-value `1.0`.
+    ///
+    /// The chi-squared approximation is poor when the expected count of an item is small; as
+    /// a rule of thumb, each should be at least 5.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let control: Counter<_> = Counter::from([("heads", 52), ("tails", 48)]);
+    /// let fair: Counter<_> = Counter::from([("heads", 47), ("tails", 53)]);
+    /// let loaded: Counter<_> = Counter::from([("heads", 80), ("tails", 20)]);
+    ///
+    /// assert!(control.same_distribution(&fair, 0.05).same_distribution);
+    ///
+    /// let test = control.same_distribution(&loaded, 0.05);
+    /// assert!(!test.same_distribution);
+    /// assert_eq!(test.degrees_of_freedom, 1);
+    /// assert!(test.p_value < 0.001);
+    /// ```
+    pub fn same_distribution(&self, other: &Self, alpha: f64) -> ChiSquaredTest {
+        let counts = self
+            .map
+            .iter()
+            .map(|(item, count)| {
+                let other_count = other.map.get(item).map_or(0.0, count_to_f64);
+                (count_to_f64(count), other_count)
+            })
+            .chain(
+                other
+                    .map
+                    .iter()
+                    .filter(|(item, _)| !self.map.contains_key(item))
+                    .map(|(_, count)| (0.0, count_to_f64(count))),
+            )
+            .filter(|&(a, b)| a + b > 0.0)
+            .collect::<Vec<_>>();
+
+        let (total_a, total_b) = counts
+            .iter()
+            .fold((0.0, 0.0), |(total_a, total_b), &(a, b)| {
+                (total_a + a, total_b + b)
+            });
+        let total = total_a + total_b;
+
+        let mut statistic = 0.0;
+        let mut degrees_of_freedom = 0;
+        if total_a > 0.0 && total_b > 0.0 && counts.len() > 1 {
+            for &(a, b) in &counts {
+                let expected_a = (a + b) * total_a / total;
+                let expected_b = (a + b) * total_b / total;
+                statistic += (a - expected_a).powi(2) / expected_a;
+                statistic += (b - expected_b).powi(2) / expected_b;
+            }
+            degrees_of_freedom = counts.len() - 1;
+        }
+
+        let p_value = special::chi_squared_survival(statistic, degrees_of_freedom);
+        ChiSquaredTest {
+            statistic,
+            degrees_of_freedom,
+            p_value,
+            same_distribution: p_value >= alpha,
+        }
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: ToPrimitive,
+    S: BuildHasher,
+{
+    /// Scores each item of either counter with `score(observed, expected)`, summed over the
+    /// four cells of the 2x2 contingency table comparing its counts against the rest of each
+    /// counter. Returns the items sorted by descending score.
+    fn keyness(&self, other: &Self, score: impl Fn(f64, f64) -> f64) -> Vec<(T, f64)> {
+        let total_a: f64 = self.map.values().map(count_to_f64).sum();
+        let total_b: f64 = other.map.values().map(count_to_f64).sum();
+        let total = total_a + total_b;
+
+        let counts = self
+            .map
+            .iter()
+            .map(|(item, count)| {
+                let other_count = other.map.get(item).map_or(0.0, count_to_f64);
+                (item, count_to_f64(count), other_count)
+            })
+            .chain(
+                other
+                    .map
+                    .iter()
+                    .filter(|(item, _)| !self.map.contains_key(item))
+                    .map(|(item, count)| (item, 0.0, count_to_f64(count))),
+            );
+
+        let mut scores = counts
+            .map(|(item, a, b)| {
+                let item_total = a + b;
+                let rest_total = total - item_total;
+                let cells = [
+                    (a, item_total * total_a),
+                    (b, item_total * total_b),
+                    (total_a - a, rest_total * total_a),
+                    (total_b - b, rest_total * total_b),
+                ];
+                let value =
+                    if item_total > 0.0 && rest_total > 0.0 && total_a > 0.0 && total_b > 0.0 {
+                        cells
+                            .iter()
+                            .map(|&(observed, expected)| score(observed, expected / total))
+                            .sum()
+                    } else {
+                        0.0
+                    };
+                (item.clone(), value)
+            })
+            .collect::<Vec<_>>();
+        scores.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        scores
+    }
+
+    /// Scores how much each item's frequency differs between this counter and `other`, with
+    /// Pearson's chi-squared statistic, and returns the items sorted by descending score.
+    ///
+    /// Each item is scored on the 2x2 contingency table of its count and the count of every
+    /// other item, in each counter. This is a common measure of keyness in corpus linguistics:
+    /// the items with the highest scores are those most characteristic of one counter relative
+    /// to the other. The score does not say which counter the item is more common in; compare
+    /// their relative frequencies for that.
+    ///
+    /// An item's score is `0.0` if either counter is empty, or if it is the only item.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let study = "the cat sat on the mat the cat".split_whitespace().collect::<Counter<_>>();
+    /// let reference = "the dog sat on the log".split_whitespace().collect::<Counter<_>>();
+    ///
+    /// let scores = study.chi_squared(&reference);
+    /// assert_eq!(scores[0].0, "cat");
+    /// assert_eq!(scores.len(), 7);
+    /// ```
+    pub fn chi_squared(&self, other: &Self) -> Vec<(T, f64)> {
+        self.keyness(other, |observed, expected| {
+            (observed - expected).powi(2) / expected
+        })
+    }
+
+    /// Scores how much each item's frequency differs between this counter and `other`, with
+    /// the log-likelihood ratio statistic G², and returns the items sorted by descending score.
+    ///
+    /// This is Dunning's log-likelihood, computed on the same 2x2 contingency table as
+    /// [`chi_squared`](Counter::chi_squared). It is the usual keyness measure in corpus
+    /// linguistics, since unlike chi-squared it stays reliable for rare items. Like
+    /// chi-squared, it follows a chi-squared distribution with one degree of freedom, so a
+    /// score above 3.84 is significant at `p < 0.05`, and above 6.63 at `p < 0.01`.
+    ///
+    /// An item's score is `0.0` if either counter is empty, or if it is the only item.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let study: Counter<_> = Counter::from([("whale", 40), ("ship", 60), ("the", 900)]);
+    /// let reference: Counter<_> = Counter::from([("whale", 1), ("ship", 50), ("the", 949)]);
+    ///
+    /// let scores = study.log_likelihood_ratio(&reference);
+    /// assert_eq!(scores[0].0, "whale");
+    /// assert!(scores[0].1 > 6.63);
+    /// ```
+    pub fn log_likelihood_ratio(&self, other: &Self) -> Vec<(T, f64)> {
+        self.keyness(other, |observed, expected| {
+            if observed > 0.0 {
+                2.0 * observed * (observed / expected).ln()
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
+/// Returns the median of `values`, which must not be empty, reordering them.
+#[allow(unknown_lints, clippy::manual_is_multiple_of)] // `is_multiple_of` needs Rust 1.87
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Clone,
+    N: ToPrimitive,
+{
+    /// Returns the items whose score, computed from their count by `score`, is at least
+    /// `threshold` in absolute value, sorted by descending absolute score.
+    fn outliers(&self, threshold: f64, score: impl Fn(f64) -> f64) -> Vec<(T, f64)> {
+        let mut outliers = self
+            .map
+            .iter()
+            .map(|(item, count)| (item, score(count_to_f64(count))))
+            .filter(|(_, score)| score.abs() >= threshold)
+            .map(|(item, score)| (item.clone(), score))
+            .collect::<Vec<_>>();
+        outliers.sort_by(|(_, a), (_, b)| b.abs().total_cmp(&a.abs()));
+        outliers
+    }
+
+    /// Returns the items whose count is at least `threshold` standard deviations from the mean
+    /// count, with their z-scores, sorted by descending absolute z-score.
+    ///
+    /// A positive z-score marks an item counted unusually often, and a negative one an item
+    /// counted unusually rarely. A threshold of 2 or 3 is typical. Nothing is an outlier if
+    /// every count is the same.
+    ///
+    /// The mean and standard deviation are themselves pulled towards large outliers, so a
+    /// single spike among few items may not stand out; see
+    /// [`mad_outliers`](Counter::mad_outliers) for a robust alternative.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let errors: Counter<_> = Counter::from([(500, 12), (502, 9), (503, 11), (504, 10), (404, 95)]);
+    /// let outliers = errors.zscore_outliers(1.5);
+    /// assert_eq!(outliers.len(), 1);
+    /// assert_eq!(outliers[0].0, 404);
+    /// assert!(outliers[0].1 > 1.9);
+    /// ```
+    pub fn zscore_outliers(&self, threshold: f64) -> Vec<(T, f64)> {
+        let Some(moments) = self.count_moments() else {
+            return Vec::new();
+        };
+        let deviation = moments.variance.sqrt();
+        if deviation == 0.0 {
+            return Vec::new();
+        }
+        self.outliers(threshold, |count| (count - moments.mean) / deviation)
+    }
+
+    /// Returns the items whose modified z-score is at least `threshold` in absolute value,
+    /// with their modified z-scores, sorted by descending absolute score.
+    ///
+    /// The modified z-score of Iglewicz and Hoaglin measures the distance of a count from the
+    /// median count in units of the median absolute deviation (MAD), scaled to match the
+    /// standard deviation of normally distributed counts. Unlike the mean and standard
+    /// deviation, the median and MAD are not dragged along by the outliers themselves, so this
+    /// finds a single spike even among few items. The usual threshold is 3.5.
+    ///
+    /// When more than half the counts are the same, the MAD is zero; the mean absolute
+    /// deviation from the median is used instead. Nothing is an outlier if every count is the
+    /// same.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let errors: Counter<_> = Counter::from([(500, 12), (502, 9), (503, 11), (504, 10), (404, 95)]);
+    /// let outliers = errors.mad_outliers(3.5);
+    /// assert_eq!(outliers.len(), 1);
+    /// assert_eq!(outliers[0].0, 404);
+    /// assert!(outliers[0].1 > 50.0);
+    /// ```
+    pub fn mad_outliers(&self, threshold: f64) -> Vec<(T, f64)> {
+        let mut counts = self.map.values().map(count_to_f64).collect::<Vec<_>>();
+        if counts.is_empty() {
+            return Vec::new();
+        }
+        let center = median(&mut counts);
+        let mut deviations = counts
+            .iter()
+            .map(|count| (count - center).abs())
+            .collect::<Vec<_>>();
+        let mad = median(&mut deviations);
+        // Scale factors which make each measure estimate the standard deviation of a normal
+        // distribution.
+        let scale = if mad > 0.0 {
+            1.4826 * mad
+        } else {
+            1.2533 * deviations.iter().sum::<f64>() / deviations.len() as f64
+        };
+        if scale == 0.0 {
+            return Vec::new();
+        }
+        self.outliers(threshold, |count| (count - center) / scale)
+    }
+}
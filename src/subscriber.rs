@@ -0,0 +1,334 @@
+use crate::collections::hash_map::Entry;
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use core::hash::Hash;
+use core::ops::{Add, AddAssign, Deref, Sub, SubAssign};
+
+/// Receives every change to the counts of a [`SubscribedCounter`].
+///
+/// A subscriber can keep a structure derived from the counter, such as a ranking or an index
+/// from counts to items, up to date one change at a time instead of rebuilding it from the
+/// whole counter.
+///
+/// Closures taking the same arguments as
+/// [`on_count_changed`](Subscriber::on_count_changed) are subscribers. Annotate the types of
+/// their arguments, so that they accept references of any lifetime.
+pub trait Subscriber<T, N> {
+    /// Called after the count of `item` changes from `old` to `new`, where `None` means that
+    /// the item is not in the counter: `old` is `None` when the item is inserted, and `new`
+    /// is `None` when it is removed.
+    fn on_count_changed(&mut self, item: &T, old: Option<&N>, new: Option<&N>);
+}
+
+impl<T, N, F> Subscriber<T, N> for F
+where
+    F: FnMut(&T, Option<&N>, Option<&N>),
+{
+    fn on_count_changed(&mut self, item: &T, old: Option<&N>, new: Option<&N>) {
+        self(item, old, new)
+    }
+}
+
+/// A counter which reports every change to its counts to a [`Subscriber`].
+///
+/// Mutation is only possible through the methods and operators of this type, each of which
+/// notifies the subscriber once for every item whose count it changes. The wrapped counter can
+/// be read through [`Deref`], but there is deliberately no `DerefMut` or `IndexMut`: a count
+/// changed through a plain `&mut` reference could not be reported. Use
+/// [`set`](SubscribedCounter::set) or [`add`](SubscribedCounter::add) instead.
+///
+/// ```rust
+/// # use counter::Counter;
+/// let mut changes = Vec::new();
+/// let record = |item: &char, old: Option<&usize>, new: Option<&usize>| {
+///     changes.push((*item, old.copied(), new.copied()));
+/// };
+/// let mut counter = Counter::new().with_subscriber(record);
+///
+/// counter.update("aab".chars());
+/// counter.subtract("b".chars());
+/// drop(counter);
+///
+/// assert_eq!(
+///     changes,
+///     [
+///         ('a', None, Some(1)),
+///         ('a', Some(1), Some(2)),
+///         ('b', None, Some(1)),
+///         ('b', Some(1), None),
+///     ],
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct SubscribedCounter<T: Hash + Eq, W, N = usize> {
+    counter: Counter<T, N>,
+    subscriber: W,
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Consumes this counter and wraps it in a [`SubscribedCounter`], which reports every
+    /// change to its counts to `subscriber`.
+    ///
+    /// The subscriber is not told about the items already counted.
+    pub fn with_subscriber<W>(self, subscriber: W) -> SubscribedCounter<T, W, N>
+    where
+        W: Subscriber<T, N>,
+    {
+        SubscribedCounter {
+            counter: self,
+            subscriber,
+        }
+    }
+}
+
+impl<T, W, N> SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+{
+    /// Returns a reference to the subscriber.
+    pub fn subscriber(&self) -> &W {
+        &self.subscriber
+    }
+
+    /// Returns a mutable reference to the subscriber.
+    pub fn subscriber_mut(&mut self) -> &mut W {
+        &mut self.subscriber
+    }
+
+    /// Consumes the `SubscribedCounter`, returning the counter and the subscriber.
+    pub fn into_parts(self) -> (Counter<T, N>, W) {
+        (self.counter, self.subscriber)
+    }
+}
+
+impl<T, W, N> SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+{
+    /// Remove `item` from the counter, returning its count.
+    pub fn remove(&mut self, item: &T) -> Option<N> {
+        let (item, count) = self.counter.map.remove_entry(item)?;
+        self.subscriber.on_count_changed(&item, Some(&count), None);
+        Some(count)
+    }
+
+    /// Remove every item from the counter, notifying the subscriber of each.
+    pub fn clear(&mut self) {
+        for (item, count) in self.counter.map.drain() {
+            self.subscriber.on_count_changed(&item, Some(&count), None);
+        }
+    }
+
+    /// Retain only the items for which the predicate returns `true`, given each item and its
+    /// count, notifying the subscriber of each item removed.
+    pub fn retain<F>(&mut self, mut predicate: F)
+    where
+        F: FnMut(&T, &N) -> bool,
+    {
+        let subscriber = &mut self.subscriber;
+        self.counter.map.retain(|item, count| {
+            let keep = predicate(item, count);
+            if !keep {
+                subscriber.on_count_changed(item, Some(count), None);
+            }
+            keep
+        });
+    }
+}
+
+impl<T, W, N> SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: Clone,
+{
+    /// Set the count of `item` to `count`, returning its previous count.
+    pub fn set(&mut self, item: T, count: N) -> Option<N> {
+        match self.counter.map.entry(item) {
+            Entry::Occupied(mut entry) => {
+                let old = entry.insert(count);
+                self.subscriber
+                    .on_count_changed(entry.key(), Some(&old), Some(entry.get()));
+                Some(old)
+            }
+            Entry::Vacant(entry) => {
+                let entry = entry.insert_entry(count);
+                self.subscriber
+                    .on_count_changed(entry.key(), None, Some(entry.get()));
+                None
+            }
+        }
+    }
+}
+
+impl<T, W, N> SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    /// Add `count` to the count of `item`.
+    pub fn add(&mut self, item: T, count: N) {
+        match self.counter.map.entry(item) {
+            Entry::Occupied(mut entry) => {
+                let old = entry.get().clone();
+                *entry.get_mut() += count;
+                self.subscriber
+                    .on_count_changed(entry.key(), Some(&old), Some(entry.get()));
+            }
+            Entry::Vacant(entry) => {
+                let mut new = N::zero();
+                new += count;
+                let entry = entry.insert_entry(new);
+                self.subscriber
+                    .on_count_changed(entry.key(), None, Some(entry.get()));
+            }
+        }
+    }
+
+    /// Subtract `count` from the count of `item`, removing it if it becomes non-positive.
+    pub fn sub(&mut self, item: T, count: N) {
+        if let Entry::Occupied(mut entry) = self.counter.map.entry(item) {
+            if *entry.get() > count {
+                let old = entry.get().clone();
+                *entry.get_mut() -= count;
+                self.subscriber
+                    .on_count_changed(entry.key(), Some(&old), Some(entry.get()));
+            } else {
+                let (item, old) = entry.remove_entry();
+                self.subscriber.on_count_changed(&item, Some(&old), None);
+            }
+        }
+    }
+}
+
+impl<T, W, N> SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + One + Clone,
+{
+    /// Add one to the count of each element of the given iterable, notifying the subscriber
+    /// of each increment.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.add(item, N::one());
+        }
+    }
+
+    /// Remove one from the count of each element of the given iterable, notifying the
+    /// subscriber of each decrement.
+    ///
+    /// Like [`Counter::subtract`], non-positive counts are removed.
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.sub(item, N::one());
+        }
+    }
+}
+
+impl<T, W, N> AddAssign<Counter<T, N>> for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    /// Add the counts of another counter, as `counter += other` does.
+    fn add_assign(&mut self, rhs: Counter<T, N>) {
+        for (item, count) in rhs.map {
+            self.add(item, count);
+        }
+    }
+}
+
+impl<T, W, N> SubAssign<Counter<T, N>> for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    /// Subtract the counts of another counter, as `counter -= other` does.
+    fn sub_assign(&mut self, rhs: Counter<T, N>) {
+        for (item, count) in rhs.map {
+            self.sub(item, count);
+        }
+    }
+}
+
+impl<T, W, N> Add<Counter<T, N>> for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    type Output = Self;
+
+    /// Add the counts of another counter, as `counter + other` does.
+    fn add(mut self, rhs: Counter<T, N>) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl<T, W, N> Sub<Counter<T, N>> for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    type Output = Self;
+
+    /// Subtract the counts of another counter, as `counter - other` does.
+    fn sub(mut self, rhs: Counter<T, N>) -> Self {
+        self -= rhs;
+        self
+    }
+}
+
+impl<T, W, N> Extend<T> for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + One + Clone,
+{
+    /// Add one to the count of each element, as [`update`](SubscribedCounter::update) does.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<T, W, N> Extend<(T, N)> for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+    W: Subscriber<T, N>,
+    N: PartialOrd + AddAssign + SubAssign + Zero + Clone,
+{
+    /// Add each count to the count of its item, as [`add`](SubscribedCounter::add) does.
+    fn extend<I: IntoIterator<Item = (T, N)>>(&mut self, iter: I) {
+        for (item, count) in iter {
+            self.add(item, count);
+        }
+    }
+}
+
+impl<T, W, N> Deref for SubscribedCounter<T, W, N>
+where
+    T: Hash + Eq,
+{
+    type Target = Counter<T, N>;
+    fn deref(&self) -> &Counter<T, N> {
+        &self.counter
+    }
+}
@@ -0,0 +1,122 @@
+//! Counting occurrences of many substrings at once.
+//!
+//! Counting how often each of a set of keywords occurs by searching the text for one keyword
+//! after another reads the whole text once per keyword. A [`MultiPatternCounter`] builds an
+//! Aho-Corasick automaton from the keywords up front, then finds all of them in a single scan,
+//! however many there are.
+//!
+//! ```rust
+//! # use counter::text::MultiPatternCounter;
+//! let keywords = MultiPatternCounter::new(["fn", "let", "mut"]).unwrap();
+//!
+//! let counts = keywords.count("fn main() { let mut x = 1; let y = x; }");
+//! assert_eq!(counts.get("fn"), Some(&1));
+//! assert_eq!(counts.get("let"), Some(&2));
+//! assert_eq!(counts.get("mut"), Some(&1));
+//!
+//! let counts = keywords.count_reader("let a = 1;\nlet b = 2;\n".as_bytes()).unwrap();
+//! assert_eq!(counts.get("let"), Some(&2));
+//! assert!(!counts.contains_key("fn"));
+//! ```
+
+use crate::Counter;
+
+use aho_corasick::AhoCorasick;
+
+use std::io;
+
+pub use aho_corasick::BuildError;
+
+/// Counts the occurrences of a fixed set of patterns in text, scanning it only once.
+///
+/// The counts are returned in a `Counter<String>` keyed by the patterns. Patterns which do not
+/// occur are left out.
+///
+/// Matches do not overlap: once a pattern is matched, scanning resumes after the end of the
+/// match, so `"aa"` occurs twice in `"aaaa"`, not three times. Where the matches of two
+/// patterns would overlap, the one which ends first is counted. Matches are found the same way
+/// whether the text is given at once or read from a stream.
+///
+/// ```rust
+/// # use counter::text::MultiPatternCounter;
+/// let counter = MultiPatternCounter::new(["aa", "ab"]).unwrap();
+/// let counts = counter.count("aaaab");
+/// assert_eq!(counts.get("aa"), Some(&2));
+/// assert_eq!(counts.get("ab"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiPatternCounter {
+    searcher: AhoCorasick,
+    patterns: Vec<String>,
+}
+
+impl MultiPatternCounter {
+    /// Build a `MultiPatternCounter` for `patterns`.
+    ///
+    /// Fails if the patterns are too many or too long for the automaton to represent.
+    pub fn new<I>(patterns: I) -> Result<Self, BuildError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let patterns: Vec<String> = patterns.into_iter().map(Into::into).collect();
+        let searcher = AhoCorasick::new(&patterns)?;
+        Ok(MultiPatternCounter { searcher, patterns })
+    }
+
+    /// Returns the patterns counted, in the order they were given.
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Count the occurrences of each pattern in `haystack`.
+    pub fn count<H>(&self, haystack: H) -> Counter<String>
+    where
+        H: AsRef<[u8]>,
+    {
+        self.count_all([haystack])
+    }
+
+    /// Count the occurrences of each pattern in all of `haystacks`, such as the lines of a
+    /// file, adding up the counts of each.
+    ///
+    /// Each haystack is searched separately, so matches never span two of them.
+    pub fn count_all<I>(&self, haystacks: I) -> Counter<String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut counts = vec![0; self.patterns.len()];
+        for haystack in haystacks {
+            for found in self.searcher.find_iter(haystack.as_ref()) {
+                counts[found.pattern().as_usize()] += 1;
+            }
+        }
+        self.to_counter(&counts)
+    }
+
+    /// Count the occurrences of each pattern in the text read from `reader`.
+    ///
+    /// The text is read in chunks, so it need not fit in memory, and matches which span two
+    /// chunks are still found. The reader is buffered internally.
+    pub fn count_reader<R>(&self, reader: R) -> io::Result<Counter<String>>
+    where
+        R: io::Read,
+    {
+        let mut counts = vec![0; self.patterns.len()];
+        for found in self.searcher.stream_find_iter(reader) {
+            counts[found?.pattern().as_usize()] += 1;
+        }
+        Ok(self.to_counter(&counts))
+    }
+
+    fn to_counter(&self, counts: &[usize]) -> Counter<String> {
+        let mut counter = Counter::new();
+        for (pattern, &count) in self.patterns.iter().zip(counts) {
+            if count > 0 {
+                *counter.map.entry(pattern.clone()).or_insert(0) += count;
+            }
+        }
+        counter
+    }
+}
@@ -0,0 +1,49 @@
+//! Instrumentation of expensive operations with the `tracing` crate.
+//!
+//! With the `tracing` feature, sorts, merges, pruning and spills each run inside a
+//! `DEBUG`-level span named after the operation, with the sizes involved as fields. How long
+//! each took is the lifetime of its span, which subscribers can report: for example,
+//! `tracing_subscriber::fmt` with `FmtSpan::CLOSE` logs the time spent in every span as it
+//! closes. Without the feature, the macros expand to nothing and their fields are never
+//! evaluated.
+//!
+//! Operations which may be called in a tight loop, such as `+=` or `retain`, are only traced
+//! when they involve at least [`MIN_TRACED_LEN`] items: on small counters, the span would cost
+//! more than the operation itself. Spills and merges of spilled runs are always traced.
+
+/// The number of items from which operations given a size are traced.
+#[cfg(feature = "tracing")]
+pub(crate) const MIN_TRACED_LEN: usize = 1 << 10;
+
+/// Enters a `DEBUG`-level span until the end of the enclosing block.
+///
+/// With a leading size, as in `trace_span!(len; "name", len)`, the span is only entered if the
+/// size is at least [`MIN_TRACED_LEN`].
+macro_rules! trace_span {
+    ($size:expr; $name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = ($size >= $crate::trace::MIN_TRACED_LEN)
+            .then(|| tracing::debug_span!($name $(, $($fields)*)?).entered());
+    };
+    ($name:literal $(, $($fields:tt)*)?) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name $(, $($fields)*)?).entered();
+    };
+}
+
+/// Emits a `DEBUG`-level event, within the current span.
+///
+/// With a leading size, as for [`trace_span!`], the event is only emitted if the size is at
+/// least [`MIN_TRACED_LEN`].
+macro_rules! trace_event {
+    ($size:expr; $($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        if $size >= $crate::trace::MIN_TRACED_LEN {
+            tracing::debug!($($args)*);
+        }
+    };
+    ($($args:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($args)*);
+    };
+}
@@ -0,0 +1,257 @@
+use crate::Counter;
+
+use crate::num::{One, Zero};
+
+use core::hash::Hash;
+use core::ops::{AddAssign, Deref, SubAssign};
+
+/// A counter which keeps a running total of its counts, so that
+/// [`total_cached`](TrackedCounter::total_cached) is *O*(1) rather than the *O*(*n*) of
+/// [`Counter::total`].
+///
+/// Every mutation made through this type adjusts the total by exactly the amount the counts
+/// changed by, including when a subtraction removes an item whose count was smaller than the
+/// amount subtracted.
+///
+/// The wrapped counter can be read through [`Deref`], but there is deliberately no `DerefMut`:
+/// changing the counts through a plain `&mut Counter` would bypass the running total. For
+/// mutations this type does not provide, use [`modify`](TrackedCounter::modify), which
+/// recomputes the total afterwards in *O*(*n*).
+///
+/// ```rust
+/// # use counter::{Counter, TrackedCounter};
+/// let mut counter = "abbccc".chars().collect::<Counter<_>>().with_tracked_total();
+/// assert_eq!(counter.total_cached(), 6);
+///
+/// counter.update("cd".chars());
+/// counter.subtract("aa".chars());
+/// counter -= "bbb".chars().collect::<Counter<_>>();
+/// assert_eq!(counter.total_cached(), 5);
+/// assert_eq!(counter.total_cached(), counter.total::<usize>());
+///
+/// counter.modify(|counter| {
+///     counter.retain_keys(|&c| c != 'c');
+/// });
+/// assert_eq!(counter.total_cached(), 1);
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TrackedCounter<T: Hash + Eq, N = usize> {
+    counter: Counter<T, N>,
+    total: N,
+}
+
+impl<T, N> TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Zero,
+{
+    /// Create a new, empty `TrackedCounter`.
+    pub fn new() -> Self {
+        TrackedCounter {
+            counter: Counter::new(),
+            total: N::zero(),
+        }
+    }
+}
+
+impl<T, N> Default for TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Default,
+{
+    fn default() -> Self {
+        TrackedCounter {
+            counter: Counter::default(),
+            total: N::default(),
+        }
+    }
+}
+
+impl<T, N> Counter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + AddAssign + Zero,
+{
+    /// Consumes this counter and wraps it in a [`TrackedCounter`], which keeps a running total
+    /// of the counts.
+    ///
+    /// The initial total is computed once, in *O*(*n*).
+    pub fn with_tracked_total(self) -> TrackedCounter<T, N> {
+        let mut total = N::zero();
+        for count in self.map.values() {
+            total += count.clone();
+        }
+        TrackedCounter {
+            counter: self,
+            total,
+        }
+    }
+}
+
+impl<T, N> From<Counter<T, N>> for TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + AddAssign + Zero,
+{
+    fn from(counter: Counter<T, N>) -> Self {
+        counter.with_tracked_total()
+    }
+}
+
+impl<T, N> TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone,
+{
+    /// Returns the sum of the counts, in *O*(1).
+    pub fn total_cached(&self) -> N {
+        self.total.clone()
+    }
+}
+
+impl<T, N> TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    /// Consumes the `TrackedCounter`, discarding the running total, and returns the counter.
+    pub fn into_inner(self) -> Counter<T, N> {
+        self.counter
+    }
+}
+
+impl<T, N> TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + AddAssign + Zero,
+{
+    /// Calls `f` with mutable access to the wrapped counter, then recomputes the total.
+    ///
+    /// This allows any mutation of the counter, at the cost of an *O*(*n*) pass to restore the
+    /// total afterwards.
+    pub fn modify<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Counter<T, N>) -> R,
+    {
+        let result = f(&mut self.counter);
+        let mut total = N::zero();
+        for count in self.counter.map.values() {
+            total += count.clone();
+        }
+        self.total = total;
+        result
+    }
+}
+
+impl<T, N> TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + AddAssign + SubAssign + Zero,
+{
+    /// Add `count` to the count of `item`.
+    pub fn add(&mut self, item: T, count: N) {
+        self.total += count.clone();
+        *self.counter.map.entry(item).or_insert_with(N::zero) += count;
+    }
+
+    /// Subtract `count` from the count of `item`.
+    ///
+    /// Like `counter -= other_counter`, the item is removed if its count would become zero or
+    /// less, and the total is reduced only by the count it had.
+    pub fn sub(&mut self, item: T, count: N) {
+        if let Some(entry) = self.counter.map.get_mut(&item) {
+            if *entry > count {
+                *entry -= count.clone();
+                self.total -= count;
+            } else if let Some(removed) = self.counter.map.remove(&item) {
+                self.total -= removed;
+            }
+        }
+    }
+
+    /// Remove `item` from the counter, returning its count.
+    pub fn remove(&mut self, item: &T) -> Option<N> {
+        let count = self.counter.map.remove(item)?;
+        self.total -= count.clone();
+        Some(count)
+    }
+
+    /// Remove every item from the counter.
+    pub fn clear(&mut self) {
+        self.counter.map.clear();
+        self.total = N::zero();
+    }
+}
+
+impl<T, N> TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + AddAssign + SubAssign + Zero + One,
+{
+    /// Add the counts of the elements from the given iterable to this counter.
+    pub fn update<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            self.add(item, N::one());
+        }
+    }
+
+    /// Remove the counts of the elements from the given iterable from this counter.
+    ///
+    /// Like [`Counter::subtract`], non-positive counts are removed.
+    pub fn subtract<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = T>,
+    {
+        for item in iterable {
+            let mut remove = false;
+            if let Some(entry) = self.counter.map.get_mut(&item) {
+                if *entry > N::zero() {
+                    *entry -= N::one();
+                    self.total -= N::one();
+                }
+                remove = *entry == N::zero();
+            }
+            if remove {
+                self.counter.map.remove(&item);
+            }
+        }
+    }
+}
+
+impl<T, N> AddAssign<Counter<T, N>> for TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + AddAssign + SubAssign + Zero,
+{
+    /// Add the counts of another counter, as `counter += other` does.
+    fn add_assign(&mut self, rhs: Counter<T, N>) {
+        for (item, count) in rhs.map {
+            self.add(item, count);
+        }
+    }
+}
+
+impl<T, N> SubAssign<Counter<T, N>> for TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+    N: Clone + PartialOrd + AddAssign + SubAssign + Zero,
+{
+    /// Subtract the counts of another counter, as `counter -= other` does.
+    fn sub_assign(&mut self, rhs: Counter<T, N>) {
+        for (item, count) in rhs.map {
+            self.sub(item, count);
+        }
+    }
+}
+
+impl<T, N> Deref for TrackedCounter<T, N>
+where
+    T: Hash + Eq,
+{
+    type Target = Counter<T, N>;
+    fn deref(&self) -> &Counter<T, N> {
+        &self.counter
+    }
+}
@@ -239,3 +239,45 @@ fn test_non_usize_count() {
     };
     assert!(counter.map == expected);
 }
+
+#[test]
+fn test_chi_squared_survival() {
+    use crate::stats::special::{chi_squared_survival, ln_gamma};
+
+    // ln Γ(n) = ln (n - 1)!
+    assert!((ln_gamma(1.0)).abs() < 1e-12);
+    assert!((ln_gamma(5.0) - 24f64.ln()).abs() < 1e-12);
+    assert!((ln_gamma(0.5) - std::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+
+    // Reference values of the chi-squared upper tail.
+    let cases = [
+        (3.841_458_820_694_124, 1, 0.05),
+        (6.634_896_601_021_214, 1, 0.01),
+        (5.991_464_547_107_979, 2, 0.05),
+        (18.307_038_053_275_146, 10, 0.05),
+        (0.0, 3, 1.0),
+    ];
+    for (statistic, degrees_of_freedom, expected) in cases {
+        let p = chi_squared_survival(statistic, degrees_of_freedom);
+        assert!(
+            (p - expected).abs() < 1e-9,
+            "{statistic}, {degrees_of_freedom}: {p}"
+        );
+    }
+    // With two degrees of freedom the tail is exactly exp(-x / 2).
+    assert!((chi_squared_survival(3.0, 2) - (-1.5f64).exp()).abs() < 1e-12);
+}
+
+#[test]
+fn test_num_cast() {
+    use crate::num::{NumCast, ToPrimitive};
+
+    assert_eq!(<u8 as NumCast>::from(255u64), Some(255));
+    assert_eq!(<u8 as NumCast>::from(256u64), None);
+    assert_eq!(<u8 as NumCast>::from(-1i32), None);
+    assert_eq!(<i8 as NumCast>::from(-128i64), Some(-128));
+    assert_eq!(<u128 as NumCast>::from(u128::MAX), Some(u128::MAX));
+    assert_eq!(<i64 as NumCast>::from(u128::MAX), None);
+    assert_eq!(u128::MAX.to_usize(), None);
+    assert_eq!(3u8.to_f64(), Some(3.0));
+}
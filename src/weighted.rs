@@ -0,0 +1,107 @@
+use crate::frequencies::count_to_f64;
+use crate::Counter;
+
+use crate::num::ToPrimitive;
+
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + ToPrimitive,
+    N: ToPrimitive,
+{
+    /// Returns the mean of the items, treating the counter as a histogram in which each item
+    /// is weighted by its count, or `None` if there are no positive counts.
+    ///
+    /// Items with a count of zero or less are ignored.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let rolls: Counter<u8> = Counter::from([(1, 2), (4, 1), (6, 1)]);
+    /// assert_eq!(rolls.weighted_mean(), Some(3.0));
+    /// ```
+    pub fn weighted_mean(&self) -> Option<f64> {
+        let (sum, total) = self
+            .map
+            .iter()
+            .map(|(item, count)| (item, count_to_f64(count)))
+            .filter(|&(_, weight)| weight > 0.0)
+            .fold((0.0, 0.0), |(sum, total), (item, weight)| {
+                let value = item.to_f64().unwrap_or(f64::NAN);
+                (sum + value * weight, total + weight)
+            });
+        (total > 0.0).then(|| sum / total)
+    }
+}
+
+impl<T, N, S> Counter<T, N, S>
+where
+    T: Hash + Eq + Ord,
+    N: ToPrimitive,
+{
+    /// Returns the weighted median of the items, treating the counter as a histogram in which
+    /// each item is weighted by its count, or `None` if there are no positive counts.
+    ///
+    /// This is the smallest item such that at least half of the total count is at or below it,
+    /// so when the weight is split evenly between two items, the lower one is returned. It is
+    /// the same as [`percentile(50.0)`](Counter::percentile).
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let latencies_ms: Counter<u32> = Counter::from([(10, 60), (20, 30), (500, 10)]);
+    /// assert_eq!(latencies_ms.weighted_median(), Some(&10));
+    /// ```
+    pub fn weighted_median(&self) -> Option<&T> {
+        self.percentile(50.0)
+    }
+
+    /// Returns the `p`th percentile of the items, treating the counter as a histogram in which
+    /// each item is weighted by its count, or `None` if there are no positive counts.
+    ///
+    /// This is the nearest-rank percentile: the smallest item such that at least `p` percent of
+    /// the total count is at or below it. `percentile(0.0)` is the smallest and
+    /// `percentile(100.0)` the largest item with a positive count. Items with a count of zero
+    /// or less are ignored.
+    ///
+    /// ```rust
+    /// # use counter::Counter;
+    /// let latencies_ms: Counter<u32> = Counter::from([(10, 60), (20, 30), (500, 10)]);
+    /// assert_eq!(latencies_ms.percentile(75.0), Some(&20));
+    /// assert_eq!(latencies_ms.percentile(95.0), Some(&500));
+    /// assert_eq!(latencies_ms.percentile(0.0), Some(&10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not between `0.0` and `100.0`, inclusive.
+    ///
+    /// # Time complexity
+    ///
+    /// *O*(*n* \* log *n*), where *n* is the number of items in the counter.
+    pub fn percentile(&self, p: f64) -> Option<&T> {
+        assert!(
+            (0.0..=100.0).contains(&p),
+            "percentile must be between 0 and 100, got {p}"
+        );
+        let mut items = self
+            .map
+            .iter()
+            .map(|(item, count)| (item, count_to_f64(count)))
+            .filter(|&(_, weight)| weight > 0.0)
+            .collect::<Vec<_>>();
+        items.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        let total: f64 = items.iter().map(|&(_, weight)| weight).sum();
+        let rank = total * p / 100.0;
+        let mut cumulative = 0.0;
+        for &(item, weight) in &items {
+            cumulative += weight;
+            if cumulative >= rank {
+                return Some(item);
+            }
+        }
+        // Rounding can leave the cumulative weight just short of the total.
+        items.last().map(|&(item, _)| item)
+    }
+}
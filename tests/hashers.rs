@@ -0,0 +1,297 @@
+//! Every inherent method of `Counter` must be available whatever its hasher, and the binary
+//! operators must accept a right-hand side with another hasher. Each module below instantiates
+//! the whole API for one `BuildHasher`, so a method whose impl block falls back to the default
+//! hasher fails to compile here.
+
+use std::hash::{BuildHasher, Hasher};
+
+/// A deterministic FNV-1a hasher, standing in for third-party hashers.
+#[derive(Clone, Default)]
+pub struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct FnvState;
+
+impl BuildHasher for FnvState {
+    type Hasher = Fnv;
+
+    fn build_hasher(&self) -> Fnv {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+macro_rules! hasher_matrix {
+    ($($name:ident => $hasher:ty),* $(,)?) => {
+        $(
+            mod $name {
+                use counter::{Counter, Journal, NonZeroPolicy, OrderBy, UpdatePlan};
+
+                type C<T, N = usize> = Counter<T, N, $hasher>;
+
+                fn chars(s: &str) -> C<char> {
+                    let mut counter = C::default();
+                    counter.update(s.chars());
+                    counter
+                }
+
+                #[test]
+                fn counting() {
+                    let mut counter = chars("abbccc");
+                    counter.subtract("c".chars());
+                    assert_eq!(counter.set('d', 4), None);
+                    counter.count_entry('d').increment();
+                    assert_eq!(counter.count_entry('d').decrement_saturating(), Some(&mut 4));
+                    counter.set_many([('e', 1)]);
+                    let plan = UpdatePlan::new().increment('a', 1).decrement('e', 1);
+                    let rollback = counter.apply_update_plan(&plan).unwrap();
+                    counter.rollback(rollback);
+                    counter.update_by(["aa"], |s| s.chars().next().unwrap());
+                    counter.subtract("a".chars());
+                    assert_eq!(counter.total::<usize>(), 10);
+                    assert_eq!(counter.total_checked::<u8>(), Some(10));
+                    assert_eq!(counter.total_saturating::<u8>(), 10);
+                    assert_eq!(counter.keys_cloned().len(), 5);
+                    assert_eq!(counter.counts_for(&['a', 'z']), [1, 0]);
+                    counter.retain_count(|&count| count > 1);
+                    counter.retain_keys(|&item| item != 'z').retain(|_, _| true);
+                    assert_eq!(counter.clone().min_count(4).len(), 1);
+                    assert_eq!(counter.elements().count(), 8);
+                    assert_eq!(counter.elements_capped(2).count(), 6);
+                    assert_eq!(counter.clone().into_elements().count(), 8);
+                    assert!(counter.sorted_elements().eq(&['b', 'b', 'c', 'c', 'd', 'd', 'd', 'd']));
+                    let _: &$hasher = counter.hasher();
+                    assert_eq!(counter.into_map().len(), 3);
+
+                    let mut pairs = C::<(char, char)>::default();
+                    pairs.update_zipped("ab".chars(), "cd".chars());
+                    pairs.update_adjacent("abc".chars());
+                    assert_eq!(pairs.len(), 4);
+
+                    let mut bytes = C::<u8>::default();
+                    bytes.update_slice(b"abba");
+                    bytes.try_update_slice(b"c").unwrap();
+                    bytes.try_reserve(1).unwrap();
+                    bytes.shrink_per_policy(&counter::GrowthPolicy::new());
+                    let policy = counter::GrowthPolicy::new().initial_capacity(8);
+                    let mut grown = C::<u8>::with_growth_policy(&policy);
+                    grown.update_slice(b"abba");
+                    grown.retain_keys_per_policy(&policy, |&byte| byte == b'a');
+                    assert!(grown.capacity() >= 8);
+                    assert_eq!(bytes.clone().try_into_sorted_elements().unwrap().len(), 5);
+                    bytes.remove(&b'c');
+                    assert_eq!(bytes[&b'a'], 2);
+                    assert_eq!(bytes.weighted_mean(), Some(97.5));
+                    assert_eq!(bytes.weighted_median(), Some(&b'a'));
+                    assert_eq!(bytes.percentile(100.0), Some(&b'b'));
+
+                    let mut ngrams = C::<Vec<char>>::default();
+                    ngrams.update_ngrams("abab".chars(), 2);
+                    let mut arrays = C::<[char; 2]>::default();
+                    arrays.update_array_ngrams("abab".chars());
+                    assert_eq!(ngrams[&vec!['a', 'b']], arrays[&['a', 'b']]);
+
+                    let mut text = C::<char>::default();
+                    text.update_chars("abbccc");
+                    assert_eq!(text, chars("abbccc"));
+
+                    let pool = counter::CounterPool::<char, usize, $hasher>::default();
+                    pool.get().update("ab".chars());
+                    let pooled = pool.get();
+                    let _: &$hasher = pooled.hasher();
+                    assert_eq!(pooled.detach(), C::default());
+
+                    let mut collected = "abbccc".chars().collect::<C<char>>();
+                    assert_eq!(collected, text);
+                    assert_eq!(collected.clone() + "d".chars() - "a".chars(), chars("bbcccd"));
+                    collected += "d".chars();
+                    collected -= "a".chars();
+                    collected.extend([('e', 2)]);
+                    collected.extend("e".chars());
+                    collected.extend(&chars("f"));
+                    assert_eq!(collected, chars("bbcccdeeef"));
+                    let pairs = [('a', 1), ('b', 2)].into_iter().collect::<C<char>>();
+                    assert_eq!(pairs, chars("abb"));
+                }
+
+                #[test]
+                fn ordering() {
+                    let counter = chars("abbccc");
+                    assert_eq!(counter.most_common()[0], ('c', 3));
+                    assert_eq!(counter.most_common_tiebreaker(Ord::cmp)[0], ('c', 3));
+                    assert_eq!(counter.most_common_ordered()[0], ('c', 3));
+                    assert_eq!(counter.k_most_common_ordered(1), [('c', 3)]);
+                    assert_eq!(counter.clone().drain_most_common(1), [('c', 3)]);
+                    assert_eq!(counter.clone().sort_into_vec()[0], ('c', 3));
+                    assert_eq!(counter.k_most_common_tiebreaker(1, Ord::cmp), [('c', 3)]);
+                    assert_eq!(counter.k_most_common_by(1, |a, b| a.cmp(&b)), [('a', 1)]);
+                    assert_eq!(counter.to_ordered_pairs(OrderBy::KeyAsc, Some(1)), [('a', 1)]);
+                    assert_eq!(counter.most_common_frequencies()[0].0, 'c');
+                    assert_eq!(counter.most_common_buckets()[&std::cmp::Reverse(3)], ['c']);
+                    assert_eq!(counter.count_of_counts()[&3], 1);
+                    assert_eq!(counter.k_most_common_by_group(|_| (), 1)[&()], [('c', 3)]);
+                    assert_eq!(counter.count_moments().unwrap().items, 3);
+                    assert_eq!(counter.simple_good_turing().unseen, 1.0 / 6.0);
+                    assert_eq!(counter.laplace_smoothed(1.0)[&'a'], 2.0 / 9.0);
+                    assert!(counter.histogram().to_string().starts_with("c | "));
+                    assert_eq!(counter.most_common_by_partial_ord()[0], ('c', 3));
+                    assert_eq!(counter.k_most_common_by_partial_ord(1), [('c', 3)]);
+                }
+
+                #[test]
+                fn set_relations() {
+                    let counter = chars("abbccc");
+                    let subset = chars("abc");
+                    assert!(subset.is_subset(&counter));
+                    assert!(subset.contains(&'a') && !subset.contains(&'z'));
+                    assert!(counter.is_superset(&subset));
+                    assert_eq!(subset.diff(&counter).changed.len(), 2);
+                    assert_eq!(subset.symmetric_difference(&counter), chars("bcc"));
+                    assert_eq!(subset.jaccard_index(&counter), 0.5);
+                    assert_eq!(subset.l1_distance(&counter), 3.0);
+                    assert_eq!(counter.positive_iter().count(), 3);
+                    assert_eq!(counter.positive_keys().count(), 3);
+                    assert_eq!(counter.positive_values().sum::<usize>(), 6);
+                    assert_eq!(counter.chi_squared(&counter)[0].1, 0.0);
+                    assert_eq!(counter.log_likelihood_ratio(&subset).len(), 3);
+                    assert_eq!(counter.zscore_outliers(1.0).len(), 2);
+                    assert_eq!(counter.mad_outliers(0.5).len(), 2);
+
+                    // The other counter may use another hasher.
+                    let other = "abc".chars().collect::<Counter<char>>();
+                    assert!(other.is_subset_with_hasher(&counter));
+                    assert!(counter.is_superset_with_hasher(&other));
+                    assert!(subset.is_subset_with_hasher(&other));
+                    assert!(subset.is_superset_with_hasher(&other));
+                    assert_eq!(counter.symmetric_difference_with_hasher(&other), chars("bcc"));
+                    assert_eq!(other.symmetric_difference_with_hasher(&counter).len(), 2);
+                }
+
+                #[test]
+                fn operators() {
+                    // The right-hand side may use another hasher; the result keeps the left's.
+                    let counter = chars("abbccc");
+                    let other = "abbd".chars().collect::<Counter<_>>();
+                    assert_eq!(counter.clone() + other.clone(), chars("aabbbbcccd"));
+                    assert_eq!(counter.clone() - other.clone(), chars("ccc"));
+                    assert_eq!(counter.clone() & other.clone(), chars("abb"));
+                    assert_eq!(counter.clone() | other.clone(), chars("abbcccd"));
+                    assert_eq!(counter.clone() ^ other.clone(), chars("cccd"));
+
+                    let mut assigned = counter.clone();
+                    assigned += other.clone();
+                    assigned -= other.clone();
+                    assigned |= other.clone();
+                    assigned ^= other.clone();
+                    assert_eq!(assigned, chars("ccc"));
+                    let mut intersected = chars("abbb");
+                    intersected &= other.clone();
+                    assert_eq!(intersected, chars("abb"));
+                    intersected.merge_with(other.clone(), |count, other| *count += other);
+                    assert_eq!(intersected, chars("aabbbbd"));
+                    intersected.subtract_counter_keeping_zeros(chars("ab"));
+                    intersected.subtract_keeping_zeros("aab".chars());
+                    assert_eq!(intersected[&'a'], 0);
+                    assert_eq!(intersected.positive_iter().count(), 2);
+                    intersected.cursor('a').increment();
+                    assert_eq!(intersected[&'a'], 1);
+                    assert_eq!(intersected.remove_count(&'a', 3), 1);
+                    assert_eq!(intersected.pop_most_common(), Some(('b', 2)));
+                    assert_eq!(intersected.pop_least_common(), Some(('d', 1)));
+                    intersected.extend_max([('a', 2), ('a', 5), ('b', 1)]);
+                    intersected.extend_min([('a', 3), ('b', 2)]);
+                    assert_eq!(intersected, chars("aaab"));
+                    assert_eq!(intersected.prune_tail_fraction(0.25), 1);
+                    assert_eq!(intersected, chars("aaa"));
+                    let dense = counter::DenseCounter::<u8>::from(C::<u8>::from([(b'a', 2)]));
+                    assert_eq!(C::<u8>::from(dense), C::from([(b'a', 2)]));
+                    let rehashed: Counter<char> = counter.clone_into_hasher();
+                    assert_eq!(rehashed.into_hasher::<$hasher>(), counter);
+                    assert_eq!(counter.clone_with_capacity(64), counter);
+                    let mut reserved = C::<char>::with_capacity_and_hasher(0, <$hasher>::default());
+                    reserved.reserve(64);
+                    assert!(reserved.capacity() >= 64);
+                    reserved.shrink_to_fit();
+                    assert_eq!(reserved, C::with_hasher(<$hasher>::default()));
+
+                    let sum: Counter<char> = other + counter;
+                    assert_eq!(sum, "aabbbbcccd".chars().collect());
+                }
+
+                #[test]
+                fn statistics() {
+                    let counter = chars("aabb");
+                    assert_eq!(counter.probability(&'a'), 0.5);
+                    assert_eq!(counter.frequencies()[&'b'], 0.5);
+                    assert_eq!(counter.entropy_bits(), 1.0);
+                    assert_eq!(counter.gini_impurity(), 0.5);
+                    assert_eq!(counter.count_mean(), Some(2.0));
+                    assert_eq!(counter.count_variance(), Some(0.0));
+                    assert!(counter.mode().is_some());
+                    assert!(counter.same_distribution(&counter, 0.05).same_distribution);
+                    let product = counter.cartesian_product(&counter).unwrap();
+                    let product: &C<(char, char)> = &product;
+                    assert_eq!(product.len(), 4);
+                }
+
+                #[test]
+                fn signed() {
+                    let mut counter = C::<char, i32>::default();
+                    counter.set('a', 2);
+                    let mut other = C::<char, i32>::default();
+                    other.set('b', 1);
+                    let difference = counter.clone().signed_sub(other.clone());
+                    assert_eq!(difference[&'b'], -1);
+                    counter.signed_sub_assign(other);
+                    assert_eq!(counter.clone().positive().len(), 1);
+                    assert_eq!(counter.negative().len(), 1);
+                }
+
+                #[test]
+                fn conversions() {
+                    let counter = chars("aab");
+                    let map = counter.clone().into_nonzero_map(NonZeroPolicy::Drop).unwrap();
+                    assert_eq!(map.len(), 2);
+                    assert_eq!(counter::flatten([counter.clone(), counter.clone()])[&'a'], 4);
+                    assert_eq!(counter::flatten_weighted([(counter.clone(), 3)])[&'b'], 3);
+
+                    let mut replica = C::default();
+                    replica.apply_journal(Journal::Snapshot("aab".chars().collect()));
+                    assert_eq!(replica, counter);
+
+                    let strings: C<String> = counter.clone().convert_keys();
+                    assert_eq!(strings.len(), 2);
+                    let codes: C<u32> = counter.try_convert_keys().unwrap();
+                    assert_eq!(codes[&97], 2);
+                }
+            }
+        )*
+    };
+}
+
+hasher_matrix! {
+    random_state => std::collections::hash_map::RandomState,
+    default_hasher => std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>,
+    fnv => crate::FnvState,
+}
+
+#[cfg(feature = "rustc-hash")]
+hasher_matrix! {
+    fx => rustc_hash::FxBuildHasher,
+}
+
+#[cfg(feature = "ahash")]
+hasher_matrix! {
+    ahash => ahash::RandomState,
+}
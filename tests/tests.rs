@@ -67,6 +67,21 @@ mod tests {
         assert!(by_common == expected);
     }
 
+    #[test]
+    fn test_most_common_ordered_large() {
+        let mut rng = rand::thread_rng();
+
+        // Few distinct counts are grouped by count, and many fall back to the comparison sort.
+        for max_count in [1, 4, 16, 17, 1000] {
+            let counter = (0..5000_u32)
+                .map(|item| (item, rng.gen_range(1..=max_count)))
+                .collect::<Counter<u32>>();
+            let mut expected = counter.clone().into_map().into_iter().collect::<Vec<_>>();
+            expected.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+            assert_eq!(counter.most_common_ordered(), expected);
+        }
+    }
+
     #[test]
     fn test_k_most_common_ordered() {
         let counter: Counter<_> = "abracadabra".chars().collect();
@@ -186,6 +201,15 @@ mod tests {
         assert!(a.is_subset(&b));
     }
 
+    #[test]
+    fn test_set_relations_infer_the_other_counter() {
+        // The argument is inferred to be a counter of the same type, as it always has been.
+        let a = "ab".chars().collect::<Counter<_>>();
+        assert!(a.is_subset(&"abc".chars().collect()));
+        assert!(!a.is_superset(&"abc".chars().collect()));
+        assert_eq!(a.symmetric_difference(&"abc".chars().collect()).len(), 1);
+    }
+
     #[cfg(feature = "serde")]
     #[test]
     fn test_serialize_deserialize() {
@@ -194,4 +218,2835 @@ mod tests {
         let b: Counter<char> = serde_json::from_str(&serialized).unwrap();
         assert!(a == b)
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_deserialize_custom_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+
+        let a = "abbccc".chars().collect::<Counter<_>>();
+        let serialized = serde_json::to_string(&a).unwrap();
+        let b: Counter<char, usize, Hasher> = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(b.total::<usize>(), 6);
+        assert_eq!(b[&'c'], 3);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_deserialize_pairs() {
+        use counter::serde::as_pairs;
+
+        let a = [(1, 2), (1, 2), (3, 4)].into_iter().collect::<Counter<_>>();
+        let mut serialized = Vec::new();
+        as_pairs::serialize(&a, &mut serde_json::Serializer::new(&mut serialized)).unwrap();
+        assert!(serialized.starts_with(b"[["));
+        let b: Counter<(i32, i32)> =
+            as_pairs::deserialize(&mut serde_json::Deserializer::from_slice(&serialized)).unwrap();
+        assert_eq!(a, b);
+
+        let summed: Counter<char> = as_pairs::deserialize(&mut serde_json::Deserializer::from_str(
+            r#"[["a", 1], ["b", 2], ["a", 3]]"#,
+        ))
+        .unwrap();
+        assert_eq!(summed, "aaaabb".chars().collect());
+    }
+
+    #[test]
+    fn test_pool_reuses_counters() {
+        use counter::CounterPool;
+
+        let pool = CounterPool::<char>::new();
+        let capacity = {
+            let mut counter = pool.get();
+            counter.update("abcdefghijklmnop".chars());
+            counter.capacity()
+        };
+        assert_eq!(pool.idle(), 1);
+
+        let counter = pool.get();
+        assert_eq!(pool.idle(), 0);
+        assert!(counter.is_empty());
+        assert_eq!(counter.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_pool_shared_between_threads() {
+        use counter::CounterPool;
+
+        let pool = CounterPool::<u8>::with_max_idle(2);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let mut counter = pool.get();
+                    counter.update(0..10);
+                    assert_eq!(counter.len(), 10);
+                });
+            }
+        });
+        assert!(pool.idle() <= 2);
+    }
+
+    #[test]
+    fn test_update_slice_matches_update() {
+        let mut rng = rand::thread_rng();
+
+        // Both sides of the dense thresholds, and of the table counts of the striped histogram.
+        for len in [0, 1, 3, 4, 5, 31, 64, 1000, 16_384, 70_000, 300_000] {
+            let bytes: Vec<u8> = (0..len).map(|_| rng.gen()).collect();
+            let expected: Counter<u8> = bytes.iter().copied().collect();
+            assert_eq!(Counter::<u8>::from_slice(&bytes), expected);
+
+            let wide: Vec<u16> = (0..len).map(|_| rng.gen()).collect();
+            let mut counter: Counter<u16, u32> = wide.iter().copied().collect();
+            counter.update_slice(&wide);
+            let expected: Counter<u16, u32> = wide.iter().chain(&wide).copied().collect();
+            assert_eq!(counter, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_update_slice_overflow() {
+        let _ = Counter::<u8, i8>::from_slice(&[0; 200]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_update_slice_sum_overflow() {
+        let mut counter = Counter::<u8, i8>::from_slice(&[0; 100]);
+        counter.update_slice(&[0; 100]);
+    }
+
+    #[test]
+    fn test_ordered_counter_matches_counter() {
+        use counter::OrderedCounter;
+
+        let mut rng = rand::thread_rng();
+        let values: Vec<u8> = (0..500).map(|_| rng.gen_range(0..50)).collect();
+        let counter: Counter<_> = values.iter().copied().collect();
+        let ordered: OrderedCounter<_> = values.iter().copied().collect();
+
+        assert_eq!(ordered.most_common_ordered(), counter.most_common_ordered());
+        for k in 0..=ordered.len() {
+            assert_eq!(
+                ordered.k_most_common_ordered(k),
+                counter.k_most_common_ordered(k)
+            );
+        }
+        assert!(ordered
+            .keys()
+            .zip(ordered.keys().skip(1))
+            .all(|(a, b)| a < b));
+        assert_eq!(
+            ordered.total_in_range::<usize, _>(10..20),
+            values.iter().filter(|v| (10..20).contains(*v)).count()
+        );
+        assert_eq!(Counter::from(ordered), counter);
+    }
+
+    #[test]
+    fn test_ordered_counter_subtract() {
+        use counter::OrderedCounter;
+
+        let mut counter: OrderedCounter<_> = "abbccc".chars().collect();
+        counter.subtract("bbccddd".chars());
+        assert_eq!(
+            counter.into_iter().collect::<Vec<_>>(),
+            [('a', 1), ('c', 1)]
+        );
+    }
+
+    #[test]
+    fn test_into_nonzero_map() {
+        use counter::NonZeroPolicy;
+
+        let counter = "abbccc".chars().collect::<Counter<_>>();
+        let map = counter.into_nonzero_map(NonZeroPolicy::Reject).unwrap();
+        let counts = map
+            .iter()
+            .map(|(c, n)| (*c, n.get()))
+            .collect::<Counter<_>>();
+        assert_eq!(counts, "abbccc".chars().collect::<Counter<_>>());
+
+        let mut signed = "ab".chars().collect::<Counter<_, i64>>();
+        signed[&'z'] = -3;
+        let map = signed.into_nonzero_map(NonZeroPolicy::Drop).unwrap();
+        assert_eq!(map.len(), 2);
+
+        let mut huge = Counter::<char, u128>::new();
+        huge[&'a'] = u128::MAX;
+        let err = huge.into_nonzero_map(NonZeroPolicy::Drop).unwrap_err();
+        assert_eq!(err.item, 'a');
+
+        // The map keeps the counter's hasher.
+        let mut seeded = Counter::<char, usize, counter::SeededState>::with_seed(9);
+        seeded.update("abc".chars());
+        let map = seeded.into_nonzero_map(NonZeroPolicy::Reject).unwrap();
+        assert_eq!(map.hasher().seed(), 9);
+    }
+
+    #[test]
+    fn test_ledger_counter() {
+        use counter::LedgerCounter;
+
+        let mut ledger = LedgerCounter::<char>::new();
+        ledger.credit("abbccc".chars());
+        ledger.debit("bbccddd".chars());
+
+        let truncated =
+            "abbccc".chars().collect::<Counter<_>>() - "bbccddd".chars().collect::<Counter<_>>();
+        let net = ledger.net();
+        assert_eq!(net[&'a'], 1);
+        assert!(!net.contains_key(&'b'));
+        assert_eq!(net[&'c'], 1);
+        assert_eq!(net[&'d'], -3);
+        assert_eq!(truncated.len(), 2);
+
+        let gross = ledger.gross();
+        assert_eq!(gross.total::<i64>(), 13);
+        assert_eq!(gross[&'b'], 4);
+
+        let (credits, debits) = ledger.into_parts();
+        assert_eq!(
+            credits.total::<i64>() - debits.total::<i64>(),
+            net.total::<i64>()
+        );
+    }
+
+    #[test]
+    fn test_total_checked_and_saturating() {
+        let counter = "eaddbbccc".chars().collect::<Counter<_, u8>>();
+        assert_eq!(counter.total_checked::<u8>(), Some(9));
+        assert_eq!(counter.total_saturating::<u8>(), 9);
+
+        let mut counter = Counter::<char, u8>::new();
+        counter[&'a'] = u8::MAX;
+        counter[&'b'] = u8::MAX;
+        assert_eq!(counter.total_checked::<u8>(), None);
+        assert_eq!(counter.total_checked::<u64>(), Some(2 * u64::from(u8::MAX)));
+        assert_eq!(counter.total_saturating::<u8>(), u8::MAX);
+
+        let mut signed = Counter::<char, i64>::new();
+        signed[&'a'] = -5;
+        signed[&'b'] = 2;
+        assert_eq!(signed.total_checked::<u32>(), None);
+        assert_eq!(signed.total_checked::<i8>(), Some(-3));
+        assert_eq!(signed.total_saturating::<u32>(), 2);
+    }
+
+    #[test]
+    fn test_total_custom_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        let mut counter: Counter<char, usize, BuildHasherDefault<DefaultHasher>> =
+            Counter::default();
+        for c in "abracadabra".chars() {
+            counter[&c] += 1;
+        }
+        assert_eq!(counter.total::<usize>(), 11);
+        assert_eq!(counter.total_checked::<u8>(), Some(11));
+        assert_eq!(counter.total_saturating::<u8>(), 11);
+        assert_eq!(counter[&'a'], 5);
+    }
+
+    #[test]
+    fn test_min_count() {
+        let counter = "able babble table babble rabble table able fable scrabble"
+            .split_whitespace()
+            .collect::<Counter<_>>();
+        let frequent = counter.clone().min_count(2);
+        let expected = "able able babble babble table table"
+            .split_whitespace()
+            .collect::<Counter<_>>();
+        assert_eq!(frequent, expected);
+        assert_eq!(counter.clone().min_count(0), counter);
+        assert!(counter.min_count(3).is_empty());
+    }
+
+    #[test]
+    fn test_counts_for() {
+        let counter = "abbccc".chars().collect::<Counter<_>>();
+        let keys = ['c', 'x', 'a', 'b', 'c'];
+        assert_eq!(counter.counts_for(&keys), vec![3, 0, 1, 2, 3]);
+        assert!(counter.counts_for(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_from_pairs() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+
+        let counter = "eaddbbccc".chars().collect::<Counter<_>>();
+        let round_trip: Counter<_> = counter.most_common_ordered().into();
+        assert_eq!(round_trip, counter);
+
+        let pairs = vec![('a', 1), ('b', 2), ('a', 3)];
+        let hashed: Counter<_, usize, BuildHasherDefault<DefaultHasher>> =
+            Counter::from(pairs.as_slice());
+        assert_eq!(hashed[&'a'], 4);
+        assert_eq!(Counter::<_>::from(pairs), "aaaabb".chars().collect());
+    }
+
+    #[test]
+    fn test_journal_replication() {
+        use counter::JournaledCounter;
+
+        let mut rng = rand::thread_rng();
+        let mut primary = JournaledCounter::<u8, i32>::with_capacity(64);
+        let mut replica = Counter::<u8, i32>::new();
+
+        for round in 0..50 {
+            for _ in 0..rng.gen_range(0..10) {
+                let item = rng.gen_range(0..8);
+                match rng.gen_range(0..10) {
+                    0 => {
+                        primary.remove(&item);
+                    }
+                    1..=3 => primary.sub(item, rng.gen_range(1..4)),
+                    _ => primary.add(item, rng.gen_range(1..4)),
+                }
+            }
+            if round % 10 == 0 {
+                primary.update((0..100).map(|i| i % 8));
+            }
+            replica.apply_journal(primary.drain_journal());
+            assert_eq!(replica, *primary);
+        }
+    }
+
+    #[test]
+    fn test_journal_overflow_produces_snapshot() {
+        use counter::{Journal, JournaledCounter};
+
+        let mut primary = JournaledCounter::<char>::with_capacity(2);
+        primary.update("abc".chars());
+        assert_eq!(primary.journal_len(), None);
+
+        let journal = primary.drain_journal();
+        assert!(matches!(journal, Journal::Snapshot(_)));
+        let mut replica = "zzz".chars().collect::<Counter<_>>();
+        replica.apply_journal(journal);
+        assert_eq!(replica, "abc".chars().collect());
+        assert_eq!(primary.journal_len(), Some(0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_journal_serialize() {
+        use counter::{Journal, JournaledCounter};
+
+        let mut primary = JournaledCounter::<char>::with_capacity(10);
+        primary.update("abbc".chars());
+        primary.clear();
+        primary.update("ab".chars());
+        let serialized = serde_json::to_string(&primary.drain_journal()).unwrap();
+        let journal: Journal<char, usize> = serde_json::from_str(&serialized).unwrap();
+        let mut replica = Counter::new();
+        replica.apply_journal(journal);
+        assert_eq!(replica, *primary);
+    }
+
+    #[test]
+    fn test_keys_cloned() {
+        let counter = "eaddbbccc".chars().collect::<Counter<_>>();
+        let mut keys = counter.keys_cloned();
+        keys.sort_unstable();
+        assert_eq!(keys, ['a', 'b', 'c', 'd', 'e']);
+    }
+
+    #[test]
+    fn test_counts_copied() {
+        let counter = "eaddbbccc".chars().collect::<Counter<_>>();
+        let keys = counter.keys_cloned();
+        let counts = counter.counts_copied();
+        assert_eq!(counts.len(), counter.len());
+        for (key, count) in keys.iter().zip(&counts) {
+            assert_eq!(counter[key], *count);
+        }
+        assert!(Counter::<char>::new().counts_copied().is_empty());
+    }
+
+    #[cfg(feature = "arc-swap")]
+    #[test]
+    fn test_published_counter() {
+        use counter::publish::PublishedCounter;
+
+        let mut writer = PublishedCounter::<char>::new();
+        let reader = writer.reader();
+        writer.update("abracadabra".chars());
+        let before = reader.load();
+        assert!(before.is_empty());
+
+        let published = writer.publish();
+        writer.update("zzzzzzzzzz".chars());
+
+        let snapshot = reader.load();
+        assert!(std::sync::Arc::ptr_eq(&snapshot, &published));
+        assert_eq!(snapshot.generation(), 1);
+        assert_eq!(snapshot[&'a'], 5);
+        assert_eq!(snapshot[&'z'], 0);
+        assert_eq!(snapshot.rank(&'b'), Some(1));
+        assert_eq!(snapshot.total::<usize>(), 11);
+        assert_eq!(
+            snapshot.k_most_common(3),
+            &writer.k_most_common_ordered(4)[1..]
+        );
+
+        let readers: Vec<_> = (0..4).map(|_| reader.clone()).collect();
+        std::thread::scope(|scope| {
+            for reader in &readers {
+                scope.spawn(move || {
+                    let mut last = 0;
+                    for _ in 0..1000 {
+                        let generation = reader.load().generation();
+                        assert!(generation >= last);
+                        last = generation;
+                    }
+                });
+            }
+            for _ in 0..100 {
+                writer.update("q".chars());
+                writer.publish();
+            }
+        });
+        assert_eq!(reader.load().generation(), 101);
+        assert_eq!(reader.load()[&'q'], 100);
+    }
+
+    #[test]
+    fn test_elements() {
+        let counter = "aabccc".chars().collect::<Counter<_>>();
+        let mut elements = counter.elements().copied().collect::<Vec<_>>();
+        elements.sort_unstable();
+        assert_eq!(elements, "aabccc".chars().collect::<Vec<_>>());
+
+        let mut owned = counter.into_elements().collect::<Vec<_>>();
+        owned.sort_unstable();
+        assert_eq!(owned, elements);
+    }
+
+    #[test]
+    fn test_elements_skips_non_positive_counts() {
+        let counter: Counter<_, i32> = Counter::from([('a', 2), ('b', 0), ('c', -3)]);
+        assert_eq!(counter.elements().collect::<Vec<_>>(), [&'a', &'a']);
+        assert_eq!(counter.into_elements().collect::<Vec<_>>(), ['a', 'a']);
+
+        let mut elements = Counter::<char>::new().into_elements();
+        assert_eq!(elements.next(), None);
+        assert_eq!(elements.next(), None);
+    }
+
+    #[test]
+    fn test_update_zipped() {
+        let mut counter = Counter::<(&str, bool)>::new();
+        counter.update_zipped(["x", "y", "x"], [true, false, true]);
+        counter.update_zipped(["x"], Vec::new());
+        let expected = maplit::hashmap! {
+            ("x", true) => 2,
+            ("y", false) => 1,
+        };
+        assert_eq!(counter.into_map(), expected);
+    }
+
+    #[test]
+    fn test_update_adjacent() {
+        let mut counter = Counter::<(u8, u8)>::new();
+        counter.update_adjacent([1, 1, 1, 2]);
+        counter.update_adjacent([2]);
+        counter.update_adjacent(Vec::new());
+        let expected = maplit::hashmap! {
+            (1, 1) => 2,
+            (1, 2) => 1,
+        };
+        assert_eq!(counter.into_map(), expected);
+    }
+
+    #[test]
+    fn test_transition_counter() {
+        use counter::markov::TransitionCounter;
+
+        let mut chain = TransitionCounter::<char>::new();
+        chain.update("abab".chars());
+        chain.update("ba".chars());
+        assert_eq!(chain.count(&'a', &'b'), 2);
+        assert_eq!(chain.count(&'b', &'a'), 2);
+        assert_eq!(chain.count(&'a', &'a'), 0);
+        assert_eq!(chain.transitions_from(&'b').unwrap().total::<usize>(), 2);
+        assert!(chain.transitions_from(&'z').is_none());
+
+        chain.add_transition('a', 'c');
+        chain.add_transition('a', 'c');
+        chain.add_transition('a', 'c');
+        let probabilities = chain.probabilities_from(&'a');
+        assert_eq!(probabilities.len(), 2);
+        assert!((probabilities[&'b'] - 0.4).abs() < 1e-12);
+        assert!((probabilities[&'c'] - 0.6).abs() < 1e-12);
+        assert!(chain.probabilities_from(&'c').is_empty());
+        assert_eq!(chain.most_likely_next(&'a'), Some(&'c'));
+
+        let mut states = chain.states().copied().collect::<Vec<_>>();
+        states.sort_unstable();
+        assert_eq!(states, ['a', 'b']);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_transition_counter_serde() {
+        use counter::markov::TransitionCounter;
+
+        let chain = ["x", "y", "x", "x"]
+            .map(String::from)
+            .into_iter()
+            .collect::<TransitionCounter<String>>();
+        let json = serde_json::to_string(&chain).unwrap();
+        let deserialized: TransitionCounter<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, chain);
+    }
+
+    #[test]
+    fn test_to_ordered_pairs() {
+        use counter::OrderBy;
+
+        let counter = "eaddbbccc".chars().collect::<Counter<_>>();
+        assert_eq!(
+            counter.to_ordered_pairs(OrderBy::CountDesc, None),
+            counter.most_common_ordered()
+        );
+        for k in 0..7 {
+            let top = counter.to_ordered_pairs(OrderBy::CountDesc, Some(k));
+            assert_eq!(top, counter.k_most_common_ordered(k));
+            assert_eq!(top.len(), k.min(5));
+        }
+        assert_eq!(
+            counter.to_ordered_pairs(OrderBy::CountAsc, None),
+            [('a', 1), ('e', 1), ('b', 2), ('d', 2), ('c', 3)]
+        );
+        assert_eq!(
+            counter.to_ordered_pairs(OrderBy::KeyDesc, Some(2)),
+            [('e', 1), ('d', 2)]
+        );
+        assert_eq!(
+            counter.to_ordered_pairs(OrderBy::KeyAsc, Some(0)),
+            Vec::new()
+        );
+
+        let vowels_first = |a: (&char, &usize), b: (&char, &usize)| {
+            let is_vowel = |c: char| "aeiou".contains(c);
+            is_vowel(*b.0).cmp(&is_vowel(*a.0)).then(a.0.cmp(b.0))
+        };
+        assert_eq!(
+            counter.to_ordered_pairs(OrderBy::Custom(&vowels_first), Some(3)),
+            [('a', 1), ('e', 1), ('b', 2)]
+        );
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let counter = "aabbbbcccc".chars().collect::<Counter<_>>();
+        let frequencies = counter.frequencies();
+        assert_eq!(frequencies.len(), 3);
+        assert_eq!(frequencies[&'a'], 0.2);
+        assert_eq!(frequencies[&'b'], 0.4);
+        assert_eq!(frequencies.values().sum::<f64>(), 1.0);
+        assert_eq!(counter.probability(&'c'), 0.4);
+        assert_eq!(counter.probability(&'d'), 0.0);
+        assert_eq!(
+            counter.most_common_frequencies(),
+            [('b', 0.4), ('c', 0.4), ('a', 0.2)]
+        );
+
+        let empty = Counter::<char>::new();
+        assert!(empty.frequencies().is_empty());
+        assert_eq!(empty.probability(&'a'), 0.0);
+    }
+
+    #[test]
+    fn test_distribution_statistics() {
+        let counter: Counter<_> = Counter::from([('a', 6), ('b', 2), ('c', 0)]);
+        let expected_entropy = -(0.75f64 * 0.75f64.ln() + 0.25 * 0.25f64.ln());
+        assert!((counter.entropy() - expected_entropy).abs() < 1e-12);
+        assert!((counter.entropy_bits() - expected_entropy / 2f64.ln()).abs() < 1e-12);
+        assert!((counter.gini_impurity() - 0.375).abs() < 1e-12);
+        assert_eq!(counter.mode(), Some(&'a'));
+        assert_eq!(counter.count_mean(), Some(8.0 / 3.0));
+        let variance = counter.count_variance().unwrap();
+        assert!((variance - 56.0 / 9.0).abs() < 1e-12);
+
+        let empty = Counter::<char>::new();
+        assert_eq!(empty.entropy(), 0.0);
+        assert_eq!(empty.gini_impurity(), 0.0);
+        assert_eq!(empty.mode(), None);
+        assert_eq!(empty.count_mean(), None);
+        assert_eq!(empty.count_variance(), None);
+    }
+
+    #[test]
+    fn test_same_distribution() {
+        let a: Counter<_> = Counter::from([('x', 30), ('y', 10), ('z', 0)]);
+        let b: Counter<_> = Counter::from([('x', 10), ('y', 30)]);
+        let test = a.same_distribution(&b, 0.05);
+        assert_eq!(test.degrees_of_freedom, 1);
+        assert!((test.statistic - 20.0).abs() < 1e-9);
+        assert!(test.p_value < 1e-4);
+        assert!(!test.same_distribution);
+        assert_eq!(b.same_distribution(&a, 0.05), test);
+
+        let identical = a.same_distribution(&a, 0.05);
+        assert_eq!(identical.statistic, 0.0);
+        assert!((identical.p_value - 1.0).abs() < 1e-12);
+        assert!(identical.same_distribution);
+
+        let test = a.same_distribution(&Counter::new(), 0.05);
+        assert_eq!(test.degrees_of_freedom, 0);
+        assert_eq!(test.p_value, 1.0);
+    }
+
+    #[cfg(feature = "arbitrary")]
+    #[test]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        let data = (0..4096)
+            .map(|i: u32| (i * 2 + 1) as u8)
+            .collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&data);
+        let counter = Counter::<u16, i32>::arbitrary(&mut u).unwrap();
+        assert!(!counter.is_empty());
+        assert_eq!(
+            counter[&u16::MAX],
+            counter.get(&u16::MAX).copied().unwrap_or(0)
+        );
+
+        let mut u = Unstructured::new(&data);
+        let bounded = Counter::<u16, i32>::arbitrary_with_max_len(&mut u, 4).unwrap();
+        assert!(!bounded.is_empty());
+        assert!(bounded.len() <= 4);
+        assert!(counter.values().chain(bounded.values()).all(|&c| c > 0));
+
+        let mut u = Unstructured::new(&data);
+        let any = Counter::<u16, i32>::arbitrary_with_any_counts(&mut u, usize::MAX).unwrap();
+        assert!(any.values().any(|&c| c <= 0));
+
+        // Counts which are not positive become one, so no item is dropped.
+        let mut u = Unstructured::new(&data);
+        let positive = Counter::<u16, i32>::arbitrary_with_max_len(&mut u, usize::MAX).unwrap();
+        assert_eq!(positive.len(), any.len());
+        assert!(any
+            .iter()
+            .all(|(item, &c)| positive[item] == if c > 0 { c } else { 1 }));
+
+        let mut u = Unstructured::new(&[]);
+        assert!(Counter::<u16>::arbitrary(&mut u).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_set() {
+        let mut counter = "aab".chars().collect::<Counter<_, i32>>();
+        assert_eq!(counter.set('a', -1), Some(2));
+        assert_eq!(counter.set('b', 4), Some(1));
+        assert_eq!(counter.set('z', -3), None);
+        assert_eq!(counter.set('y', 1), None);
+        assert_eq!(counter.into_map(), maplit::hashmap! { 'b' => 4, 'y' => 1 });
+
+        let mut counter = Counter::<&str>::new();
+        counter.set_many(vec![("x", 1), ("y", 2), ("x", 0), ("z", 0)]);
+        assert_eq!(counter.into_map(), maplit::hashmap! { "y" => 2 });
+    }
+
+    #[test]
+    fn test_signed_sub() {
+        let c: Counter<_, i32> = Counter::from([("x", 3), ("y", 1), ("w", -2)]);
+        let d: Counter<_, i32> = Counter::from([("x", 1), ("y", 1), ("z", 4), ("w", -5)]);
+        let expected = maplit::hashmap! { "x" => 2, "y" => 0, "z" => -4, "w" => 3 };
+        assert_eq!(c.clone().signed_sub(d.clone()).into_map(), expected);
+
+        let mut e = c;
+        e.signed_sub_assign(d);
+        assert_eq!(e.into_map(), expected);
+
+        // Floats are only counts with `num-traits`.
+        #[cfg(feature = "num")]
+        {
+            let floats: Counter<_, f64> = Counter::from([('a', 0.5)]);
+            let diff = floats.signed_sub(Counter::from([('a', 1.5)]));
+            assert_eq!(diff[&'a'], -1.0);
+        }
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        let words = "a b b".split_whitespace().collect::<Counter<_>>();
+        let digits = [1, 1, 1].into_iter().collect::<Counter<_>>();
+        let product = words.cartesian_product(&digits).unwrap();
+        let expected = maplit::hashmap! { ("a", 1) => 3, ("b", 1) => 6 };
+        assert_eq!(product.into_map(), expected);
+
+        let empty = Counter::<i32>::new();
+        assert!(words.cartesian_product(&empty).unwrap().is_empty());
+        assert!(empty.cartesian_product(&words).unwrap().is_empty());
+
+        let big: Counter<_, u16> = Counter::from([('x', 256), ('y', 255)]);
+        assert_eq!(big.cartesian_product(&big), None);
+    }
+
+    #[test]
+    fn test_signed_algebra() {
+        let c: Counter<_, i32> = Counter::from([('a', 3), ('b', 0), ('c', -2)]);
+        assert_eq!(
+            (-c.clone()).into_map(),
+            maplit::hashmap! { 'a' => -3, 'b' => 0, 'c' => 2 }
+        );
+        assert_eq!(-(-c.clone()), c);
+        assert_eq!(
+            c.clone().positive().into_map(),
+            maplit::hashmap! { 'a' => 3 }
+        );
+        assert_eq!(
+            c.clone().negative().into_map(),
+            maplit::hashmap! { 'c' => 2 }
+        );
+
+        // positive and negative partition the non-zero counts
+        let restored = c.clone().positive().signed_sub(c.negative());
+        assert_eq!(
+            restored.into_map(),
+            maplit::hashmap! { 'a' => 3, 'c' => -2 }
+        );
+
+        let unsigned = "aab".chars().collect::<Counter<_>>();
+        assert_eq!(unsigned.clone().positive(), unsigned);
+    }
+
+    #[test]
+    fn test_scale() {
+        let counter = "aaaabbc".chars().collect::<Counter<_>>();
+        assert_eq!(
+            (counter.clone() * 3).into_map(),
+            maplit::hashmap! { 'a' => 12, 'b' => 6, 'c' => 3 }
+        );
+        let mut erased = counter.clone();
+        erased *= 0;
+        assert!(erased.is_empty());
+        assert_eq!(
+            (counter.clone() / 2).into_map(),
+            maplit::hashmap! { 'a' => 2, 'b' => 1 }
+        );
+
+        let mut scaled = counter.clone();
+        scaled *= 2;
+        scaled /= 2;
+        assert_eq!(scaled, counter);
+
+        let mut signed: Counter<_, i32> = Counter::from([('x', 4), ('y', -2)]);
+        signed *= -1;
+        assert_eq!(signed.into_map(), maplit::hashmap! { 'x' => -4, 'y' => 2 });
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_downsample() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let full = (0..100)
+            .flat_map(|i| vec![i; i + 1])
+            .collect::<Counter<_>>();
+
+        let mut keys = full.clone();
+        keys.downsample_keys(10, &mut rng);
+        assert_eq!(keys.len(), 10);
+        for (item, count) in &keys {
+            assert_eq!(*count, full[item]);
+        }
+        let mut unchanged = full.clone();
+        unchanged.downsample_keys(1000, &mut rng);
+        assert_eq!(unchanged, full);
+
+        let mut observations = full.clone();
+        observations.downsample_observations(500, &mut rng);
+        assert_eq!(observations.total::<usize>(), 500);
+        assert!(observations.is_subset(&full));
+        assert!(observations.values().all(|&count| count > 0));
+
+        let mut signed: Counter<_, i32> = Counter::from([('a', 3), ('b', -5), ('c', 0)]);
+        signed.downsample_observations(2, &mut rng);
+        assert_eq!(signed.into_map(), maplit::hashmap! { 'a' => 2 });
+
+        let mut none = full.clone();
+        none.downsample_observations(0, &mut rng);
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn test_k_most_common_by() {
+        let mut rng = rand::thread_rng();
+        let counter = (0..500)
+            .map(|_| rng.gen_range(0..100u32))
+            .collect::<Counter<_>>();
+        let by_key_desc = |(a, _): (&u32, &usize), (b, _): (&u32, &usize)| b.cmp(a);
+        let mut all = counter.clone().into_iter().collect::<Vec<_>>();
+        all.sort_unstable_by(|(a, _), (b, _)| b.cmp(a));
+        for k in [0, 1, 2, 10, 99, 100, 200] {
+            let top = counter.k_most_common_by(k, by_key_desc);
+            assert_eq!(top, all[..k.min(all.len())]);
+            assert_eq!(
+                counter.k_most_common_tiebreaker(k, |a, b| b.cmp(a)),
+                counter
+                    .most_common_tiebreaker(|a, b| b.cmp(a))
+                    .into_iter()
+                    .take(k)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[cfg(feature = "approx")]
+    #[test]
+    fn test_heavy_hitters() {
+        use counter::approx::HeavyHitters;
+
+        let mut rng = rand::thread_rng();
+        let stream = (0..10_000)
+            .map(|i| {
+                if i % 3 == 0 {
+                    1000
+                } else {
+                    rng.gen_range(0..500u32)
+                }
+            })
+            .collect::<Vec<_>>();
+        let exact = stream.iter().copied().collect::<Counter<_>>();
+
+        let mut hitters = HeavyHitters::<u32>::new(20);
+        hitters.update(stream.iter().copied());
+        assert_eq!(hitters.len(), 20);
+        assert_eq!(*hitters.total(), stream.len());
+        assert_eq!(hitters.k_most_common_ordered(1)[0].0, 1000);
+        for (item, estimate) in hitters.iter() {
+            let error = *hitters.error(item).unwrap();
+            assert!(*estimate >= exact[item]);
+            assert!(estimate - error <= exact[item]);
+            assert!(error <= stream.len() / 20);
+        }
+        assert_eq!(
+            hitters
+                .guaranteed_at_least(&3000)
+                .map(|(item, _)| *item)
+                .collect::<Vec<_>>(),
+            [1000]
+        );
+        assert_eq!(*hitters.estimate(&u32::MAX), 0);
+        assert_eq!(hitters.error(&u32::MAX), None);
+
+        let small = "aabbbc".chars().collect::<Counter<_>>();
+        let exact_hitters = HeavyHitters::from_counter(small.clone(), 3);
+        assert_eq!(Counter::from(exact_hitters), small);
+        let lossy = HeavyHitters::from_counter(small, 2);
+        assert_eq!(lossy.len(), 2);
+        assert_eq!(Counter::from(lossy).total::<usize>(), 6);
+    }
+
+    #[test]
+    fn test_diff() {
+        let baseline: Counter<_, i32> = Counter::from([("x", 5), ("y", 2), ("z", 1)]);
+        let current: Counter<_, i32> = Counter::from([("x", 3), ("y", 2), ("w", 4)]);
+        let diff = baseline.diff(&current);
+        assert_eq!(diff.added, [("w", 4)]);
+        assert_eq!(diff.removed, [("z", 1)]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            (
+                diff.changed[0].item,
+                diff.changed[0].before,
+                diff.changed[0].after
+            ),
+            ("x", 5, 3)
+        );
+        let summary = diff.summary();
+        assert_eq!((summary.added, summary.removed, summary.changed), (4, 1, 2));
+        assert_eq!(diff.to_string(), "+ w: 4\n- z: 1\n~ x: 5 -> 3\n");
+        assert!(baseline.diff(&baseline).is_empty());
+        assert_eq!(baseline.diff(&baseline).to_string(), "");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_diff_serde() {
+        let baseline = "aab".chars().collect::<Counter<_>>();
+        let current = "abc".chars().collect::<Counter<_>>();
+        let diff = baseline.diff(&current);
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "added": [["c", 1]],
+                "removed": [],
+                "changed": [{ "item": "a", "before": 2, "after": 1 }],
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(diff.summary()).unwrap()["changed"],
+            serde_json::json!(1)
+        );
+    }
+
+    #[test]
+    fn test_convert_keys_merges_collisions() {
+        #[derive(Debug, PartialEq, Eq, Hash)]
+        struct Parity(bool);
+
+        impl From<i32> for Parity {
+            fn from(n: i32) -> Self {
+                Parity(n % 2 == 0)
+            }
+        }
+
+        let counter = Counter::<i32>::from([(1, 1), (2, 2), (3, 3), (4, 4)]);
+        let parities = counter.convert_keys::<Parity>();
+        assert_eq!(parities.len(), 2);
+        assert_eq!(parities[&Parity(true)], 6);
+        assert_eq!(parities[&Parity(false)], 4);
+    }
+
+    #[test]
+    fn test_try_convert_keys() {
+        let counter = Counter::<i32>::from([(-1, 1), (1, 2)]);
+        let err = counter.try_convert_keys::<u8>().unwrap_err();
+        assert_eq!(err.item, -1);
+        assert_eq!(
+            err.to_string(),
+            format!("item -1 could not be converted: {}", err.error)
+        );
+
+        let counter = Counter::<i32>::from([(0, 1), (255, 2)]);
+        let converted = counter.try_convert_keys::<u8>().unwrap();
+        assert_eq!(converted, Counter::from([(0u8, 1), (255, 2)]));
+    }
+
+    #[test]
+    fn test_elements_capped() {
+        let mut counter = "abbcccdddd".chars().collect::<Counter<_, i32>>();
+        counter[&'e'] = -3;
+        let mut elements = counter.elements_capped(3).copied().collect::<Vec<_>>();
+        elements.sort();
+        assert_eq!(elements, "abbcccddd".chars().collect::<Vec<_>>());
+        assert_eq!(counter.elements_capped(0).count(), 0);
+
+        let capped = counter.elements_capped(2);
+        assert_eq!(capped.size_hint(), (0, Some(10)));
+    }
+
+    #[test]
+    fn test_flatten() {
+        let empty: Vec<Counter<char>> = Vec::new();
+        assert!(counter::flatten(empty).is_empty());
+
+        let shards = ["ab", "bcc", "", "cccd"].map(|s| s.chars().collect::<Counter<_>>());
+        let total = counter::flatten(shards);
+        assert_eq!(total, "abbcccccd".chars().collect());
+    }
+
+    #[test]
+    fn test_flatten_weighted() {
+        let empty: Vec<(Counter<char, i32>, i32)> = Vec::new();
+        assert!(counter::flatten_weighted(empty).is_empty());
+
+        let a = "aab".chars().collect::<Counter<_, i32>>();
+        let b = "bbc".chars().collect::<Counter<_, i32>>();
+        let c = "ccd".chars().collect::<Counter<_, i32>>();
+        let total = counter::flatten_weighted([(a, 3), (b, -1), (c, 0)]);
+        let expected: Counter<char, i32> = Counter::from([('a', 6), ('b', 1), ('c', -1)]);
+        assert_eq!(total, expected);
+    }
+
+    #[cfg(feature = "indexmap")]
+    #[test]
+    fn test_preserving_counter() {
+        use counter::preserving;
+
+        let mut counter = "zyzxy".chars().collect::<preserving::Counter<_>>();
+        assert_eq!(counter.keys().collect::<String>(), "zyx");
+        assert_eq!(counter.most_common(), [('z', 2), ('y', 2), ('x', 1)]);
+        assert_eq!(
+            counter.most_common_ordered(),
+            [('y', 2), ('z', 2), ('x', 1)]
+        );
+        assert_eq!(counter.k_most_common(1), [('z', 2)]);
+        assert_eq!(counter.k_most_common_ordered(1), [('y', 2)]);
+
+        counter.subtract("zz".chars());
+        counter[&'w'] += 3;
+        assert_eq!(counter.keys().collect::<String>(), "yxw");
+        assert_eq!(counter[&'z'], 0);
+        assert_eq!(counter.total::<usize>(), 6);
+
+        let hashed = Counter::from(counter.clone());
+        assert_eq!(hashed, "yyxwww".chars().collect());
+    }
+
+    #[test]
+    fn test_with_seed() {
+        let words = "a b c d e f g h i j k l m n o p".split_whitespace();
+        let mut first = Counter::<&str, usize, counter::SeededState>::with_seed(7);
+        first.update(words.clone());
+        let mut second = Counter::<&str, usize, counter::SeededState>::with_seed(7);
+        second.update(words);
+        assert_eq!(
+            first.keys().collect::<Vec<_>>(),
+            second.keys().collect::<Vec<_>>()
+        );
+        assert_eq!(first.most_common(), second.most_common());
+        assert_eq!(*first.hasher(), counter::SeededState::new(7));
+    }
+
+    #[test]
+    fn test_most_common_buckets() {
+        let counter = Counter::<char>::new();
+        assert!(counter.most_common_buckets().is_empty());
+
+        let counter = "zyxxwwvvv".chars().collect::<Counter<_>>();
+        let buckets = counter.most_common_buckets();
+        let expected = [(3, vec!['v']), (2, vec!['w', 'x']), (1, vec!['y', 'z'])];
+        let buckets = buckets
+            .into_iter()
+            .map(|(std::cmp::Reverse(count), items)| (count, items));
+        assert_eq!(buckets.collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn test_histogram() {
+        use counter::OrderBy;
+
+        let counter = Counter::<char>::new();
+        assert_eq!(counter.histogram().to_string(), "");
+
+        let mut counter = Counter::<&str, i32>::new();
+        counter[&"apple"] = 4;
+        counter[&"fig"] = 1;
+        counter[&"kiwi"] = -2;
+        let chart = counter
+            .histogram()
+            .width(8)
+            .order(OrderBy::KeyAsc)
+            .to_string();
+        assert_eq!(
+            chart,
+            "apple | ######## 4\n\
+             fig   | ##       1\n\
+             kiwi  |          -2\n"
+        );
+
+        let chart = counter.histogram().width(4).max_rows(0).to_string();
+        assert_eq!(chart, "... 3 more\n");
+    }
+
+    #[test]
+    fn test_update_plan() {
+        use counter::{PlanError, UpdatePlan};
+
+        let mut counter = "aabbbc".chars().collect::<Counter<_>>();
+        let plan = UpdatePlan::new()
+            .decrement('c', 1)
+            .increment('d', 2)
+            .set('a', 5)
+            .decrement('a', 1);
+        assert_eq!(plan.len(), 4);
+        let rollback = counter.apply_update_plan(&plan).unwrap();
+        let expected = maplit::hashmap! { 'a' => 4, 'b' => 3, 'd' => 2 };
+        assert_eq!(counter.clone().into_map(), expected);
+
+        counter.rollback(rollback);
+        assert_eq!(counter, "aabbbc".chars().collect());
+
+        // A later step may depend on an earlier one; validation fails part way through.
+        let plan = UpdatePlan::new()
+            .increment('z', 1)
+            .decrement('z', 1)
+            .decrement('z', 1);
+        let err = counter.apply_update_plan(&plan).unwrap_err();
+        assert_eq!(
+            err,
+            PlanError {
+                step: 2,
+                item: 'z',
+                count: 0
+            }
+        );
+        assert_eq!(counter, "aabbbc".chars().collect());
+
+        let mut signed = Counter::<char, i32>::new();
+        let err = signed
+            .apply_update_plan(&UpdatePlan::new().set('a', -1))
+            .unwrap_err();
+        assert_eq!((err.step, err.item, err.count), (0, 'a', 0));
+        assert!(signed.is_empty());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_round_trip() {
+        use counter::csv::{CsvError, CsvFormat};
+        use counter::OrderBy;
+
+        let counter = ["a,b", "plain", "plain", "say \"hi\""]
+            .into_iter()
+            .map(String::from)
+            .collect::<Counter<_>>();
+        let format = CsvFormat::new().order(OrderBy::KeyAsc);
+        let mut csv = Vec::new();
+        counter.to_csv_writer(&mut csv, &format).unwrap();
+        assert_eq!(
+            String::from_utf8(csv.clone()).unwrap(),
+            "item,count\n\"a,b\",1\nplain,2\n\"say \"\"hi\"\"\",1\n"
+        );
+        let read = Counter::<String>::from_csv_reader(csv.as_slice(), &format).unwrap();
+        assert_eq!(read, counter);
+
+        // Repeated items are summed.
+        let format = CsvFormat::new().header(false).delimiter(b'\t');
+        let read = Counter::<char>::from_csv_reader(&b"a\t1\nb\t2\na\t3\n"[..], &format).unwrap();
+        assert_eq!(read.into_map(), maplit::hashmap! { 'a' => 4, 'b' => 2 });
+
+        let err = Counter::<char>::from_csv_reader(&b"a\t1\nb\tmany\n"[..], &format).unwrap_err();
+        assert!(matches!(err, CsvError::Row { line: 2, .. }), "{err}");
+        let err = Counter::<char>::from_csv_reader(&b"a\t1\t2\n"[..], &format).unwrap_err();
+        assert!(matches!(err, CsvError::Row { line: 1, .. }), "{err}");
+    }
+
+    #[test]
+    fn test_sorted_elements() {
+        let mut counter = "mississippi".chars().collect::<Counter<_, i32>>();
+        counter[&'x'] = -2;
+        counter[&'y'] = 0;
+        let lazy = counter.sorted_elements();
+        assert_eq!(lazy.size_hint(), (11, Some(11)));
+        assert_eq!(lazy.collect::<String>(), "iiiimppssss");
+        assert_eq!(
+            counter
+                .into_sorted_elements()
+                .into_iter()
+                .collect::<String>(),
+            "iiiimppssss"
+        );
+    }
+
+    #[test]
+    fn test_weighted_statistics() {
+        let mut histogram: Counter<i64, i32> = Counter::from([(-5, 1), (0, 2), (10, 1), (7, 0)]);
+        histogram[&100] = -3;
+        assert_eq!(histogram.weighted_mean(), Some(1.25));
+        assert_eq!(histogram.weighted_median(), Some(&0));
+        assert_eq!(histogram.percentile(0.0), Some(&-5));
+        assert_eq!(histogram.percentile(25.0), Some(&-5));
+        assert_eq!(histogram.percentile(25.1), Some(&0));
+        assert_eq!(histogram.percentile(100.0), Some(&10));
+
+        let empty = Counter::<i64>::new();
+        assert_eq!(empty.weighted_mean(), None);
+        assert_eq!(empty.weighted_median(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "between 0 and 100")]
+    fn test_percentile_out_of_range() {
+        let counter = [1, 2, 3].into_iter().collect::<Counter<u8>>();
+        counter.percentile(101.0);
+    }
+
+    #[test]
+    fn test_count_of_counts() {
+        let counter = "the cat sat on the mat with the hat"
+            .split_whitespace()
+            .collect::<Counter<_>>();
+        let expected = maplit::hashmap! { 1 => 6, 3 => 1 };
+        assert_eq!(counter.count_of_counts().into_map(), expected);
+        assert!(Counter::<char>::new().count_of_counts().is_empty());
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut counter = ["x", "y"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Counter<_, i8>>();
+        counter[&"y".to_string()] = 0;
+        counter[&"z".to_string()] = -4;
+        assert!(counter.contains("x"));
+        assert!(!counter.contains("y"));
+        assert!(!counter.contains("z"));
+        assert!(!counter.contains("w"));
+        assert_eq!(counter.len(), 3);
+    }
+
+    #[test]
+    fn test_smoothing() {
+        let mut counter = Counter::<u32, i32>::new();
+        for (count, items) in [
+            (1, 120),
+            (2, 40),
+            (3, 24),
+            (4, 13),
+            (5, 15),
+            (7, 5),
+            (10, 2),
+        ] {
+            for _ in 0..items {
+                let item = counter.len() as u32;
+                counter[&item] = count;
+            }
+        }
+        counter[&9999] = -1;
+        let estimate = counter.simple_good_turing();
+        // The negative count is left out of the sample.
+        let sample_size = counter.total::<i32>() + 1;
+        assert_eq!(estimate.unseen, 120.0 / sample_size as f64);
+        assert!(!estimate.probabilities.contains_key(&9999));
+        let seen: f64 = estimate.probabilities.values().sum();
+        assert!((seen + estimate.unseen - 1.0).abs() < 1e-9);
+        // Every observed item is discounted, but larger counts stay more probable.
+        let probability_of = |count| {
+            let (item, _) = counter.iter().find(|&(_, &c)| c == count).unwrap();
+            estimate.probabilities[item]
+        };
+        for count in [1, 2, 3, 4, 5, 7, 10] {
+            assert!(probability_of(count) < count as f64 / sample_size as f64);
+        }
+        assert!(probability_of(1) < probability_of(2));
+        assert!(probability_of(7) < probability_of(10));
+
+        let none = Counter::<char>::new().simple_good_turing();
+        assert!(none.probabilities.is_empty());
+        assert_eq!(none.unseen, 1.0);
+
+        let laplace = "aab".chars().collect::<Counter<_>>().laplace_smoothed(0.5);
+        assert_eq!(laplace[&'a'], 2.5 / 4.0);
+        assert_eq!(laplace[&'b'], 1.5 / 4.0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_field_adapters() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Tallies {
+            #[serde(with = "counter::serde::as_map")]
+            words: Counter<String>,
+            #[serde(with = "counter::serde::as_pairs")]
+            bigrams: Counter<(char, char), u8>,
+        }
+
+        let tallies = Tallies {
+            words: Counter::from([("hi".to_string(), 2)]),
+            bigrams: Counter::from([(('h', 'i'), 2)]),
+        };
+        let json = serde_json::to_string(&tallies).unwrap();
+        assert_eq!(json, r#"{"words":{"hi":2},"bigrams":[[["h","i"],2]]}"#);
+        assert_eq!(serde_json::from_str::<Tallies>(&json).unwrap(), tallies);
+
+        let duplicates = r#"{"words":{},"bigrams":[[["a","b"],1],[["a","b"],3]]}"#;
+        let parsed = serde_json::from_str::<Tallies>(duplicates).unwrap();
+        assert_eq!(parsed.bigrams[&('a', 'b')], 4);
+    }
+
+    #[test]
+    fn test_drain_most_common() {
+        let mut counter = "abbcccddddeeeefff".chars().collect::<Counter<_>>();
+        // 'd' and 'e' tie for the most common; 'c' and 'f' tie for the third.
+        assert_eq!(counter.drain_most_common(3), [('d', 4), ('e', 4), ('c', 3)]);
+        let expected = maplit::hashmap! { 'a' => 1, 'b' => 2, 'f' => 3 };
+        assert_eq!(counter.clone().into_map(), expected);
+
+        assert!(counter.drain_most_common(0).is_empty());
+        assert_eq!(counter.len(), 3);
+        assert_eq!(
+            counter.drain_most_common(10),
+            [('f', 3), ('b', 2), ('a', 1)]
+        );
+        assert!(counter.is_empty());
+    }
+
+    #[test]
+    fn test_const_new() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::BuildHasherDefault;
+        use std::sync::Mutex;
+
+        type Registry = Counter<&'static str, i64, BuildHasherDefault<DefaultHasher>>;
+        static REGISTRY: Mutex<Registry> =
+            Mutex::new(Counter::const_new(BuildHasherDefault::new(), 0));
+
+        let mut registry = REGISTRY.lock().unwrap();
+        registry.update(["hit", "miss", "hit"]);
+        assert_eq!(registry[&"hit"], 2);
+        assert_eq!(registry[&"absent"], 0);
+    }
+
+    #[test]
+    fn test_retain_chaining() {
+        let mut counter = "a rose is a rose is a rose"
+            .split_whitespace()
+            .collect::<Counter<_>>();
+        let pruned = counter
+            .retain_keys(|word| *word != "a")
+            .retain(|_, count| {
+                *count *= 10;
+                *count > 20
+            })
+            .clone();
+        assert_eq!(pruned, Counter::from([("rose", 30)]));
+        assert_eq!(counter, pruned);
+    }
+
+    #[test]
+    fn test_count_moments() {
+        let counter = "abbcccddddddddd".chars().collect::<Counter<_>>();
+        let moments = counter.count_moments().unwrap();
+        assert_eq!(moments.items, 4);
+        assert!((moments.mean - counter.count_mean().unwrap()).abs() < 1e-12);
+        assert!((moments.variance - counter.count_variance().unwrap()).abs() < 1e-12);
+        assert!(moments.skewness > 0.0);
+
+        let uniform = "abc".chars().collect::<Counter<_>>();
+        assert_eq!(uniform.count_moments().unwrap().skewness, 0.0);
+        assert_eq!(Counter::<char>::new().count_moments(), None);
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let mut c: Counter<_, i32> = Counter::from([('a', 3), ('b', -2), ('c', 1), ('d', 4)]);
+        c[&'z'] = 0;
+        let d: Counter<_, i32> = Counter::from([('a', 1), ('b', 3), ('c', 1), ('e', -5)]);
+        let expected = maplit::hashmap! { 'a' => 2, 'b' => 5, 'd' => 4, 'e' => 5 };
+        assert_eq!(c.symmetric_difference(&d).into_map(), expected);
+        assert_eq!(d.symmetric_difference(&c).into_map(), expected);
+        assert_eq!((c.clone() ^ d.clone()).into_map(), expected);
+        c ^= d;
+        assert_eq!(c.into_map(), expected);
+    }
+
+    #[test]
+    fn test_similarity() {
+        let a = "the cat sat on the mat"
+            .split_whitespace()
+            .collect::<Counter<_>>();
+        let b = "the dog sat on the log"
+            .split_whitespace()
+            .collect::<Counter<_>>();
+        // min: the 2, sat 1, on 1; max: the 2, cat 1, sat 1, on 1, mat 1, dog 1, log 1
+        assert_eq!(a.jaccard_index(&b), 4.0 / 8.0);
+        assert_eq!(a.overlap_coefficient(&b), 4.0 / 6.0);
+        assert_eq!(a.l1_distance(&b), 4.0);
+        assert!((a.cosine_similarity(&b) - 6.0 / 8.0).abs() < 1e-12);
+        assert_eq!(a.jaccard_index(&a), 1.0);
+        assert_eq!(a.l1_distance(&b), b.l1_distance(&a));
+
+        let empty = Counter::new();
+        assert_eq!(a.jaccard_index(&empty), 0.0);
+        assert_eq!(empty.jaccard_index(&empty), 0.0);
+        assert_eq!(a.cosine_similarity(&empty), 0.0);
+        assert_eq!(a.overlap_coefficient(&empty), 0.0);
+        assert_eq!(a.l1_distance(&empty), 6.0);
+    }
+
+    #[test]
+    fn test_keyness() {
+        let study: Counter<_> = Counter::from([('a', 10), ('b', 90)]);
+        let reference: Counter<_> = Counter::from([('a', 30), ('b', 70), ('c', 0)]);
+
+        let chi_squared = study.chi_squared(&reference);
+        assert_eq!(chi_squared.len(), 3);
+        assert_eq!(chi_squared[2], ('c', 0.0));
+        for (_, score) in &chi_squared[..2] {
+            assert!((score - 12.5).abs() < 1e-9);
+        }
+        let test = study.same_distribution(&reference, 0.05);
+        assert!((test.statistic - 12.5).abs() < 1e-9);
+
+        let g2 = 2.0
+            * (10.0 * 0.5f64.ln()
+                + 30.0 * 1.5f64.ln()
+                + 90.0 * 1.125f64.ln()
+                + 70.0 * 0.875f64.ln());
+        let log_likelihood = study.log_likelihood_ratio(&reference);
+        for (_, score) in &log_likelihood[..2] {
+            assert!((score - g2).abs() < 1e-9);
+        }
+
+        let only: Counter<_> = Counter::from([('a', 5)]);
+        assert_eq!(only.chi_squared(&only), vec![('a', 0.0)]);
+        assert_eq!(study.log_likelihood_ratio(&Counter::new())[0].1, 0.0);
+    }
+
+    #[test]
+    fn test_k_most_common_by_group() {
+        let counter = "aaAAbBBbbccddeeeE".chars().collect::<Counter<_>>();
+        let top = counter.k_most_common_by_group(|c| c.is_uppercase(), 2);
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[&false], [('b', 3), ('e', 3)]);
+        assert_eq!(top[&true], [('A', 2), ('B', 2)]);
+
+        let all = counter.k_most_common_by_group(|c| c.to_ascii_lowercase(), 10);
+        assert_eq!(all[&'e'], [('e', 3), ('E', 1)]);
+        assert_eq!(all[&'c'], [('c', 2)]);
+
+        assert!(counter.k_most_common_by_group(|_| (), 0).is_empty());
+        assert!(Counter::<char>::new()
+            .k_most_common_by_group(|_| (), 3)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_from_sorted_pairs_unchecked() {
+        let counter = "abracadabra".chars().collect::<Counter<_>>();
+        let dump = counter.most_common_ordered();
+        assert_eq!(Counter::from_sorted_pairs_unchecked(dump.clone()), counter);
+        assert_eq!(dump.into_iter().collect::<Counter<_>>(), counter);
+
+        let pairs = (0..100).map(|n| (n, n * 2)).filter(|&(n, _)| n % 3 == 0);
+        let counter = Counter::<i32, i32>::from_sorted_pairs_unchecked(pairs);
+        assert_eq!(counter.len(), 34);
+        assert_eq!(counter[&99], 198);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "duplicate item")]
+    fn test_from_sorted_pairs_unchecked_duplicate() {
+        Counter::<_>::from_sorted_pairs_unchecked([('a', 1), ('a', 2)]);
+    }
+
+    #[test]
+    fn test_tracked_counter() {
+        use counter::TrackedCounter;
+
+        let mut counter = TrackedCounter::<char, i32>::new();
+        counter.update("aabbbc".chars());
+        counter.add('d', 4);
+        counter.sub('b', 1);
+        counter.sub('c', 5);
+        counter.subtract("ae".chars());
+        assert_eq!(counter.total_cached(), 7);
+        assert_eq!(counter.total_cached(), counter.total::<i32>());
+
+        counter += Counter::from([('a', 2), ('z', 1)]);
+        counter -= Counter::from([('d', 10), ('y', 3)]);
+        assert_eq!(counter.remove(&'z'), Some(1));
+        assert_eq!(counter.remove(&'z'), None);
+        assert_eq!(counter.total_cached(), 5);
+        assert_eq!(*counter, Counter::from([('a', 3), ('b', 2)]));
+
+        let doubled = counter.modify(|counter| {
+            for count in counter.values_mut() {
+                *count *= 2;
+            }
+            counter.len()
+        });
+        assert_eq!(doubled, 2);
+        assert_eq!(counter.total_cached(), 10);
+
+        counter.clear();
+        assert_eq!(counter.total_cached(), 0);
+        assert_eq!(
+            TrackedCounter::from("abc".chars().collect::<Counter<_>>()).total_cached(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_error_conversions() {
+        use counter::{Error, NonZeroPolicy, UpdatePlan};
+        use std::error::Error as _;
+
+        let mut counter: Counter<_, i32> = Counter::from([('a', 1), ('z', 0)]);
+        let err: Error = counter
+            .apply_update_plan(&UpdatePlan::new().decrement('a', 2))
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, Error::InvalidPlan(_)));
+        assert!(err.to_string().starts_with("invalid update plan: step 0"));
+        assert!(err.source().is_some());
+
+        let err: Error = counter
+            .clone()
+            .into_nonzero_map(NonZeroPolicy::Reject)
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, Error::Conversion(_)));
+
+        let err: Error = Counter::<i32>::from([(-1, 1)])
+            .try_convert_keys::<u8>()
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, Error::Conversion(_)));
+
+        let mut bytes = Counter::<u8, u8>::new();
+        assert!(matches!(
+            bytes.try_update_slice(&[0; 256]),
+            Err(Error::Overflow)
+        ));
+        assert!(bytes.is_empty());
+        assert!(Error::Overflow.source().is_none());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn test_csv_error_conversion() {
+        use counter::csv::CsvFormat;
+        use counter::Error;
+
+        let input = "item,count\na,not a number\n";
+        let err: Error = Counter::<String>::from_csv_reader(input.as_bytes(), &CsvFormat::new())
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, Error::Parse(_)));
+        assert!(err.to_string().contains("line 2"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_concurrent_counter() {
+        use counter::ConcurrentCounter;
+
+        let counter = ConcurrentCounter::<u32>::with_shards(8);
+        assert_eq!(counter.shards(), 8);
+        std::thread::scope(|scope| {
+            for thread in 0..4 {
+                let counter = &counter;
+                scope.spawn(move || {
+                    counter.update(0..1000);
+                    counter.add(thread, 10);
+                    counter.merge((0..100).collect::<Counter<_>>());
+                });
+            }
+        });
+
+        assert_eq!(counter.get(&0), 4 + 10 + 4);
+        assert_eq!(counter.get(&500), 4);
+        assert_eq!(counter.get(&5000), 0);
+        let snapshot = counter.snapshot();
+        let total = snapshot.total::<usize>();
+        assert_eq!(total, 4 * (1000 + 10 + 100));
+        assert_eq!(counter.into_counter(), snapshot);
+
+        let single = ConcurrentCounter::<char>::with_shards(1);
+        single.update("aab".chars());
+        assert_eq!(single.into_counter(), "aab".chars().collect());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    #[should_panic(expected = "at least one shard")]
+    fn test_concurrent_counter_no_shards() {
+        counter::ConcurrentCounter::<char>::with_shards(0);
+    }
+
+    #[test]
+    fn test_atomic_counter() {
+        use counter::AtomicCounter;
+
+        let mut counter = AtomicCounter::<String>::new();
+        assert!(counter.is_empty());
+        assert!(counter.insert("get".to_string()));
+        assert!(counter.insert("put".to_string()));
+        assert!(counter.insert("delete".to_string()));
+        assert!(counter.add("put", 5));
+        assert!(!counter.insert("put".to_string()));
+        assert_eq!(counter.get("put"), Some(5));
+        assert_eq!(counter.len(), 3);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        assert!(counter.increment("get"));
+                    }
+                    assert!(!counter.increment("post"));
+                });
+            }
+        });
+
+        assert!(counter.contains_key("delete"));
+        assert!(!counter.contains_key("post"));
+        assert_eq!(counter.get("post"), None);
+        let expected = Counter::from([("get".to_string(), 8000), ("put".to_string(), 5)]);
+        assert_eq!(counter.snapshot(), expected);
+        assert_eq!(counter.freeze(), expected);
+    }
+
+    #[test]
+    fn test_sort_into_vec() {
+        let counter = "abracadabra".chars().collect::<Counter<_>>();
+        let expected = counter.most_common_ordered();
+        assert_eq!(counter.sort_into_vec(), expected);
+        assert!(Counter::<char>::new().sort_into_vec().is_empty());
+    }
+
+    #[test]
+    fn test_positive_iterators() {
+        let mut counter = "abbccc".chars().collect::<Counter<_, i32>>();
+        counter[&'a'] = 0;
+        counter[&'b'] = -2;
+        counter[&'d'] = 0;
+        assert_eq!(counter.len(), 4);
+
+        let mut positive = counter.positive_iter().collect::<Vec<_>>();
+        positive.sort();
+        assert_eq!(positive, [(&'c', &3)]);
+        assert!(counter.positive_keys().all(|item| counter.contains(item)));
+        assert_eq!(counter.positive_values().copied().collect::<Vec<_>>(), [3]);
+        assert_eq!(Counter::<char>::new().positive_iter().count(), 0);
+    }
+
+    #[test]
+    fn test_growth_policy() {
+        use counter::GrowthPolicy;
+
+        let policy = GrowthPolicy::default();
+        let mut counter = Counter::<u32>::with_growth_policy(&policy);
+        counter.update(0..1000);
+        let grown = counter.capacity();
+
+        // More than a quarter of the capacity is still in use: nothing to release.
+        counter.retain_keys(|&item| item < 600);
+        let capacity = counter.capacity();
+        counter.shrink_per_policy(&policy);
+        assert_eq!(counter.capacity(), capacity);
+
+        counter
+            .retain_keys(|&item| item < 10)
+            .shrink_per_policy(&policy);
+        assert!(counter.capacity() < grown / 10);
+        assert_eq!(counter.len(), 10);
+
+        let never = GrowthPolicy::new().shrink_below(0.0);
+        let mut counter = Counter::<u32>::with_growth_policy(&never);
+        counter.update(0..1000);
+        counter.retain_keys(|_| false);
+        let capacity = counter.capacity();
+        counter.shrink_per_policy(&never);
+        assert_eq!(counter.capacity(), capacity);
+
+        let floor = GrowthPolicy::new().initial_capacity(500);
+        let mut counter = Counter::<u32>::with_growth_policy(&floor);
+        counter.update(0..1000);
+        counter.retain_keys(|_| false).shrink_per_policy(&floor);
+        assert!(counter.capacity() >= 500);
+
+        // The pruning methods taking a policy shrink as it allows.
+        let mut counter = Counter::<u32>::with_growth_policy(&policy);
+        counter.update(0..1000);
+        counter.retain_per_policy(&policy, |&item, _| item < 600);
+        assert!(counter.capacity() >= 600);
+        counter.retain_keys_per_policy(&policy, |&item| item < 10);
+        assert!(counter.capacity() < grown / 10);
+
+        let mut counter = Counter::<u32>::with_growth_policy(&policy);
+        counter.update(0..1000);
+        counter.update(0..10);
+        let counter = counter.min_count_per_policy(2, &policy);
+        assert_eq!(counter.len(), 10);
+        assert!(counter.capacity() < grown / 10);
+
+        let mut counter = Counter::<u32>::with_growth_policy(&policy);
+        counter.update(0..1000);
+        counter[&0] = 100_000;
+        assert_eq!(counter.prune_tail_fraction_per_policy(0.1, &policy), 999);
+        assert_eq!(counter.len(), 1);
+        assert!(counter.capacity() < grown / 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "shrink fraction must be between 0 and 1")]
+    fn test_growth_policy_invalid_fraction() {
+        counter::GrowthPolicy::new().shrink_below(1.5);
+    }
+
+    #[test]
+    fn test_merge_with() {
+        let base = "aaabbc".chars().collect::<Counter<_>>();
+        let other = "abbbd".chars().collect::<Counter<_>>();
+
+        let mut sum = base.clone();
+        sum.merge_with(other.clone(), |count, other| *count += other);
+        assert_eq!(sum, base.clone() + other.clone());
+
+        let mut max = base.clone();
+        max.merge_with(other.clone(), |count, other| *count = (*count).max(other));
+        assert_eq!(max, base.clone() | other.clone());
+
+        let mut min = base.clone();
+        min.merge_with(other.clone(), |count, other| *count = (*count).min(other));
+        assert_eq!(min, Counter::from([('a', 1), ('b', 2), ('c', 1), ('d', 1)]));
+
+        let mut calls = 0;
+        let mut unchanged = base.clone();
+        unchanged.merge_with(Counter::<char>::new(), |_, _| calls += 1);
+        assert_eq!(unchanged, base);
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn test_counter_entry() {
+        let mut counter = Counter::<char, i32>::new();
+        assert_eq!(*counter.count_entry('a').increment(), 1);
+        assert_eq!(*counter.count_entry('a').increment_by(4), 5);
+        assert_eq!(counter.count_entry('b').count(), &0);
+        assert_eq!(counter.count_entry('b').key(), &'b');
+        assert!(!counter.contains_key(&'b'));
+
+        assert_eq!(
+            counter.count_entry('a').decrement_saturating(),
+            Some(&mut 4)
+        );
+        assert_eq!(counter.count_entry('a').decrement_by_saturating(10), None);
+        assert_eq!(counter.count_entry('z').decrement_saturating(), None);
+        assert!(counter.is_empty());
+
+        assert_eq!(counter.count_entry('c').set(3), None);
+        assert_eq!(counter.count_entry('c').set(7), Some(3));
+        assert_eq!(counter.count_entry('c').set(0), Some(7));
+        assert_eq!(counter.count_entry('d').set(-1), None);
+        assert!(counter.is_empty());
+
+        // The map's own entry methods keep working.
+        *counter.count_entry('e').or_insert(0) += 2;
+        *counter.count_entry('f').or_default() += 1;
+        counter.count_entry('e').and_modify(|count| *count *= 10);
+        assert_eq!(*counter.count_entry('g').or_insert_with(|| 3), 3);
+        assert_eq!(counter.count_entry('g').remove(), Some(3));
+        assert_eq!(counter, Counter::from([('e', 20), ('f', 1)]));
+        assert_eq!(
+            format!("{:?}", counter.count_entry('e')),
+            "CounterEntry { item: 'e', count: 20 }"
+        );
+
+        // The map's own entry API is reached through `DerefMut`, rather than shadowed.
+        *counter.entry('h').or_insert(0) += 1;
+        assert_eq!(counter[&'h'], 1);
+    }
+
+    #[test]
+    fn test_subtract_keeping_zeros() {
+        let mut counter = "abbccc".chars().collect::<Counter<_>>();
+        counter.subtract_keeping_zeros("abbbz".chars());
+        let expected = [('a', 0), ('b', 0), ('c', 3)];
+        assert_eq!(counter, Counter::from(expected));
+
+        let mut counter = "abbccc".chars().collect::<Counter<_>>();
+        counter.subtract_counter_keeping_zeros("aaabbz".chars().collect::<Counter<_>>());
+        assert_eq!(counter, Counter::from(expected));
+        assert!(!counter.contains_key(&'z'));
+
+        // Dropping the zeros afterwards gives the same result as plain subtraction.
+        let kept_then_dropped: Counter<_> =
+            counter.positive_iter().map(|(&c, &n)| (c, n)).collect();
+        let subtracted = "abbccc".chars().collect::<Counter<_>>() - "aaabbz".chars();
+        assert_eq!(subtracted, kept_then_dropped);
+    }
+
+    #[test]
+    fn test_count_cursor() {
+        let mut counter = "aab".chars().collect::<Counter<_>>();
+        let mut cursor = counter.cursor('a');
+        assert_eq!(cursor.key(), &'a');
+        assert_eq!(cursor.increment_by(3), &5);
+        assert_eq!(cursor.decrement_by_saturating(10), &0);
+        assert_eq!(cursor.set(4), 0);
+        drop(cursor);
+        assert_eq!(counter[&'a'], 4);
+
+        // A count left at zero removes the item when the cursor is dropped.
+        let mut cursor = counter.cursor('b');
+        cursor.decrement_saturating();
+        drop(cursor);
+        assert!(!counter.contains_key(&'b'));
+
+        let cursor = counter.cursor('c');
+        let cursor = cursor.remove_if(|&count| count > 0).unwrap_err();
+        assert_eq!(cursor.remove(), 0);
+        assert_eq!(counter, Counter::from([('a', 4)]));
+    }
+
+    #[cfg(feature = "spill")]
+    #[test]
+    fn test_spilling_counter() {
+        use counter::spill::{SpillingCounter, MERGE_FAN_IN, MIN_IN_MEMORY};
+
+        let dir = std::env::temp_dir().join(format!("counter-spill-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Items which need the length prefix to be written as text.
+        let items = (0..500).map(|n| format!("item\t{}\n", n % 150));
+        let expected = items.clone().collect::<Counter<_>>();
+
+        let mut counter = SpillingCounter::<String>::new(&dir, 40);
+        counter.update(items.clone()).unwrap();
+        assert!(counter.runs() > 1);
+        assert!(counter.in_memory().len() <= 40);
+        assert_eq!(
+            counter.get(&"item\t7\n".to_string()).unwrap(),
+            expected[&"item\t7\n".to_string()]
+        );
+        assert_eq!(counter.get(&"missing".to_string()).unwrap(), 0);
+
+        let sorted = counter.into_sorted_iter().unwrap();
+        let sorted = sorted.collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(sorted.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(sorted.into_iter().collect::<Counter<_>>(), expected);
+
+        let mut counter = SpillingCounter::<String>::new(&dir, 40);
+        counter.update(items).unwrap();
+        counter.spill().unwrap();
+        assert_eq!(counter.into_counter().unwrap(), expected);
+
+        // Runs are merged as they pile up, so there are never many of them.
+        let mut counter = SpillingCounter::<u32>::new(&dir, MIN_IN_MEMORY);
+        let items = (0..20_000u32).map(|n| n.wrapping_mul(7_919) % 10_007);
+        counter.update(items.clone()).unwrap();
+        assert!(counter.runs() < 3 * MERGE_FAN_IN);
+        let expected = items.collect::<Counter<_>>();
+        for item in [0, 1, 5_003, 10_006, 10_007] {
+            assert_eq!(
+                counter.get(&item).unwrap(),
+                expected.get(&item).copied().unwrap_or(0)
+            );
+        }
+        let sorted = counter.into_sorted_iter().unwrap();
+        let sorted = sorted.collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(sorted.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(sorted.into_iter().collect::<Counter<_>>(), expected);
+
+        // Every run file has been deleted, so the directory can be removed.
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_remove_count() {
+        let mut counter = "aaabbc".chars().collect::<Counter<_>>();
+        assert_eq!(counter.remove_count(&'a', 2), 2);
+        assert_eq!(counter.remove_count(&'b', 2), 2);
+        assert_eq!(counter.remove_count(&'c', 5), 1);
+        assert_eq!(counter.remove_count(&'z', 1), 0);
+        assert_eq!(counter, Counter::from([('a', 1)]));
+
+        // Items with a count which is not positive are removed, and nothing is subtracted.
+        let mut counter = Counter::<char, i32>::new();
+        counter[&'x'] = -2;
+        assert_eq!(counter.remove_count(&'x', 1), 0);
+        assert!(counter.is_empty());
+
+        let mut counter = ["apple".to_string()].into_iter().collect::<Counter<_>>();
+        assert_eq!(counter.remove_count("apple", 1), 1);
+        assert!(counter.is_empty());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_to_weighted_index() {
+        use rand::distributions::Distribution;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut counter = Counter::<char, i32>::from([('a', 1), ('b', 2), ('c', 5), ('d', 0)]);
+        counter[&'e'] = -3;
+        let table = counter.to_weighted_index().unwrap();
+        let mut items = table.items().to_vec();
+        items.sort_unstable();
+        assert_eq!(items, ['a', 'b', 'c']);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let draws = (&table)
+            .sample_iter(&mut rng)
+            .take(80_000)
+            .collect::<Counter<_>>();
+        assert_eq!(draws.len(), 3);
+        for (item, weight) in [('a', 1.0), ('b', 2.0), ('c', 5.0)] {
+            let expected = 80_000.0 * weight / 8.0;
+            assert!((draws[&item] as f64 - expected).abs() < expected * 0.05);
+        }
+        assert!(table.items().contains(table.sample_ref(&mut rng)));
+
+        assert!(Counter::<char>::new().to_weighted_index().is_none());
+        assert!(Counter::<char, i32>::from([('a', 0)])
+            .to_weighted_index()
+            .is_none());
+    }
+
+    #[test]
+    fn test_pop_most_and_least_common() {
+        let counter = "abracadabra".chars().collect::<Counter<_>>();
+
+        let mut popped = counter.clone();
+        let mut order = Vec::new();
+        while let Some(pair) = popped.pop_most_common() {
+            order.push(pair);
+        }
+        assert_eq!(order, counter.most_common_ordered());
+
+        let mut popped = counter.clone();
+        assert_eq!(popped.pop_least_common(), Some(('c', 1)));
+        assert_eq!(popped.pop_least_common(), Some(('d', 1)));
+        assert_eq!(popped.pop_least_common(), Some(('b', 2)));
+        assert_eq!(popped.len(), 2);
+
+        // Huffman-style merging: repeatedly combine the two rarest subtrees.
+        let mut weights = Counter::<String>::from([
+            ("a".to_string(), 5),
+            ("b".to_string(), 2),
+            ("c".to_string(), 1),
+            ("d".to_string(), 1),
+        ]);
+        while weights.len() > 1 {
+            let (first, first_count) = weights.pop_least_common().unwrap();
+            let (second, second_count) = weights.pop_least_common().unwrap();
+            weights.insert(format!("({first}{second})"), first_count + second_count);
+        }
+        assert_eq!(
+            weights.pop_most_common(),
+            Some(("(((cd)b)a)".to_string(), 9))
+        );
+        assert!(weights.is_empty());
+    }
+
+    #[cfg(feature = "num")]
+    #[test]
+    fn test_non_copy_counts() {
+        use counter::num::{One, Zero};
+        use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+        // A heap-allocated count, standing in for an arbitrary-precision integer: it is `Clone`
+        // but not `Copy`.
+        #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Big(Box<u128>);
+
+        impl Add for Big {
+            type Output = Big;
+            fn add(self, rhs: Big) -> Big {
+                Big(Box::new(*self.0 + *rhs.0))
+            }
+        }
+        impl AddAssign for Big {
+            fn add_assign(&mut self, rhs: Big) {
+                *self.0 += *rhs.0;
+            }
+        }
+        impl Sub for Big {
+            type Output = Big;
+            fn sub(self, rhs: Big) -> Big {
+                Big(Box::new(*self.0 - *rhs.0))
+            }
+        }
+        impl SubAssign for Big {
+            fn sub_assign(&mut self, rhs: Big) {
+                *self.0 -= *rhs.0;
+            }
+        }
+        impl Mul for Big {
+            type Output = Big;
+            fn mul(self, rhs: Big) -> Big {
+                Big(Box::new(*self.0 * *rhs.0))
+            }
+        }
+        impl Zero for Big {
+            fn zero() -> Big {
+                Big(Box::new(0))
+            }
+            fn is_zero(&self) -> bool {
+                *self.0 == 0
+            }
+        }
+        impl One for Big {
+            fn one() -> Big {
+                Big(Box::new(1))
+            }
+        }
+        impl<'a> std::iter::Sum<&'a Big> for Big {
+            fn sum<I: Iterator<Item = &'a Big>>(iter: I) -> Big {
+                iter.fold(Big::zero(), |total, count| total + count.clone())
+            }
+        }
+        let big = |n: u128| Big(Box::new(n));
+
+        let mut counter = "abracadabra".chars().collect::<Counter<_, Big>>();
+        counter[&'z'] = big(u128::from(u64::MAX) * 4);
+        assert_eq!(counter.total::<Big>(), big(u128::from(u64::MAX) * 4 + 11));
+        assert_eq!(
+            counter.k_most_common_ordered(2),
+            [('z', counter[&'z'].clone()), ('a', big(5))]
+        );
+        assert_eq!(counter.most_common_ordered().len(), 6);
+
+        counter.subtract("zab".chars());
+        assert_eq!(counter[&'a'], big(4));
+        let other = "abc".chars().collect::<Counter<_, Big>>();
+        assert_eq!((counter.clone() & other.clone())[&'b'], big(1));
+        assert_eq!((counter.clone() - other.clone()).get(&'c'), None);
+        assert_eq!((counter + other)[&'c'], big(2));
+    }
+
+    #[test]
+    fn test_epoch_counter() {
+        use counter::EpochCounter;
+
+        let mut counter = EpochCounter::<char, 2, u32>::new();
+        counter.update("aab".chars());
+        counter.rotate();
+        counter.add('b', 5);
+        assert_eq!(counter.current(), &Counter::from([('b', 5)]));
+        assert_eq!(counter.epoch(1), Some(&"aab".chars().collect()));
+        assert_eq!(counter.epoch(2), None);
+        assert_eq!(counter.epochs().count(), 2);
+        assert_eq!(counter.windowed_total::<u32>(), 8);
+        assert_eq!(counter.windowed_most_common(5), [('b', 6), ('a', 2)]);
+
+        // The oldest epoch leaves the window.
+        assert_eq!(counter.rotate(), "aab".chars().collect());
+        assert_eq!(counter.windowed_counter(), Counter::from([('b', 5)]));
+        assert!(counter.current().is_empty());
+        counter.rotate();
+        assert_eq!(counter.windowed_total::<u32>(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "an epoch counter needs at least one epoch")]
+    fn test_epoch_counter_without_epochs() {
+        counter::EpochCounter::<char, 0>::new();
+    }
+
+    #[test]
+    fn test_clone_capacity_and_hasher() {
+        use counter::SeededState;
+
+        let mut counter = (0..1000).collect::<Counter<_>>();
+        counter.retain(|&item, _| item < 10);
+        assert_eq!(counter.clone().capacity(), counter.capacity());
+
+        let trimmed = counter.clone_with_capacity(0);
+        assert_eq!(trimmed, counter);
+        assert!(trimmed.capacity() < counter.capacity());
+
+        let seeded: Counter<_, _, SeededState> = counter.clone_into_hasher();
+        assert_eq!(seeded.len(), 10);
+        assert!(seeded.iter().all(|(item, count)| counter[item] == *count));
+        let back = seeded.into_hasher::<std::collections::hash_map::RandomState>();
+        assert_eq!(back, counter);
+    }
+
+    #[cfg(feature = "num")]
+    #[test]
+    fn test_most_common_by_partial_ord() {
+        let mut weights = Counter::<&str, f64>::new();
+        for (item, weight) in [
+            ("a", 0.5),
+            ("b", f64::NAN),
+            ("c", 2.5),
+            ("d", -1.0),
+            ("e", 0.75),
+        ] {
+            weights[&item] = weight;
+        }
+        let ranked = weights.most_common_by_partial_ord();
+        let items = ranked.iter().map(|&(item, _)| item).collect::<Vec<_>>();
+        assert_eq!(items, ["c", "e", "a", "d", "b"]);
+        assert!(ranked[4].1.is_nan());
+
+        assert_eq!(
+            weights.k_most_common_by_partial_ord(2),
+            [("c", 2.5), ("e", 0.75)]
+        );
+        assert_eq!(weights.k_most_common_by_partial_ord(4).len(), 4);
+        assert!(weights.k_most_common_by_partial_ord(10)[4].1.is_nan());
+    }
+
+    #[test]
+    fn test_outliers() {
+        let counter: Counter<_> = Counter::from([
+            ('a', 2),
+            ('b', 4),
+            ('c', 4),
+            ('d', 4),
+            ('e', 5),
+            ('f', 5),
+            ('g', 7),
+            ('h', 9),
+        ]);
+        // Mean 5, standard deviation 2.
+        let outliers = counter.zscore_outliers(1.4);
+        assert_eq!(outliers.len(), 2);
+        assert_eq!(outliers[0].0, 'h');
+        assert!((outliers[0].1 - 2.0).abs() < 1e-12);
+        assert_eq!(outliers[1].0, 'a');
+        assert!((outliers[1].1 + 1.5).abs() < 1e-12);
+        assert!(counter.zscore_outliers(2.5).is_empty());
+
+        // Median 4.5, MAD 0.5: 'a' and 'g' are 2.5 away, 'h' is 4.5 away.
+        let outliers = counter.mad_outliers(3.5);
+        assert_eq!(outliers.len(), 1);
+        assert!((outliers[0].1 - 4.5 / (1.4826 * 0.5)).abs() < 1e-12);
+        let outliers = counter.mad_outliers(3.0);
+        assert_eq!(outliers.len(), 3);
+        assert_eq!(outliers.iter().filter(|(_, score)| *score < 0.0).count(), 1);
+
+        // With a MAD of zero, the mean absolute deviation stands in.
+        let spiked: Counter<_> = Counter::from([('a', 1), ('b', 1), ('c', 1), ('d', 1), ('e', 41)]);
+        assert_eq!(spiked.mad_outliers(3.5).len(), 1);
+        let flat: Counter<_> = Counter::from([('a', 3), ('b', 3)]);
+        assert!(flat.mad_outliers(0.0).is_empty());
+        assert!(flat.zscore_outliers(0.0).is_empty());
+        assert!(Counter::<char>::new().mad_outliers(0.0).is_empty());
+    }
+
+    #[test]
+    fn test_count_by() {
+        struct Line {
+            severity: &'static str,
+        }
+        let lines = ["INFO", "WARN", "INFO", "ERROR", "INFO"].map(|severity| Line { severity });
+
+        let mut counter = Counter::<_>::count_by(&lines, |line| line.severity);
+        assert_eq!(
+            counter,
+            Counter::from([("INFO", 3), ("WARN", 1), ("ERROR", 1)])
+        );
+
+        counter.update_by(0..4, |n| if n % 2 == 0 { "EVEN" } else { "ODD" });
+        assert_eq!(counter[&"EVEN"], 2);
+        assert_eq!(counter[&"ODD"], 2);
+        assert_eq!(counter.total::<usize>(), 9);
+    }
+
+    #[test]
+    fn test_from_delimited() {
+        use counter::{DelimitedError, Delimiter};
+
+        // `wc -l` output, with a total line whose item repeats.
+        let wc = "  10 src/a.rs\n   5 src/b.rs\n   2 src/a.rs\n";
+        let counter =
+            Counter::<String, u32>::from_delimited(wc.as_bytes(), Delimiter::Whitespace, 1, 0)
+                .unwrap();
+        assert_eq!(counter[&"src/a.rs".to_string()], 12);
+        assert_eq!(counter[&"src/b.rs".to_string()], 5);
+
+        let tsv = "extra\tred apple \t 4\nextra\tpear\t1\n";
+        let counter =
+            Counter::<String>::from_delimited(tsv.as_bytes(), Delimiter::Char('\t'), 1, 2).unwrap();
+        assert_eq!(counter[&"red apple".to_string()], 4);
+
+        let err =
+            Counter::<String>::from_delimited("a,1\nb\n".as_bytes(), Delimiter::Char(','), 0, 1)
+                .unwrap_err();
+        assert!(matches!(err, DelimitedError::Line { line: 2, .. }));
+        assert_eq!(
+            err.to_string(),
+            "invalid line 2: expected at least 2 columns, found 1"
+        );
+        assert!(matches!(
+            counter::Error::from(err),
+            counter::Error::Parse(_)
+        ));
+
+        let err = Counter::<u8>::from_delimited("300 1\n".as_bytes(), Delimiter::Whitespace, 0, 1)
+            .unwrap_err();
+        assert!(matches!(err, DelimitedError::Line { line: 1, .. }));
+    }
+
+    #[test]
+    fn test_ngrams() {
+        let tokens = "a b a b a".split(' ');
+        let counter = Counter::<Vec<_>>::from_ngrams(tokens.clone(), 2);
+        assert_eq!(
+            counter,
+            Counter::from([(vec!["a", "b"], 2), (vec!["b", "a"], 2)])
+        );
+
+        let mut counter = Counter::<[&str; 3]>::from_array_ngrams(tokens.clone());
+        assert_eq!(
+            counter,
+            Counter::from([(["a", "b", "a"], 2), (["b", "a", "b"], 1)])
+        );
+        // N-grams do not span separate calls.
+        counter.update_array_ngrams(["b", "a"]);
+        counter.update_array_ngrams(["b"]);
+        assert_eq!(counter.total::<usize>(), 3);
+
+        let unigrams = Counter::<Vec<_>>::from_ngrams(tokens.clone(), 1);
+        assert_eq!(unigrams[&vec!["a"]], 3);
+        assert!(Counter::<Vec<_>>::from_ngrams(tokens, 6).is_empty());
+
+        // Long enough for the window to move through its buffer several times.
+        let digits = (0..100).map(|i| i * i % 7).collect::<Vec<u32>>();
+        for n in 1..=5 {
+            let expected = digits
+                .windows(n)
+                .map(<[u32]>::to_vec)
+                .collect::<Counter<_>>();
+            let ngrams = Counter::<Vec<_>>::from_ngrams(digits.iter().copied(), n);
+            assert_eq!(ngrams, expected);
+        }
+        let arrays = Counter::<[u32; 3]>::from_array_ngrams(digits.iter().copied());
+        let expected = digits
+            .windows(3)
+            .map(|window| [window[0], window[1], window[2]])
+            .collect::<Counter<_>>();
+        assert_eq!(arrays, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "n-grams must have a length of at least one")]
+    fn test_zero_length_ngrams() {
+        Counter::<Vec<char>>::from_ngrams("abc".chars(), 0);
+    }
+
+    #[test]
+    fn test_from_bytes_and_chars() {
+        let bytes = b"\x00\xffabcabc\xff";
+        assert_eq!(
+            Counter::<u8>::from_bytes(bytes),
+            bytes.iter().copied().collect()
+        );
+
+        let long = "ünïcödé and plain ascii text ".repeat(10);
+        for text in [
+            "",
+            "plain ascii text",
+            "ünïcödé ünïcödé",
+            "日本語のテキスト and ASCII",
+            &long,
+        ] {
+            let expected = text.chars().collect::<Counter<char>>();
+            assert_eq!(Counter::<char>::from_char_frequencies(text), expected);
+        }
+
+        let mut counter = Counter::<char, u64>::from_char_frequencies("aé");
+        counter.update_chars("éé\u{7f}");
+        assert_eq!(counter, Counter::from([('a', 1), ('é', 3), ('\u{7f}', 1)]));
+
+        counter.try_update_chars("aé").unwrap();
+        assert_eq!(counter, Counter::from([('a', 2), ('é', 4), ('\u{7f}', 1)]));
+
+        let mut small = Counter::<char, u8>::new();
+        assert!(matches!(
+            small.try_update_chars(&"a".repeat(256)),
+            Err(counter::Error::Overflow)
+        ));
+        assert!(small.is_empty());
+    }
+
+    #[test]
+    fn test_extend_max_min() {
+        let base = "aaabbc".chars().collect::<Counter<_>>();
+        let other = "abbbd".chars().collect::<Counter<_>>();
+
+        let mut max = base.clone();
+        max.extend_max(other.clone());
+        assert_eq!(max, base.clone() | other.clone());
+
+        let mut min = base.clone();
+        min.extend_min(other.clone());
+        assert_eq!(min, Counter::from([('a', 1), ('b', 2), ('c', 1), ('d', 1)]));
+
+        // Repeated items are merged with each other as well as with the counter.
+        let mut peaks = Counter::<&str, u32>::new();
+        peaks.extend_max([("x", 3), ("x", 9), ("x", 4)]);
+        assert_eq!(peaks[&"x"], 9);
+        peaks.extend_min([("x", 7), ("x", 8)]);
+        assert_eq!(peaks[&"x"], 7);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans() {
+        use std::sync::{Arc, Mutex};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        /// Records the names of the spans created.
+        struct Spans(Arc<Mutex<Vec<&'static str>>>);
+
+        impl Subscriber for Spans {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut names = self.0.lock().unwrap();
+                names.push(span.metadata().name());
+                Id::from_u64(names.len() as u64)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, _: &Event<'_>) {}
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let names = Arc::new(Mutex::new(Vec::new()));
+        tracing::subscriber::with_default(Spans(names.clone()), || {
+            // Small counters aren't traced.
+            let mut counter = "abbccc".chars().collect::<Counter<_>>();
+            counter.most_common();
+            counter += "abc".chars().collect::<Counter<_>>();
+            counter.retain(|_, &mut count| count > 2);
+
+            let mut counter = (0..2000_u16).collect::<Counter<_>>();
+            counter.most_common();
+            counter += (0..10_u16).collect::<Counter<_>>();
+            counter.retain(|_, &mut count| count > 1);
+            counter.retain(|_, _| false);
+        });
+        assert_eq!(
+            *names.lock().unwrap(),
+            ["most_common", "add_assign", "retain"]
+        );
+    }
+
+    #[test]
+    fn test_dense_counter() {
+        use counter::DenseCounter;
+
+        let bytes = b"mississippi";
+        let dense = bytes.iter().copied().collect::<DenseCounter<u8>>();
+        let sparse = bytes.iter().copied().collect::<Counter<u8>>();
+        assert_eq!(dense[b's'], 4);
+        assert_eq!(dense[b'z'], 0);
+        assert_eq!(dense.len(), 4);
+        assert_eq!(dense.total::<usize>(), 11);
+        assert_eq!(dense.most_common_ordered(), sparse.most_common_ordered());
+        assert_eq!(
+            dense.k_most_common_ordered(2),
+            sparse.k_most_common_ordered(2)
+        );
+        assert_eq!(dense.k_most_common_ordered(0), []);
+        assert_eq!(dense.iter().count(), 4);
+
+        assert_eq!(Counter::<u8>::from(dense.clone()), sparse);
+        assert_eq!(DenseCounter::from(sparse), dense);
+
+        let other = b"sip".iter().copied().collect::<DenseCounter<u8>>();
+        let expected = |text: &[u8]| text.iter().copied().collect::<DenseCounter<u8>>();
+        assert_eq!(dense.clone() + other.clone(), expected(b"mississippisip"));
+        assert_eq!(dense.clone() - other.clone(), expected(b"missisip"));
+        assert_eq!(dense.clone() | other.clone(), dense);
+        assert_eq!(dense.clone() & other.clone(), other);
+        assert_eq!(
+            other.clone() - dense.clone(),
+            DenseCounter::<u8>::with_domain(256)
+        );
+        assert!((other.clone() - dense.clone()).is_empty());
+
+        let mut indexed = DenseCounter::<u8, i32>::new();
+        indexed[b'z'] -= 2;
+        indexed.add_count(b'a', 5);
+        assert_eq!(indexed.counts().len(), usize::from(b'z') + 1);
+        assert_eq!(
+            Counter::<u8, i32>::from(indexed),
+            Counter::from([(b'a', 5), (b'z', -2)])
+        );
+    }
+
+    #[test]
+    fn test_prune_tail_fraction() {
+        let counts = [
+            ('a', 50),
+            ('b', 20),
+            ('c', 10),
+            ('d', 10),
+            ('e', 5),
+            ('f', 5),
+        ];
+
+        let mut counter = Counter::<_>::from(counts);
+        assert_eq!(counter.prune_tail_fraction(0.0), 0);
+        assert_eq!(counter.len(), 6);
+
+        // 'e' and 'f' are tied, so neither fits a budget of 8.
+        let mut counter = Counter::<_>::from(counts);
+        assert_eq!(counter.prune_tail_fraction(0.08), 0);
+        assert_eq!(counter.len(), 6);
+
+        let mut counter = Counter::<_>::from(counts);
+        assert_eq!(counter.prune_tail_fraction(0.25), 10);
+        assert_eq!(
+            counter,
+            Counter::from([('a', 50), ('b', 20), ('c', 10), ('d', 10)])
+        );
+
+        let mut counter = Counter::<_>::from(counts);
+        assert_eq!(counter.prune_tail_fraction(1.0), 100);
+        assert!(counter.is_empty());
+
+        let mut empty = Counter::<char>::new();
+        assert_eq!(empty.prune_tail_fraction(0.5), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "pruned fraction must be between 0 and 1")]
+    fn test_prune_tail_fraction_invalid() {
+        Counter::<char>::new().prune_tail_fraction(-0.1);
+    }
+
+    #[test]
+    fn test_with_hasher_and_capacity() {
+        use counter::SeededState;
+
+        let mut counter = Counter::<char, u32, _>::with_hasher(SeededState::new(7));
+        counter.update("hello".chars());
+        assert_eq!(counter[&'l'], 2);
+        assert_eq!(counter.hasher(), &SeededState::new(7));
+
+        let mut counter =
+            Counter::<u32, usize, _>::with_capacity_and_hasher(8, SeededState::new(7));
+        assert!(counter.capacity() >= 8);
+        counter.update(0..4);
+        counter.reserve(100);
+        assert!(counter.capacity() >= 104);
+        counter.shrink_to_fit();
+        assert!(counter.capacity() < 104);
+        assert_eq!(counter.len(), 4);
+    }
+
+    #[cfg(all(feature = "ahash", feature = "rustc-hash"))]
+    #[test]
+    fn test_fast_hash_counters() {
+        use counter::{AHashCounter, FxCounter};
+
+        let fx = "abbccc".chars().collect::<FxCounter<_>>();
+        let ahash = [('a', 1), ('b', 2), ('c', 3)]
+            .into_iter()
+            .collect::<AHashCounter<_>>();
+        assert_eq!(
+            fx.into_hasher::<std::collections::hash_map::RandomState>(),
+            ahash.into_hasher()
+        );
+
+        let mut fx = FxCounter::<u32, u8>::with_fx_hasher();
+        fx.update([7, 7]);
+        let mut ahash = AHashCounter::<u32, u8>::with_ahasher();
+        ahash.update([7, 7]);
+        assert_eq!(fx[&7], ahash[&7]);
+    }
+
+    #[test]
+    fn test_normalizing_counter() {
+        use counter::NormalizingCounter;
+
+        let mut counter = NormalizingCounter::new(|c: char| c.to_ascii_lowercase());
+        counter.update("AbBa".chars());
+        counter.extend([('C', 2)]);
+        counter.extend("c".chars());
+        counter += Counter::from([('A', 1), ('d', 1)]);
+        counter['D'] += 1;
+        assert_eq!(
+            counter.clone().into_inner(),
+            Counter::from([('a', 3), ('b', 2), ('c', 3), ('d', 2)])
+        );
+
+        counter.subtract("AC".chars());
+        counter -= Counter::from([('B', 1), ('b', 1), ('D', 5)]);
+        assert_eq!(counter.into_inner(), Counter::from([('a', 2), ('c', 2)]));
+
+        let mut counter =
+            Counter::from([('X', 2), ('x', 1)]).with_normalizer(|c: char| c.to_ascii_lowercase());
+        assert_eq!(counter['X'], 3);
+        assert_eq!(*counter.get('x'), 3);
+        assert_eq!(counter.normalize('Q'), 'q');
+        assert_eq!(counter.remove('X'), Some(3));
+        assert!(counter.is_empty());
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_k_most_common_smallvec() {
+        let counter = "the quick brown fox jumps over the lazy dog"
+            .chars()
+            .collect::<Counter<_>>();
+        assert_eq!(
+            counter.k_most_common_smallvec::<1>().as_slice(),
+            &counter.k_most_common_ordered(1)[..]
+        );
+        // The fourth place is a tie among several letters seen twice.
+        assert_eq!(
+            counter.k_most_common_smallvec::<4>().as_slice(),
+            &counter.k_most_common_ordered(4)[..]
+        );
+        assert!(counter.k_most_common_smallvec::<0>().is_empty());
+        let all = counter.k_most_common_smallvec::<64>();
+        assert_eq!(all.as_slice(), &counter.most_common_ordered()[..]);
+    }
+
+    #[test]
+    fn test_subscribed_counter() {
+        use counter::Subscriber;
+        use std::collections::{BTreeMap, BTreeSet};
+
+        /// Indexes the items by their counts.
+        #[derive(Default)]
+        struct ByCount(BTreeMap<usize, BTreeSet<char>>);
+
+        impl Subscriber<char, usize> for ByCount {
+            fn on_count_changed(&mut self, &item: &char, old: Option<&usize>, new: Option<&usize>) {
+                if let Some(old) = old {
+                    let items = self.0.get_mut(old).unwrap();
+                    assert!(items.remove(&item));
+                    if items.is_empty() {
+                        self.0.remove(old);
+                    }
+                }
+                if let Some(&new) = new {
+                    self.0.entry(new).or_default().insert(item);
+                }
+            }
+        }
+
+        fn rebuilt(counter: &Counter<char>) -> BTreeMap<usize, BTreeSet<char>> {
+            let mut index = ByCount::default();
+            for (item, count) in counter.iter() {
+                index.on_count_changed(item, None, Some(count));
+            }
+            index.0
+        }
+
+        let mut counter = Counter::new().with_subscriber(ByCount::default());
+        counter.update("abracadabra".chars());
+        assert_eq!(counter.subscriber().0, rebuilt(&counter));
+
+        counter.subtract("aaaaaad".chars());
+        counter += "rxy".chars().collect::<Counter<_>>();
+        counter -= "ry".chars().collect::<Counter<_>>();
+        assert_eq!(counter.set('b', 7), Some(2));
+        assert_eq!(counter.set('z', 1), None);
+        assert_eq!(counter.remove(&'c'), Some(1));
+        assert_eq!(counter.remove(&'q'), None);
+        assert_eq!(counter.subscriber().0, rebuilt(&counter));
+        assert_eq!(
+            *counter,
+            Counter::from([('b', 7), ('r', 2), ('x', 1), ('z', 1)])
+        );
+
+        counter.extend("bq".chars());
+        counter.extend([('q', 2), ('z', 1)]);
+        let mut counter =
+            counter + "q".chars().collect::<Counter<_>>() - "qqqq".chars().collect::<Counter<_>>();
+        assert_eq!(counter.subscriber().0, rebuilt(&counter));
+        assert_eq!(
+            *counter,
+            Counter::from([('b', 8), ('r', 2), ('x', 1), ('z', 2)])
+        );
+
+        counter.retain(|_, &count| count > 1);
+        assert_eq!(counter.subscriber().0, rebuilt(&counter));
+        counter.clear();
+        let (counter, index) = counter.into_parts();
+        assert!(counter.is_empty());
+        assert!(index.0.is_empty());
+    }
+
+    #[cfg(feature = "aho-corasick")]
+    #[test]
+    fn test_multi_pattern_counter() {
+        use counter::text::MultiPatternCounter;
+        use std::io::{self, Read};
+
+        /// Hands out one byte per read, so that every match spans several reads.
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                match self.0.split_first() {
+                    Some((&byte, rest)) if !buf.is_empty() => {
+                        buf[0] = byte;
+                        self.0 = rest;
+                        Ok(1)
+                    }
+                    _ => Ok(0),
+                }
+            }
+        }
+
+        let counter = MultiPatternCounter::new(["she", "he", "hers", "his"]).unwrap();
+        assert_eq!(counter.patterns(), ["she", "he", "hers", "his"]);
+
+        // "hers" is never counted: the "he" within it ends first.
+        let text = "she said his hers were hers, he said";
+        let expected = [("she", 1), ("he", 3), ("his", 1)]
+            .map(|(pattern, count)| (pattern.to_string(), count))
+            .into_iter()
+            .collect::<Counter<String>>();
+        assert_eq!(counter.count(text), expected);
+        assert_eq!(counter.count(text.as_bytes()), expected);
+        assert_eq!(
+            counter.count_reader(Trickle(text.as_bytes())).unwrap(),
+            expected
+        );
+        assert_eq!(counter.count_all(text.split(' ')), expected);
+        assert_eq!(counter.count_all(["s", "he"]).get("she"), None);
+        assert!(counter.count("").is_empty());
+
+        let repeated = MultiPatternCounter::new(["ab", "ab"]).unwrap();
+        assert_eq!(repeated.count("abab").into_map().len(), 1);
+        assert_eq!(repeated.count("abab").get("ab"), Some(&2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_sorted_serialization() {
+        use counter::OrderBy;
+
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Golden {
+            #[serde(with = "counter::serde::sorted_map")]
+            words: Counter<String>,
+            #[serde(with = "counter::serde::sorted_pairs")]
+            bigrams: Counter<(char, char), u8>,
+        }
+
+        let words = "the cat and the hat and the bat"
+            .split(' ')
+            .map(String::from)
+            .collect::<Counter<_>>();
+        let bigrams = Counter::<(char, char), u8>::from([(('h', 'i'), 2), (('a', 'b'), 1)]);
+        let golden = Golden { words, bigrams };
+        let json = serde_json::to_string(&golden).unwrap();
+        assert_eq!(
+            json,
+            r#"{"words":{"and":2,"bat":1,"cat":1,"hat":1,"the":3},"bigrams":[[["a","b"],1],[["h","i"],2]]}"#
+        );
+        assert_eq!(serde_json::from_str::<Golden>(&json).unwrap(), golden);
+
+        // Counters equal as multisets serialize identically, whatever order they were built in.
+        let reversed = "bat the and hat the and cat the"
+            .split(' ')
+            .map(String::from)
+            .collect::<Counter<_>>();
+        let by_count = |counter: &Counter<String>| {
+            serde_json::to_string(&counter.as_sorted_serialize(OrderBy::CountDesc)).unwrap()
+        };
+        assert_eq!(by_count(&reversed), by_count(&golden.words));
+        let rarest = serde_json::to_string(&golden.words.as_sorted_serialize(OrderBy::CountAsc));
+        assert_eq!(
+            rarest.unwrap(),
+            r#"{"bat":1,"cat":1,"hat":1,"and":2,"the":3}"#
+        );
+
+        let reverse_alphabetical = |a: (&String, &usize), b: (&String, &usize)| b.0.cmp(a.0);
+        let custom = golden
+            .words
+            .as_sorted_serialize(OrderBy::Custom(&reverse_alphabetical));
+        assert_eq!(
+            serde_json::to_string(&custom).unwrap(),
+            r#"{"the":3,"hat":1,"cat":1,"bat":1,"and":2}"#
+        );
+        let weights = Counter::<_, f64>::from([("b", 0.5), ("a", 1.5)]);
+        assert_eq!(
+            serde_json::to_string(&weights.as_key_sorted_serialize()).unwrap(),
+            r#"{"a":1.5,"b":0.5}"#
+        );
+    }
+
+    #[cfg(feature = "borsh")]
+    #[test]
+    fn test_borsh_round_trip() {
+        let counter = [3u32, 1, 2, 3, 3].into_iter().collect::<Counter<_>>();
+        let bytes = borsh::to_vec(&counter).unwrap();
+        // The length, then the pairs in increasing order of the items.
+        let expected = [
+            3u32.to_le_bytes().as_slice(),
+            &1u32.to_le_bytes(),
+            &1u64.to_le_bytes(),
+        ]
+        .concat();
+        assert_eq!(bytes[..16], expected);
+        assert_eq!(bytes.len(), 4 + 3 * 12);
+        assert_eq!(borsh::from_slice::<Counter<u32>>(&bytes).unwrap(), counter);
+
+        // The bytes depend only on the counts, not on how the counter was built.
+        let rebuilt = Counter::<u32>::from([(2, 1), (3, 3), (1, 1)]);
+        assert_eq!(borsh::to_vec(&rebuilt).unwrap(), bytes);
+        assert_eq!(borsh::to_vec(&Counter::<u32>::new()).unwrap(), [0; 4]);
+
+        let out_of_order = borsh::to_vec(&vec![(2u32, 1u64), (1, 1)]).unwrap();
+        let err = borsh::from_slice::<Counter<u32>>(&out_of_order).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let repeated = borsh::to_vec(&vec![(1u32, 1u64), (1, 1)]).unwrap();
+        assert!(borsh::from_slice::<Counter<u32>>(&repeated).is_err());
+        assert!(borsh::from_slice::<Counter<u32>>(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn test_count_iterator_ext() {
+        use counter::CountIteratorExt;
+
+        let counter = "abbcccdddd".chars().collect::<Counter<_, u8>>();
+
+        let mut copied = counter.iter().counts_copied().collect::<Vec<_>>();
+        copied.sort_unstable();
+        assert_eq!(copied, [(&'a', 1), (&'b', 2), (&'c', 3), (&'d', 4)]);
+        assert_eq!(counter.iter().counts_copied().len(), 4);
+
+        let mut frequent = (&counter).into_iter().min_count(3).collect::<Vec<_>>();
+        frequent.sort_unstable();
+        assert_eq!(frequent, [(&'c', &3), (&'d', &4)]);
+        assert_eq!(counter.iter().min_count(5).next(), None);
+
+        let ordered = counter.iter().by_count_desc().collect::<Vec<_>>();
+        let expected = counter.most_common_ordered();
+        assert!(ordered
+            .iter()
+            .map(|(&item, &count)| (item, count))
+            .eq(expected));
+        let mut top = counter.iter().by_count_desc();
+        assert_eq!(top.len(), 4);
+        assert_eq!(top.next(), Some((&'d', &4)));
+        assert_eq!(top.len(), 3);
+
+        // Ties are broken by the items, whatever order the pairs arrive in.
+        let ties = Counter::<char, u8>::from([('z', 2), ('x', 2), ('y', 2), ('w', 1)]);
+        let items = ties.iter().by_count_desc().map(|(&item, _)| item);
+        assert_eq!(items.collect::<String>(), "xyzw");
+
+        let signed = Counter::<char, i32>::from([('a', 3), ('b', -1), ('c', 1)]);
+        let positive = signed.positive_iter().by_count_desc().counts_copied();
+        assert!(positive.eq([(&'a', 3), (&'c', 1)]));
+    }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn test_proptest_strategies() {
+        use proptest::prelude::*;
+        use proptest::test_runner::TestRunner;
+
+        TestRunner::deterministic()
+            .run(&any::<Counter<u8, i8>>(), |counter| {
+                prop_assert!(counter.values().all(|&count| count > 0));
+                Ok(())
+            })
+            .unwrap();
+
+        let params = ((1..=3).into(), (), ());
+        TestRunner::deterministic()
+            .run(&any_with::<Counter<bool, u8>>(params), |counter| {
+                prop_assert!(counter.len() <= 2);
+                Ok(())
+            })
+            .unwrap();
+
+        // Non-positive counts are redrawn rather than dropped, so the size range is kept.
+        let params = (5.into(), (), ());
+        TestRunner::deterministic()
+            .run(&any_with::<Counter<u16, i8>>(params), |counter| {
+                prop_assert_eq!(counter.len(), 5);
+                prop_assert!(counter.values().all(|&count| count > 0));
+                Ok(())
+            })
+            .unwrap();
+
+        let strategy = counter::proptest::counter(0..50u16, -1..=0i64, 5..10);
+        let saw_negative = std::cell::Cell::new(false);
+        TestRunner::deterministic()
+            .run(&strategy, |counter: Counter<u16, i64>| {
+                prop_assert!((5..10).contains(&counter.len()));
+                prop_assert!(counter.values().all(|&count| count == 0 || count == -1));
+                saw_negative.set(saw_negative.get() || counter.values().any(|&count| count < 0));
+                Ok(())
+            })
+            .unwrap();
+        assert!(saw_negative.get());
+    }
+
+    #[test]
+    fn test_try_update_and_extend() {
+        use counter::{Error, OverflowError};
+
+        let mut counter = Counter::<&str, u8>::from([("spam", 254), ("eggs", 255)]);
+        counter.try_update(["spam"]).unwrap();
+        assert_eq!(counter[&"spam"], 255);
+        let err = counter.try_update(["ham", "spam", "toast"]).unwrap_err();
+        assert_eq!(
+            err,
+            OverflowError {
+                item: "spam",
+                added: 1
+            }
+        );
+        assert_eq!(counter[&"spam"], 255);
+        assert_eq!(counter[&"ham"], 1);
+        assert_eq!(counter.get(&"toast"), None);
+        assert!(matches!(Error::from(err), Error::Overflow));
+
+        let err = counter.try_extend([("eggs", 1)]).unwrap_err();
+        assert_eq!(err.item, "eggs");
+        assert_eq!(counter[&"eggs"], 255);
+
+        let mut signed = Counter::<char, i8>::new();
+        signed
+            .try_extend([('a', i8::MAX), ('a', -1), ('b', i8::MIN)])
+            .unwrap();
+        assert_eq!(signed[&'a'], i8::MAX - 1);
+        let err = signed.try_extend([('b', -1)]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "adding -1 to the count of item 'b' would overflow"
+        );
+
+        // Items without `Debug` can still be counted; only the error needs it to display.
+        #[derive(PartialEq, Eq, Hash)]
+        struct Key;
+        let mut keys = Counter::<Key, u8>::new();
+        assert!(keys.try_update([Key, Key]).is_ok());
+        assert_eq!(keys[&Key], 2);
+    }
+
+    #[cfg(feature = "num")]
+    #[test]
+    fn test_bigint_counts() {
+        use num_bigint::{BigInt, BigUint};
+
+        let mut counter = Counter::<char, BigUint>::new();
+        counter.update("abracadabra".chars());
+        // Counts past `u128::MAX` neither overflow nor lose precision.
+        let huge = BigUint::from(u128::MAX) * 3u32;
+        counter[&'z'] = huge.clone();
+        assert_eq!(counter.total::<BigUint>(), &huge + 11u32);
+        assert_eq!(
+            counter.most_common()[..2],
+            [('z', huge.clone()), ('a', BigUint::from(5u32))]
+        );
+        assert_eq!(
+            counter.k_most_common_ordered(2),
+            [('z', huge.clone()), ('a', BigUint::from(5u32))]
+        );
+
+        let other = "abc".chars().collect::<Counter<_, BigUint>>();
+        assert_eq!((counter.clone() + other.clone())[&'c'], BigUint::from(2u32));
+        assert_eq!((counter.clone() - other.clone())[&'a'], BigUint::from(4u32));
+        assert_eq!((counter.clone() - other.clone()).get(&'c'), None);
+        assert_eq!((counter.clone() & other.clone())[&'a'], BigUint::from(1u32));
+        assert_eq!((counter.clone() | other.clone())[&'z'], huge);
+        counter.subtract("zz".chars());
+        assert_eq!(counter[&'z'], &huge - 2u32);
+
+        let mut signed = "aab".chars().collect::<Counter<_, BigInt>>();
+        signed[&'z'] = -BigInt::from(u128::MAX);
+        assert_eq!(signed.total::<BigInt>(), 3 - BigInt::from(u128::MAX));
+        let ranked = signed.most_common_ordered();
+        assert_eq!(ranked[0], ('a', BigInt::from(2)));
+        assert_eq!(ranked[2].0, 'z');
+
+        let other = "abbb".chars().collect::<Counter<_, BigInt>>();
+        let diff = signed.clone().signed_sub(other.clone());
+        assert_eq!(diff[&'a'], BigInt::from(1));
+        assert_eq!(diff[&'b'], BigInt::from(-2));
+        assert_eq!((signed.clone() + other.clone())[&'b'], BigInt::from(4));
+        assert_eq!((signed & other)[&'a'], BigInt::from(1));
+    }
+
+    #[cfg(feature = "spill")]
+    #[test]
+    #[should_panic(expected = "must hold at least 32 items in memory")]
+    fn test_spilling_counter_too_small() {
+        let _ = counter::spill::SpillingCounter::<u32>::new(std::env::temp_dir(), 1);
+    }
 }